@@ -2,7 +2,7 @@
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use cubing::algorithm::kociemba;
-use cubing::core::search;
+use cubing::core::search::{self, Solved};
 use once_cell::sync::Lazy;
 
 static TABLES: Lazy<kociemba::Tables> = Lazy::new(|| kociemba::generate_tables());
@@ -54,20 +54,27 @@ pub fn superflip(c: &mut Criterion) {
 
     let position = kociemba::Phase1::from(&superflip);
     group.bench_function("phase_1/1_solution", |b| {
-        b.iter(|| search::ida_iter(black_box(position), &TABLES.1, &TABLES.0, None).next())
+        b.iter(|| search::ida_iter(black_box(position), &Solved, &TABLES.1, &TABLES.0, None).next())
     });
 
     group.bench_function("phase_1/10_solutions", |b| {
-        b.iter(|| search::ida_iter(black_box(position), &TABLES.1, &TABLES.0, None).advance_by(10))
+        b.iter(|| {
+            search::ida_iter(black_box(position), &Solved, &TABLES.1, &TABLES.0, None)
+                .advance_by(10)
+        })
     });
 
     group.bench_function("phase_1/100_solutions", |b| {
-        b.iter(|| search::ida_iter(black_box(position), &TABLES.1, &TABLES.0, None).advance_by(100))
+        b.iter(|| {
+            search::ida_iter(black_box(position), &Solved, &TABLES.1, &TABLES.0, None)
+                .advance_by(100)
+        })
     });
 
     group.bench_function("phase_1/1000_solutions", |b| {
         b.iter(|| {
-            search::ida_iter(black_box(position), &TABLES.1, &TABLES.0, None).advance_by(1000)
+            search::ida_iter(black_box(position), &Solved, &TABLES.1, &TABLES.0, None)
+                .advance_by(1000)
         })
     });
 
@@ -93,7 +93,7 @@ fn array8x3_transitions(c: &mut Criterion) {
     let mut position = Array::default();
     for ix in 0..GENS.len() {
         group.bench_function(format!("without_table/mutable/gen_{:?}", ix), |b| {
-            b.iter(|| position.permute_inplace(&mut GENS[ix].clone()))
+            b.iter(|| position.permute_inplace(&mut { GENS[ix] }))
         });
     }
 
@@ -0,0 +1,272 @@
+//! A rough model of how fast a sequence can be executed by hand, for ranking algorithms which
+//! reach the same state. The right hand holds R and the left hand L, each wrist being able to
+//! turn up to a half turn either way from its home position before it has to regrip. U turns are
+//! flicks by whichever index finger is free, which is awkward unless its wrist is at home or has
+//! made a quarter turn which brings the finger up to U, as R does for U and L' for U'.
+
+use crate::metric::htm::Face;
+use crate::metric::Htm;
+use crate::notation::{Alg, Move, Slice, Turn};
+use std::cmp::Ordering;
+
+/// The costs of the model, which can be adjusted to suit a particular style of turning.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Ergonomics {
+    /// The cost of a quarter turn of each face, in the order U, R, F, L, D, B.
+    pub faces: [f32; 6],
+    /// The cost of a quarter turn of each of M, E and S.
+    pub slices: [f32; 3],
+    /// The factor for turning a face together with its slice, compared to the face alone.
+    pub wide: f32,
+    /// The factor for a half turn, compared to a quarter turn.
+    pub double: f32,
+    /// The factor for a U turn which neither index finger is in place to flick.
+    pub awkward: f32,
+    /// The cost of moving a hand back to its home grip.
+    pub regrip: f32,
+    /// The cost of a rotation of the whole cube, after which both hands are at home.
+    pub rotation: f32,
+}
+
+impl Default for Ergonomics {
+    fn default() -> Self {
+        Self {
+            faces: [0.8, 0.9, 1.4, 1.2, 1.4, 2.2],
+            slices: [1.1, 2.0, 2.2],
+            wide: 1.1,
+            double: 1.5,
+            awkward: 1.4,
+            regrip: 1.5,
+            rotation: 2.0,
+        }
+    }
+}
+
+/// The positions of the two wrists, in quarter turns away from home in the direction of R and L
+/// respectively.
+#[derive(Clone, Copy, Debug, Default)]
+struct Grip {
+    right: i8,
+    left: i8,
+}
+
+impl Ergonomics {
+    pub fn score(&self, moves: &[Move]) -> f32 {
+        let mut grip = Grip::default();
+
+        moves
+            .iter()
+            .map(|&turn| self.execute(turn, &mut grip))
+            .sum()
+    }
+
+    pub fn score_alg(&self, alg: &Alg) -> f32 {
+        self.score(alg.moves())
+    }
+
+    pub fn score_htm(&self, turns: &[Htm]) -> f32 {
+        self.score(
+            &turns
+                .iter()
+                .map(|&turn| Move::from(turn))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    /// Sort the algorithms from the fastest to the slowest.
+    pub fn sort(&self, algs: &mut [Alg]) {
+        algs.sort_by(|a, b| {
+            let (a, b) = (self.score_alg(a), self.score_alg(b));
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        });
+    }
+
+    /// Sort sequences of face turns, such as solutions from the searches, from the fastest to
+    /// the slowest.
+    pub fn sort_htm(&self, sequences: &mut [Vec<Htm>]) {
+        sequences.sort_by(|a, b| {
+            let (a, b) = (self.score_htm(a), self.score_htm(b));
+            a.partial_cmp(&b).unwrap_or(Ordering::Equal)
+        });
+    }
+
+    /// The cost of `turn`, moving the wrists as needed.
+    fn execute(&self, Move { turn, amount }: Move, grip: &mut Grip) -> f32 {
+        let factor = if amount == 2 { self.double } else { 1.0 };
+
+        match turn {
+            Turn::Face(face @ (Face::R | Face::L)) => {
+                self.faces[face as usize] * factor + self.wrist(face, amount, grip)
+            }
+            Turn::Wide(face @ (Face::R | Face::L)) => {
+                self.faces[face as usize] * self.wide * factor + self.wrist(face, amount, grip)
+            }
+            Turn::Face(Face::U) => self.faces[Face::U as usize] * factor * self.flick(amount, grip),
+            Turn::Wide(Face::U) => {
+                self.faces[Face::U as usize] * self.wide * factor * self.flick(amount, grip)
+            }
+            Turn::Face(face) => self.faces[face as usize] * factor,
+            Turn::Wide(face) => self.faces[face as usize] * self.wide * factor,
+            Turn::Slice(slice) => {
+                let ix = match slice {
+                    Slice::M => 0,
+                    Slice::E => 1,
+                    Slice::S => 2,
+                };
+                self.slices[ix] * factor
+            }
+            Turn::Rotation(_) => {
+                *grip = Grip::default();
+                self.rotation
+            }
+        }
+    }
+
+    /// The cost of regripping before turning the wrist holding `face`, which must be R or L.
+    fn wrist(&self, face: Face, amount: u8, grip: &mut Grip) -> f32 {
+        let wrist = match face {
+            Face::R => &mut grip.right,
+            _ => &mut grip.left,
+        };
+        let change = match amount {
+            1 => 1,
+            2 if *wrist <= 0 => 2,
+            2 => -2,
+            _ => -1,
+        };
+
+        if (-2..=2).contains(&(*wrist + change)) {
+            *wrist += change;
+            0.0
+        } else {
+            // Start from the other side, so that the turn ends close to home
+            *wrist = change - change.signum();
+            self.regrip
+        }
+    }
+
+    /// The factor for flicking U, which is done by the right index finger for U and the left for
+    /// U', and by either for U2, if the wrist of that hand is at home or a quarter turn up from it,
+    /// after R or L' respectively.
+    fn flick(&self, amount: u8, grip: &Grip) -> f32 {
+        let right = matches!(grip.right, 0 | 1);
+        let left = matches!(grip.left, 0 | -1);
+        let free = match amount {
+            1 => right,
+            3 => left,
+            _ => right || left,
+        };
+
+        if free {
+            1.0
+        } else {
+            self.awkward
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(source: &str) -> f32 {
+        Ergonomics::default().score_alg(&source.parse().unwrap())
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!(
+            (actual - expected).abs() < 1e-4,
+            "{} != {}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    pub fn ranking() {
+        // The same algorithm, performed on the right or at the back
+        assert!(score("R U R' U R U2 R'") < score("B U B' U B U2 B'"));
+
+        // A third turn of R in the same direction needs a regrip
+        let ergonomics = Ergonomics::default();
+        assert_eq!(score("R R"), 2.0 * ergonomics.faces[Face::R as usize]);
+        assert_eq!(
+            score("R R R"),
+            3.0 * ergonomics.faces[Face::R as usize] + ergonomics.regrip
+        );
+
+        // U' is awkward once the left wrist has turned
+        assert!(score("L U'") > score("L U"));
+
+        let free_rotations = Ergonomics {
+            rotation: 0.0,
+            ..Ergonomics::default()
+        };
+        assert_eq!(free_rotations.score_alg(&"x y".parse().unwrap()), 0.0);
+
+        let mut algs: Vec<Alg> = ["F R U R' U' F'", "R U R' U'", "B U B' U'"]
+            .iter()
+            .map(|source| source.parse().unwrap())
+            .collect();
+        ergonomics.sort(&mut algs);
+        assert_eq!(format!("{}", algs[0]), "R U R' U'");
+        assert_eq!(format!("{}", algs[2]), "F R U R' U' F'");
+    }
+
+    #[test]
+    pub fn grips() {
+        let ergonomics = Ergonomics::default();
+        let [u, r, _, l, ..] = ergonomics.faces;
+
+        // Each wrist turns up to a half turn either way, and regrips to start from the other side
+        assert_close(score("R2 R2 R2"), 3.0 * r * ergonomics.double);
+        assert_close(score("R' R' R'"), 3.0 * r + ergonomics.regrip);
+        assert_close(score("R R L R"), 3.0 * r + l + ergonomics.regrip);
+        assert_close(score("R R R R"), 4.0 * r + ergonomics.regrip);
+        assert_close(
+            score("Rw Rw R"),
+            2.0 * r * ergonomics.wide + r + ergonomics.regrip,
+        );
+
+        // A rotation brings both hands home
+        assert_close(score("R R x R"), 3.0 * r + ergonomics.rotation);
+
+        // U is flicked by the right index finger, U' by the left and U2 by either
+        assert_close(score("U"), u);
+        assert_close(score("R U'"), r + u);
+        assert_close(score("R U2"), r + u * ergonomics.double);
+        assert_close(score("R R' U"), 2.0 * r + u);
+
+        // Which is also in place after R for U and after L' for U', but not after the inverses
+        assert_close(score("R U"), r + u);
+        assert_close(score("R' U"), r + u * ergonomics.awkward);
+        assert_close(score("L' U'"), l + u);
+        assert_close(score("L U'"), l + u * ergonomics.awkward);
+        assert_close(score("R L' U2"), r + l + u * ergonomics.double);
+        assert_close(
+            score("R2 L2 U2"),
+            (r + l + u * ergonomics.awkward) * ergonomics.double,
+        );
+    }
+
+    #[test]
+    pub fn sequences() {
+        use Htm::*;
+
+        let ergonomics = Ergonomics::default();
+        assert_eq!(ergonomics.score_htm(&[R1, U1, R3, U3]), score("R U R' U'"));
+        assert_eq!(ergonomics.score_htm(&[]), 0.0);
+
+        // The sort is stable, so sequences of equal cost keep their order
+        let mut sequences = vec![vec![F1, R1], vec![R1, U1, R3, U3], vec![R1, F1], vec![B1]];
+        assert_eq!(
+            ergonomics.score_htm(&sequences[0]),
+            ergonomics.score_htm(&sequences[2])
+        );
+        ergonomics.sort_htm(&mut sequences);
+        assert_eq!(
+            sequences,
+            vec![vec![B1], vec![F1, R1], vec![R1, F1], vec![R1, U1, R3, U3]]
+        );
+    }
+}
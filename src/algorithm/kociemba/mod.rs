@@ -1,9 +1,10 @@
 pub mod phase1;
 pub mod phase2;
+pub mod qtm;
 
-use crate::core::search::{ida_iter, Depth};
+use crate::core::search::{ida_iter, Depth, Observer, Search, Solved};
 // use crate::metric::Domino as DominoMetric;
-use crate::metric::Htm;
+use crate::metric::{Domino, Htm, Metric};
 // use crate::puzzle::{domino, Cube3x3, Domino};
 use crate::puzzle::Cube3x3;
 use std::convert::TryFrom;
@@ -20,42 +21,117 @@ pub type Tables = (
     // domino::PruningTable,
 );
 
+/// The search of one of the two phases, starting from the full cube, where each edge is the
+/// index of a move of `Metric`.
+trait Phase: Search<Edge = usize> {
+    type Metric: Metric;
+
+    fn from_cube(cube: &Cube3x3) -> Self;
+}
+
+impl Phase for Phase1 {
+    type Metric = Htm;
+
+    fn from_cube(cube: &Cube3x3) -> Self {
+        Self::from(cube)
+    }
+}
+
+impl Phase for Phase2 {
+    type Metric = Domino;
+
+    fn from_cube(cube: &Cube3x3) -> Self {
+        Self::try_from(cube).unwrap()
+    }
+}
+
 impl Cube3x3 {
-    pub fn kociemba(
+    pub fn kociemba(&self, tables: &Tables, max_length: Option<Depth>) -> Vec<Htm> {
+        self.kociemba_observed(tables, max_length, &mut (), &mut ())
+    }
+
+    /// Solve the cube with Kociemba's algorithm, reporting the progress of each of the two phases
+    /// to the corresponding observer. The phase 2 observer sees every phase 2 search, one for
+    /// each phase 1 solution tried.
+    pub fn kociemba_observed(
+        &self,
+        (move_table_1, pruning_table_1, move_table_2, pruning_table_2): &Tables,
+        max_length: Option<Depth>,
+        phase_1: &mut impl Observer,
+        phase_2: &mut impl Observer,
+    ) -> Vec<Htm> {
+        self.two_phase::<Phase1, Phase2>(
+            (move_table_1, pruning_table_1),
+            (move_table_2, pruning_table_2),
+            max_length,
+            phase_1,
+            phase_2,
+        )
+    }
+
+    /// Solve the cube with Kociemba's algorithm, where the length of each phase is measured in
+    /// quarter turns, so that the solution is short in QTM rather than HTM. This needs the tables
+    /// from `generate_qtm_tables`, and `max_length` is in quarter turns.
+    pub fn kociemba_qtm(&self, tables: &Tables, max_length: Option<Depth>) -> Vec<Htm> {
+        self.kociemba_qtm_observed(tables, max_length, &mut (), &mut ())
+    }
+
+    pub fn kociemba_qtm_observed(
         &self,
         (move_table_1, pruning_table_1, move_table_2, pruning_table_2): &Tables,
         max_length: Option<Depth>,
+        phase_1: &mut impl Observer,
+        phase_2: &mut impl Observer,
     ) -> Vec<Htm> {
-        let initial_phase_1 = Phase1::from(self);
+        self.two_phase::<qtm::Phase1, qtm::Phase2>(
+            (move_table_1, pruning_table_1),
+            (move_table_2, pruning_table_2),
+            max_length,
+            phase_1,
+            phase_2,
+        )
+    }
 
-        let res =
-            ida_iter(initial_phase_1, pruning_table_1, move_table_1, None).find_map(|path_1| {
-                let sol_1 = path_1.into_iter().map(|(_, e)| e).map(Phase1::gen_to_htm);
+    fn two_phase<A: Phase, B: Phase>(
+        &self,
+        (move_table_1, pruning_table_1): (&A::TransitionData, &A::HeuristicData),
+        (move_table_2, pruning_table_2): (&B::TransitionData, &B::HeuristicData),
+        max_length: Option<Depth>,
+        phase_1: &mut impl Observer,
+        phase_2: &mut impl Observer,
+    ) -> Vec<Htm> {
+        let initial_phase_1 = A::from_cube(self);
 
-                let intermediate_position = self.apply_seq(sol_1.clone());
+        let res = ida_iter(
+            initial_phase_1,
+            &Solved,
+            pruning_table_1,
+            move_table_1,
+            None,
+        )
+        .with_observer(phase_1)
+        .find_map(|path_1| {
+            let length_1: Depth = path_1.iter().map(|&(_, e)| A::cost(e)).sum();
+            let sol_1 = path_1
+                .into_iter()
+                .map(|(_, e)| A::Metric::ALL[e].to_htm())
+                .collect::<Vec<_>>();
 
-                // let initial_phase_2 = Domino::try_from(&intermediate_position).unwrap();
-                let initial_phase_2 = Phase2::try_from(&intermediate_position).unwrap();
+            let intermediate_position = self.apply_seq(sol_1.iter().copied());
 
-                let max = max_length.map(|l| l - sol_1.len() as Depth);
+            // let initial_phase_2 = Domino::try_from(&intermediate_position).unwrap();
+            let initial_phase_2 = B::from_cube(&intermediate_position);
 
-                let path_2 =
-                    ida_iter(initial_phase_2, &pruning_table_2, &move_table_2, max).next()?;
-                let sol_2 = path_2.into_iter().map(|(_, e)| e).collect::<Vec<_>>();
+            let max = max_length.map(|l| l - length_1);
 
-                let solution = sol_1
-                    .into_iter()
-                    .map(Htm::from)
-                    .chain(
-                        sol_2
-                            .into_iter()
-                            // .map(|x| DominoMetric::try_from(x).unwrap().into()),
-                            .map(|x| Phase2::gen_to_htm(x)),
-                    )
-                    .collect::<Vec<_>>();
+            let path_2 = ida_iter(initial_phase_2, &Solved, pruning_table_2, move_table_2, max)
+                .with_observer(&mut *phase_2)
+                .next()?;
+            let sol_2 = path_2.into_iter().map(|(_, e)| B::Metric::ALL[e].to_htm());
 
-                Some(solution)
-            });
+            // The last turn of phase 1 may cancel against the first of phase 2
+            Some(Htm::simplify(sol_1.into_iter().chain(sol_2)))
+        });
 
         res.unwrap()
     }
@@ -71,3 +147,15 @@ pub fn generate_tables() -> Tables {
 
     (move_1, pruning_1, move_2, pruning_2)
 }
+
+/// The tables for `Cube3x3::kociemba_qtm`, which share the move tables with `generate_tables`
+/// but measure distances in quarter turns.
+pub fn generate_qtm_tables() -> Tables {
+    let move_1 = phase1::Table::new();
+    let pruning_1 = qtm::Phase1::pruning_table(&move_1);
+
+    let move_2 = phase2::Table::new();
+    let pruning_2 = qtm::Phase2::pruning_table(&move_2);
+
+    (move_1, pruning_1, move_2, pruning_2)
+}
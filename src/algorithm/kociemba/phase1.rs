@@ -5,14 +5,13 @@ use crate::core::transition as trans;
 use crate::metric::{htm, Htm};
 use crate::puzzle::*;
 use std::cmp::max;
-use std::convert::TryFrom;
-use std::iter::FromIterator;
+use std::iter::{once, FromIterator};
 
 type Corners = def::OrientationCoord<8, 3>;
 type Edges = def::OrientationCoord<12, 2>;
 type Slice = def::CombinationCoord<12, 4>;
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Cube {
     corners: Corners,
     edges: Edges,
@@ -35,10 +34,6 @@ impl Cube {
     pub fn create_pruning_table(move_table: &Table) -> PruningTable {
         PruningTable::new(move_table)
     }
-
-    pub fn gen_to_htm(gen: usize) -> Htm {
-        Htm::try_from(gen).unwrap()
-    }
 }
 
 impl Search for Cube {
@@ -163,11 +158,29 @@ pub struct PruningTable(
 );
 
 impl PruningTable {
-    pub fn new(Table(c_table, e_table, s_table): &Table) -> Self {
+    pub fn new(table: &Table) -> Self {
+        Self::with_generators(table, &Htm::GENERATORS, |_| 1)
+    }
+
+    /// Construct the table for searches using only `generators`, given as indices into the move
+    /// table, with the costs given by `cost`.
+    pub fn with_generators<const M: usize>(
+        Table(c_table, e_table, s_table): &Table,
+        generators: &[usize; M],
+        cost: impl Fn(usize) -> Depth,
+    ) -> Self {
+        let cost = |gen: &usize| cost(*gen);
+
         Self(
-            pruning::Table::new(&Htm::GENERATORS, |coord, gen| c_table.lookup(coord, *gen)),
-            pruning::Table::new(&Htm::GENERATORS, |coord, gen| e_table.lookup(coord, *gen)),
-            pruning::Table::new(&Htm::GENERATORS, |coord, gen| s_table.lookup(coord, *gen)),
+            pruning::Table::with_costs(once(Default::default()), generators, cost, |coord, gen| {
+                c_table.lookup(coord, *gen)
+            }),
+            pruning::Table::with_costs(once(Default::default()), generators, cost, |coord, gen| {
+                e_table.lookup(coord, *gen)
+            }),
+            pruning::Table::with_costs(once(Default::default()), generators, cost, |coord, gen| {
+                s_table.lookup(coord, *gen)
+            }),
         )
     }
 
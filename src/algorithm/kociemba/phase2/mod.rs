@@ -1,16 +1,12 @@
-mod moves;
-
 use crate::core::definitions as def;
 use crate::core::pruning;
 use crate::core::search::{Depth, Search};
 use crate::core::transition as trans;
-use crate::metric::Htm;
+use crate::metric::{Domino, Htm, Metric};
 use crate::puzzle::Cube3x3;
-use crate::util::count;
-use moves::*;
 use std::cmp::max;
-use std::convert::TryFrom;
-use std::iter::FromIterator;
+use std::convert::{TryFrom, TryInto};
+use std::iter::{once, FromIterator};
 
 type Corners = def::PermutationCoord<CORNERS>;
 type Edges = def::PermutationCoord<EDGES>;
@@ -19,10 +15,9 @@ type Slice = def::PermutationCoord<SLICE_EDGES>;
 const CORNERS: usize = 8;
 const EDGES: usize = 8;
 const SLICE_EDGES: usize = 4;
-const MOVE_COUNT: usize = 10;
-const GENERATORS: [usize; MOVE_COUNT] = count::<MOVE_COUNT>();
+const MOVE_COUNT: usize = Domino::COUNT;
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Cube {
     corners: Corners,
     edges: Edges,
@@ -45,24 +40,6 @@ impl Cube {
     pub fn create_pruning_table(move_table: &Table) -> PruningTable {
         PruningTable::new(move_table)
     }
-
-    pub fn gen_to_htm(value: usize) -> Htm {
-        use Htm::*;
-
-        match value {
-            0 => U1,
-            1 => U2,
-            2 => U3,
-            3 => D1,
-            4 => D2,
-            5 => D3,
-            6 => R2,
-            7 => F2,
-            8 => L2,
-            9 => B2,
-            _ => panic!("Not a phase 2 generator"),
-        }
-    }
 }
 
 impl Search for Cube {
@@ -103,59 +80,28 @@ impl TryFrom<Htm> for Cube {
     type Error = ();
 
     fn try_from(turn: Htm) -> Result<Self, Self::Error> {
-        use Htm::*;
-
-        let ix = match turn {
-            U1 => Ok(0),
-            U2 => Ok(1),
-            U3 => Ok(2),
-            D1 => Ok(3),
-            D2 => Ok(4),
-            D3 => Ok(5),
-            R2 => Ok(6),
-            F2 => Ok(7),
-            L2 => Ok(8),
-            B2 => Ok(9),
-            _ => Err(()),
-        }?;
-
-        let corners = CORNER_MOVES[ix].p_coordinate();
-        let edges = EDGE_MOVES[ix].p_coordinate();
-        let slice = SLICE_MOVES[ix].p_coordinate();
-
-        Ok(Cube {
-            corners,
-            edges,
-            slice,
-        })
+        Domino::try_from(turn)?;
+        Ok(Self::from_iter(once(turn)))
     }
 }
 
 // TODO: this is dumb, is it used?
 impl FromIterator<Htm> for Cube {
     fn from_iter<T: IntoIterator<Item = Htm>>(iter: T) -> Self {
-        let (corners, edges, slice) = iter
-            .into_iter()
-            .map(usize::try_from)
-            .map(|x| x.expect("Invalid move for Kociemba phase 2."))
-            .map(|ix| (&CORNER_MOVES[ix], &EDGE_MOVES[ix], &SLICE_MOVES[ix]))
-            .fold(
-                (
-                    def::Array::default(),
-                    def::Array::default(),
-                    def::Array::default(),
-                ),
-                |(u, v, w), (x, y, z)| (u.permute(&x), v.permute(&y), w.permute(&z)),
-            );
-
-        Self::new(
-            corners.p_coordinate(),
-            edges.p_coordinate(),
-            slice.p_coordinate(),
-        )
+        let cube = Cube3x3::from_iter(iter.into_iter().inspect(|&turn| {
+            Domino::try_from(turn).expect("Invalid move for Kociemba phase 2.");
+        }));
+
+        Self::try_from(&cube).unwrap()
     }
 }
 
+/// The movement of some of the pieces, given by `pieces`, by each move of `M` in turn.
+fn moves<M: Metric, A: std::fmt::Debug>(pieces: impl Fn(M) -> A) -> [A; MOVE_COUNT] {
+    let moves: Vec<_> = M::ALL.iter().copied().map(pieces).collect();
+    moves.try_into().unwrap()
+}
+
 pub struct Table(
     trans::Table<Corners, { Corners::BOUND }, MOVE_COUNT>,
     trans::Table<Edges, { Edges::BOUND }, MOVE_COUNT>,
@@ -165,9 +111,25 @@ pub struct Table(
 impl Table {
     pub fn new() -> Self {
         Self(
-            trans::Table::new(&CORNER_MOVES, Corners::all(), Corners::permute),
-            trans::Table::new(&EDGE_MOVES, Edges::all(), Edges::permute),
-            trans::Table::new(&SLICE_MOVES, Slice::all(), Slice::permute),
+            trans::Table::new(
+                &moves::<Domino, _>(|turn| *turn.corners()),
+                Corners::all(),
+                Corners::permute,
+            ),
+            trans::Table::new(
+                &moves::<Domino, _>(|turn| turn.edges().truncate::<EDGES>().unwrap()),
+                Edges::all(),
+                Edges::permute,
+            ),
+            trans::Table::new(
+                // The slice edges are numbered from 8, and their coordinate renumbers them from 0
+                &moves::<Domino, _>(|turn| {
+                    let slice = turn.edges().drop::<SLICE_EDGES>().unwrap();
+                    slice.p_coordinate().array::<1>()
+                }),
+                Slice::all(),
+                Slice::permute,
+            ),
         )
     }
 
@@ -201,11 +163,29 @@ pub struct PruningTable(
 );
 
 impl PruningTable {
-    pub fn new(Table(c_table, e_table, s_table): &Table) -> Self {
+    pub fn new(table: &Table) -> Self {
+        Self::with_generators(table, &Domino::GENERATORS, |_| 1)
+    }
+
+    /// Construct the table for searches using only `generators`, given as indices into the move
+    /// table, with the costs given by `cost`.
+    pub fn with_generators<const M: usize>(
+        Table(c_table, e_table, s_table): &Table,
+        generators: &[usize; M],
+        cost: impl Fn(usize) -> Depth,
+    ) -> Self {
+        let cost = |gen: &usize| cost(*gen);
+
         Self(
-            pruning::Table::new(&GENERATORS, |coord, gen| c_table.lookup(coord, *gen)),
-            pruning::Table::new(&GENERATORS, |coord, gen| e_table.lookup(coord, *gen)),
-            pruning::Table::new(&GENERATORS, |coord, gen| s_table.lookup(coord, *gen)),
+            pruning::Table::with_costs(once(Default::default()), generators, cost, |coord, gen| {
+                c_table.lookup(coord, *gen)
+            }),
+            pruning::Table::with_costs(once(Default::default()), generators, cost, |coord, gen| {
+                e_table.lookup(coord, *gen)
+            }),
+            pruning::Table::with_costs(once(Default::default()), generators, cost, |coord, gen| {
+                s_table.lookup(coord, *gen)
+            }),
         )
     }
 
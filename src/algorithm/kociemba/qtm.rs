@@ -0,0 +1,160 @@
+//! The two phases of Kociemba's algorithm measured in the quarter turn metric. These use the same
+//! coordinates and move tables as in HTM, with their own pruning tables.
+
+use super::{phase1, phase2, Phase};
+use crate::core::search::{Depth, Search};
+use crate::metric::{Domino, Htm, Qtm};
+use crate::puzzle::Cube3x3;
+
+/// The indices of the quarter turns in the phase 1 move table, which is indexed by `Htm`.
+const PHASE_1_TURNS: [usize; Qtm::COUNT] = {
+    let mut turns = [0; Qtm::COUNT];
+
+    let mut ix = 0;
+    while ix < Qtm::COUNT {
+        turns[ix] = Qtm::ALL[ix].to_htm() as usize;
+        ix += 1;
+    }
+
+    turns
+};
+
+/// The indices of U, U', D, D', R2, F2, L2 and B2 in the phase 2 move table. U2 and D2 are left
+/// out as they are two quarter turns each.
+const PHASE_2_TURNS: [usize; 8] = {
+    use Domino::*;
+    [
+        U1 as usize,
+        U3 as usize,
+        D1 as usize,
+        D3 as usize,
+        R2 as usize,
+        F2 as usize,
+        L2 as usize,
+        B2 as usize,
+    ]
+};
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Phase1(pub phase1::Cube);
+
+impl Phase1 {
+    pub fn pruning_table(table: &phase1::Table) -> phase1::PruningTable {
+        phase1::PruningTable::with_generators(table, &PHASE_1_TURNS, |_| 1)
+    }
+}
+
+impl Search for Phase1 {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = phase1::PruningTable;
+    type TransitionData = phase1::Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.lookup(self.0)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        PHASE_1_TURNS
+            .iter()
+            .map(|&ix| (Self(table.lookup(self.0, ix)), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// The edges are indices into the phase 1 move table, which has every turn of `Htm`.
+impl Phase for Phase1 {
+    type Metric = Htm;
+
+    fn from_cube(cube: &Cube3x3) -> Self {
+        Self(phase1::Cube::from_cube(cube))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Phase2(pub phase2::Cube);
+
+impl Phase2 {
+    pub fn pruning_table(table: &phase2::Table) -> phase2::PruningTable {
+        phase2::PruningTable::with_generators(table, &PHASE_2_TURNS, half_turn_cost)
+    }
+}
+
+/// Every phase 2 turn of R, F, L or B is a half turn.
+fn half_turn_cost(ix: usize) -> Depth {
+    if Domino::ALL[ix].to_htm().amount() == 2 {
+        2
+    } else {
+        1
+    }
+}
+
+impl Search for Phase2 {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = phase2::PruningTable;
+    type TransitionData = phase2::Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.lookup(self.0)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        PHASE_2_TURNS
+            .iter()
+            .map(|&ix| (Self(table.lookup(self.0, ix)), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    fn cost(edge: usize) -> Depth {
+        half_turn_cost(edge)
+    }
+}
+
+impl Phase for Phase2 {
+    type Metric = Domino;
+
+    fn from_cube(cube: &Cube3x3) -> Self {
+        Self(phase2::Cube::from_cube(cube))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::generate_qtm_tables;
+    use crate::metric::Htm::{self, *};
+    use crate::metric::Qtm;
+    use crate::notation::Alg;
+    use crate::puzzle::Cube3x3;
+
+    fn qtm(solution: &[Htm]) -> usize {
+        Qtm::from_htm_seq(solution).len()
+    }
+
+    #[test]
+    pub fn quarter_turns() {
+        let tables = generate_qtm_tables();
+
+        // Within phase 2, where the quarter turn solution is optimal
+        let scramble = [R2, U1, D3, F2, U2];
+        let position = Cube3x3::from_slice(&scramble);
+        let solution = position.kociemba_qtm(&tables, None);
+        assert_eq!(
+            position.apply_seq(solution.iter().copied()),
+            Cube3x3::default()
+        );
+        assert!(qtm(&solution) <= qtm(&scramble));
+
+        let scramble = [R1, U3, F1, L1, D2, B3, R2, U1];
+        let position = Cube3x3::from_slice(&scramble);
+        let solution = position.kociemba_qtm(&tables, None);
+        assert_eq!(
+            position.apply_seq(solution.iter().copied()),
+            Cube3x3::default()
+        );
+        assert!(qtm(&solution) <= qtm(&scramble));
+        assert_eq!(Alg::from(solution.clone()).qtm(), qtm(&solution));
+    }
+}
@@ -0,0 +1,288 @@
+//! The last six edges of the Roux method, solved with M and U turns once both blocks and the
+//! corners of the last layer are solved. The pruning table holds the exact distance of every
+//! position, so the solutions are optimal in the slice turn metric.
+
+use crate::core::definitions as def;
+use crate::core::pruning;
+use crate::core::search::{ida_iter, Depth, Search, Solved};
+use crate::core::transition as trans;
+use crate::metric::htm::Face;
+use crate::metric::{Htm, Stm};
+use crate::notation::Rotation;
+use crate::puzzle::Cube3x3;
+use crate::util;
+use std::convert::{TryFrom, TryInto};
+
+type Edges = def::Array<6, 2>;
+type EdgeCoord = def::Coordinate<6, 2>;
+
+const EDGES: usize = def::OrientationCoord::<6, 2>::BOUND * def::PermutationCoord::<6>::BOUND;
+const MOVE_COUNT: usize = 6;
+
+/// The positions UR, UF, UL, UB, DF and DB of the six edges, as indices into `Cube3x3::edges`.
+const POSITIONS: [u8; 6] = [0, 1, 2, 3, 5, 7];
+
+pub const GENERATORS: [Stm; MOVE_COUNT] = [Stm::U1, Stm::U2, Stm::U3, Stm::M1, Stm::M2, Stm::M3];
+
+pub type Tables = (Table, PruningTable);
+
+/// The six edges, together with the number of quarter turns of M made by the centres and of U
+/// made by the corners.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Lse {
+    edges: EdgeCoord,
+    centres: u8,
+    corners: u8,
+}
+
+impl Lse {
+    pub const BOUND: usize = EDGES * 16;
+
+    fn apply(self, table: &Table, ix: usize) -> Self {
+        let Self {
+            edges,
+            centres,
+            corners,
+        } = self;
+        let amount = GENERATORS[ix].amount();
+
+        match GENERATORS[ix] {
+            Stm::U1 | Stm::U2 | Stm::U3 => Self {
+                edges: table.lookup(edges, ix),
+                centres,
+                corners: (corners + amount) % 4,
+            },
+            _ => Self {
+                edges: table.lookup(edges, ix),
+                centres: (centres + amount) % 4,
+                corners,
+            },
+        }
+    }
+
+    /// An optimal solution, in the slice turn metric.
+    pub fn solve(self, (table, pruning_table): &Tables) -> Vec<Stm> {
+        ida_iter(self, &Solved, pruning_table, table, None)
+            .next()
+            .unwrap()
+            .into_iter()
+            .map(|(_, ix)| GENERATORS[ix])
+            .collect()
+    }
+}
+
+/// The six edges of a cube, if none of the other edges are out of place.
+fn restrict(edges: &def::Array<12, 2>) -> Option<Edges> {
+    let edges = edges.elements();
+
+    let others_solved = (0..12)
+        .filter(|ix| !POSITIONS.contains(ix))
+        .all(|ix| edges[ix as usize] == (ix, 0));
+    if !others_solved {
+        return None;
+    }
+
+    let mut restricted = [(0, 0); 6];
+    for (ix, &position) in POSITIONS.iter().enumerate() {
+        let (edge, flip) = edges[position as usize];
+        let edge = POSITIONS.iter().position(|&p| p == edge)?;
+        restricted[ix] = (edge as u8, flip);
+    }
+
+    Edges::try_from(restricted).ok()
+}
+
+/// Whether the six edges can be solved with the centres and the corners turned as given. Each
+/// turn of M or U flips an even number of the edges, and each quarter turn is odd on the edges
+/// and turns the centres or the corners by one, so the parity of the edges is that of both turns.
+fn solvable(edges: &Edges, centres: u8, corners: u8) -> bool {
+    let odd = |amount: u8| amount % 2 == 1;
+    let flips = edges.elements().iter().map(|&(_, flip)| flip).sum::<u8>();
+
+    !odd(flips) && edges.is_even() != odd(centres + corners)
+}
+
+fn coordinate(edges: &Edges) -> EdgeCoord {
+    def::Coordinate(edges.o_coordinate(), edges.p_coordinate())
+}
+
+/// The position of a cube with both blocks and the last layer corners solved, up to a turn of U,
+/// if it can be solved.
+impl TryFrom<&Cube3x3> for Lse {
+    type Error = ();
+
+    fn try_from(cube: &Cube3x3) -> Result<Self, Self::Error> {
+        let edges = restrict(&cube.edges).ok_or(())?;
+
+        let centres = (0..4)
+            .find(|&amount| cube.centres == Rotation::of(Face::L).pow(amount))
+            .ok_or(())?;
+        let corners = (0..4)
+            .find(|&amount| {
+                cube.corners
+                    == (0..amount).fold(Default::default(), |corners: def::Array<8, 3>, _| {
+                        corners.permute(Htm::U1.to_corners())
+                    })
+            })
+            .ok_or(())?;
+
+        if !solvable(&edges, centres, corners) {
+            return Err(());
+        }
+
+        Ok(Self {
+            edges: coordinate(&edges),
+            centres,
+            corners,
+        })
+    }
+}
+
+impl From<Lse> for usize {
+    fn from(
+        Lse {
+            edges,
+            centres,
+            corners,
+        }: Lse,
+    ) -> Self {
+        usize::from(edges) * 16 + centres as usize * 4 + corners as usize
+    }
+}
+
+impl TryFrom<usize> for Lse {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            edges: (value / 16).try_into()?,
+            centres: (value / 4 % 4) as u8,
+            corners: (value % 4) as u8,
+        })
+    }
+}
+
+impl Search for Lse {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.0.lookup(self)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        (0..MOVE_COUNT)
+            .map(|ix| (self.apply(table, ix), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+pub struct Table(trans::Table<EdgeCoord, EDGES, MOVE_COUNT>);
+
+impl Table {
+    pub fn new() -> Self {
+        let moves = GENERATORS.map(|turn| restrict(&Stm::EDGE_MOVES[turn as usize]).unwrap());
+
+        Self(trans::Table::new(
+            &moves,
+            EdgeCoord::all(),
+            |coord, turn| coordinate(&coord.array().permute(turn)),
+        ))
+    }
+
+    pub fn lookup(&self, edges: EdgeCoord, index: usize) -> EdgeCoord {
+        self.0.lookup(edges, index)
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct PruningTable(pruning::Table<Lse, { Lse::BOUND }>);
+
+impl PruningTable {
+    pub fn new(table: &Table) -> Self {
+        Self(pruning::Table::new(
+            &util::count::<MOVE_COUNT>(),
+            |position: Lse, &ix: &usize| position.apply(table, ix),
+        ))
+    }
+}
+
+pub fn generate_tables() -> Tables {
+    let table = Table::new();
+    let pruning_table = PruningTable::new(&table);
+
+    (table, pruning_table)
+}
+
+impl Cube3x3 {
+    /// Solve the last six edges optimally in the slice turn metric, or `None` if anything other
+    /// than the six edges, the M slice centres and the turn of U is out of place, or if the cube
+    /// cannot be solved, e.g. with a single flipped edge.
+    pub fn lse(&self, tables: &Tables) -> Option<Vec<Stm>> {
+        Lse::try_from(self).ok().map(|lse| lse.solve(tables))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Alg;
+
+    fn cube(source: &str) -> Cube3x3 {
+        let alg: Alg = source.parse().unwrap();
+        alg.moves()
+            .iter()
+            .fold(Cube3x3::default(), |cube, &turn| cube.apply_move(turn))
+    }
+
+    #[test]
+    pub fn last_six_edges() {
+        let tables = generate_tables();
+
+        let scramble = cube("M' U2 M U M' U' M2 U2 M U");
+        let solution = scramble.lse(&tables).unwrap();
+        assert!(solution.len() <= 10);
+        assert_eq!(
+            solution
+                .iter()
+                .fold(scramble, |cube, &turn| cube.apply_stm(turn)),
+            Cube3x3::default()
+        );
+
+        assert_eq!(cube("M2 U2 M2 U2").lse(&tables).unwrap().len(), 4);
+        assert_eq!(cube("M").lse(&tables), Some(vec![Stm::M3]));
+        assert_eq!(cube("R U R'").lse(&tables), None);
+
+        // A single flipped edge, or a single swap of two, cannot be solved
+        let flipped = cube("M' U2 M");
+        let mut edges = flipped.edges.elements();
+        edges[1].1 ^= 1;
+        let flipped = Cube3x3::new(flipped.corners, def::Array::new(edges));
+        assert_eq!(flipped.lse(&tables), None);
+
+        let mut edges = Cube3x3::default().edges.elements();
+        edges.swap(0, 2);
+        let swapped = Cube3x3::new(Default::default(), def::Array::new(edges));
+        assert_eq!(swapped.lse(&tables), None);
+    }
+
+    #[test]
+    pub fn reachable() {
+        let (_, PruningTable(pruning_table)) = &generate_tables();
+
+        for ix in 0..Lse::BOUND {
+            let lse = Lse::try_from(ix).unwrap();
+            let reachable = lse == Lse::default() || pruning_table.lookup(lse) > 0;
+            let solvable = solvable(&lse.edges.array(), lse.centres, lse.corners);
+            assert_eq!(solvable, reachable, "{:?}", lse);
+        }
+    }
+}
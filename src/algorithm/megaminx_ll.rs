@@ -0,0 +1,201 @@
+//! The last layer of the Megaminx in four looks, once the rest of the puzzle is solved: the
+//! edges are oriented, then permuted, then the corners are permuted and last twisted. Each step
+//! keeps the rest of the puzzle and the steps before it solved, and looks up the pieces it
+//! solves in a table of sequences of its algorithms, each found by a breadth-first search.
+
+use crate::core::definitions as def;
+use crate::metric::Megaminx as MegaminxMetric;
+use crate::puzzle::Megaminx;
+use std::collections::VecDeque;
+
+/// The positions of the pieces of the last layer, around U, are the first five of each kind.
+const LAYER: usize = 5;
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Step {
+    EdgeOrientation,
+    EdgePermutation,
+    CornerPermutation,
+    CornerOrientation,
+}
+
+impl Step {
+    pub const ALL: [Self; 4] = [
+        Step::EdgeOrientation,
+        Step::EdgePermutation,
+        Step::CornerPermutation,
+        Step::CornerOrientation,
+    ];
+
+    /// The algorithms of the step, each of which is also made from the other angles of U. Those
+    /// of the edges may end with a turn of U, which those of the corners must keep in place.
+    const fn algs(self) -> &'static [&'static str] {
+        match self {
+            Step::EdgeOrientation => &["U", "F R U R' U' F'"],
+            Step::EdgePermutation => &["U", "R U R' U R U2' R'", "R U2 R' U' R U' R'"],
+            Step::CornerPermutation => &["R U2' L' U2 R' U2' L U2"],
+            Step::CornerOrientation => &["DR R' DR' R DR R' DR' R U R' DR R DR' R' DR R DR' U'"],
+        }
+    }
+
+    /// The algorithms, seen from each angle of U.
+    fn moves(self) -> Vec<Vec<MegaminxMetric>> {
+        let u = |amount| (1..=amount).map(|_| MegaminxMetric::U1);
+
+        self.algs()
+            .iter()
+            .flat_map(|alg| {
+                let alg = MegaminxMetric::parse(alg).unwrap();
+                (0..5).map(move |amount| {
+                    let conjugate = u(amount)
+                        .chain(alg.iter().copied())
+                        .chain(u(5 - amount))
+                        .collect::<Vec<_>>();
+                    MegaminxMetric::simplify(&conjugate)
+                })
+            })
+            .collect()
+    }
+
+    pub const fn bound(self) -> usize {
+        match self {
+            Step::EdgeOrientation => def::OrientationCoord::<LAYER, 2>::BOUND,
+            Step::CornerOrientation => def::OrientationCoord::<LAYER, 3>::BOUND,
+            _ => def::PermutationCoord::<LAYER>::BOUND,
+        }
+    }
+
+    /// The pieces of the last layer which the step solves, as a coordinate. As the rest of the
+    /// puzzle is solved, the first five corners and edges are the pieces of the last layer.
+    fn coordinate(self, puzzle: &Megaminx) -> usize {
+        let corners = puzzle.corners.truncate::<LAYER>().unwrap();
+        let edges = puzzle.edges.truncate::<LAYER>().unwrap();
+
+        match self {
+            Step::EdgeOrientation => edges.o_coordinate().into(),
+            Step::EdgePermutation => edges.p_coordinate().into(),
+            Step::CornerPermutation => corners.p_coordinate().into(),
+            Step::CornerOrientation => corners.o_coordinate().into(),
+        }
+    }
+
+    /// The solution of each coordinate which the algorithms of the step reach, as the fewest of
+    /// them which solve it.
+    fn search(self) -> Vec<Option<Vec<MegaminxMetric>>> {
+        let moves = self.moves();
+        let mut solutions = vec![None; self.bound()];
+
+        let solved = Megaminx::default();
+        solutions[self.coordinate(&solved)] = Some(vec![]);
+        let mut queue = VecDeque::from(vec![(solved, vec![])]);
+
+        while let Some((puzzle, solution)) = queue.pop_front() {
+            for turns in moves.iter() {
+                let next = puzzle.apply_seq(turns.iter().copied());
+                let coordinate = self.coordinate(&next);
+                if solutions[coordinate].is_none() {
+                    let mut undo = MegaminxMetric::invert_seq(turns);
+                    undo.extend_from_slice(&solution);
+                    let undo = MegaminxMetric::simplify(&undo);
+
+                    solutions[coordinate] = Some(undo.clone());
+                    queue.push_back((next, undo));
+                }
+            }
+        }
+
+        solutions
+    }
+}
+
+/// The solutions of each step, indexed by the coordinate of the step.
+pub struct Table([Vec<Option<Vec<MegaminxMetric>>>; 4]);
+
+impl Table {
+    pub fn new() -> Self {
+        Self(Step::ALL.map(Step::search))
+    }
+
+    /// The solution of the step for the position, if the step can solve it.
+    pub fn lookup(&self, step: Step, puzzle: &Megaminx) -> Option<&[MegaminxMetric]> {
+        self.0[step as usize][step.coordinate(puzzle)].as_deref()
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Megaminx {
+    /// Whether all but the last layer is solved, with the centres in place.
+    pub fn last_layer_only(&self) -> bool {
+        let corners = self.corners.elements();
+        let edges = self.edges.elements();
+
+        self.centres == def::Array::IDENTITY
+            && (LAYER..corners.len()).all(|ix| corners[ix] == (ix as u8, 0))
+            && (LAYER..edges.len()).all(|ix| edges[ix] == (ix as u8, 0))
+    }
+
+    /// Solve the last layer in four steps, or `None` if anything other than the last layer is
+    /// out of place.
+    pub fn last_layer(&self, table: &Table) -> Option<Vec<MegaminxMetric>> {
+        if !self.last_layer_only() {
+            return None;
+        }
+
+        let mut puzzle = *self;
+        let mut solution = vec![];
+        for &step in Step::ALL.iter() {
+            let turns = table.lookup(step, &puzzle)?;
+            puzzle = puzzle.apply_seq(turns.iter().copied());
+            solution.extend_from_slice(turns);
+        }
+
+        Some(MegaminxMetric::simplify(&solution))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::seq::SliceRandom;
+    use std::iter::FromIterator;
+
+    #[test]
+    pub fn last_layer() {
+        let table = Table::new();
+
+        // Every orientation of the edges and of the corners and every even permutation of either
+        let reachable = |step: Step| table.0[step as usize].iter().flatten().count();
+        assert_eq!(reachable(Step::EdgeOrientation), 16);
+        assert_eq!(reachable(Step::EdgePermutation), 60);
+        assert_eq!(reachable(Step::CornerPermutation), 60);
+        assert_eq!(reachable(Step::CornerOrientation), 81);
+
+        // The algorithms keep the rest of the puzzle solved
+        for &step in Step::ALL.iter() {
+            for turns in step.moves() {
+                assert!(Megaminx::from_iter(turns).last_layer_only());
+            }
+        }
+
+        let mut rng = rand::thread_rng();
+        let moves = Step::ALL
+            .iter()
+            .flat_map(|step| step.moves())
+            .collect::<Vec<_>>();
+        for _ in 0..5 {
+            let puzzle = (0..20).fold(Megaminx::default(), |puzzle, _| {
+                puzzle.apply_seq(moves.choose(&mut rng).unwrap().iter().copied())
+            });
+            let solution = puzzle.last_layer(&table).unwrap();
+            assert!(puzzle.apply_seq(solution).is_solved());
+        }
+
+        let puzzle = Megaminx::from_iter(MegaminxMetric::parse("R").unwrap());
+        assert_eq!(puzzle.last_layer(&table), None);
+    }
+}
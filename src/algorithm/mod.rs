@@ -1 +1,4 @@
+pub mod ergonomics;
 pub mod kociemba;
+pub mod lse;
+pub mod megaminx_ll;
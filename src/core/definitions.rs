@@ -1,4 +1,4 @@
-use crate::util::{binomial, factorial, power};
+use crate::util::{binomial, factorial, power, wide_factorial, wide_power};
 use std::convert::{TryFrom, TryInto};
 use std::iter::Product;
 
@@ -7,6 +7,7 @@ type Orientation = u8;
 type CCoordWidth = u16;
 type OCoordWidth = u16;
 type PCoordWidth = u32;
+type WideCoordWidth = u128;
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum CreationError {
@@ -14,7 +15,7 @@ pub enum CreationError {
     InvalidPermutation,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Array<const N: usize, const M: Orientation>([(Element, Orientation); N]);
 
 impl<const N: usize, const M: Orientation> Array<N, M> {
@@ -76,6 +77,26 @@ impl<const N: usize, const M: Orientation> Array<N, M> {
         Ok(Self(candidate))
     }
 
+    pub const fn elements(&self) -> [(Element, Orientation); N] {
+        self.0
+    }
+
+    /// The array which undoes this one, so that permuting by both in either order is the
+    /// identity.
+    pub const fn inverse(&self) -> Self {
+        let Self(this) = self;
+        let Self(mut base) = Self::IDENTITY;
+
+        let mut ix = 0;
+        while ix < N {
+            let (j, o) = this[ix];
+            base[j as usize] = (ix as Element, (M - o) % M);
+            ix += 1;
+        }
+
+        Array(base)
+    }
+
     pub const fn permute(&self, Self(that): &Self) -> Self {
         let Self(this) = self;
         let Self(mut base) = Self::IDENTITY;
@@ -136,6 +157,33 @@ impl<const N: usize, const M: Orientation> Array<N, M> {
         PermutationCoord(t)
     }
 
+    /// The same coordinate as `p_coordinate`, for arrays too long for its width.
+    pub const fn wide_p_coordinate(&self) -> WidePermutationCoord<N> {
+        debug_assert!(N < 35, "Coordinate space exceeds u128");
+
+        let Self(this) = self;
+
+        let n = N as WideCoordWidth;
+        let mut t: WideCoordWidth = 0;
+
+        let mut ix = 0;
+        while ix < N - 1 {
+            t *= n - ix as WideCoordWidth;
+
+            let mut jx = ix + 1;
+            while jx < N {
+                if this[ix].0 > this[jx].0 {
+                    t += 1;
+                };
+                jx += 1;
+            }
+
+            ix += 1;
+        }
+
+        WidePermutationCoord(t)
+    }
+
     pub const fn o_coordinate(&self) -> OrientationCoord<N, M> {
         let Self(this) = self;
         let mut t: OCoordWidth = 0;
@@ -151,6 +199,22 @@ impl<const N: usize, const M: Orientation> Array<N, M> {
         OrientationCoord(t)
     }
 
+    /// The same coordinate as `o_coordinate`, for arrays too long for its width.
+    pub const fn wide_o_coordinate(&self) -> WideOrientationCoord<N, M> {
+        let Self(this) = self;
+        let mut t: WideCoordWidth = 0;
+
+        let mut ix = 0;
+        while ix < N - 1 {
+            t *= M as WideCoordWidth;
+            t += this[ix].1 as WideCoordWidth;
+
+            ix += 1;
+        }
+
+        WideOrientationCoord(t)
+    }
+
     pub const fn c_coordinate<const K: usize>(&self) -> CombinationCoord<N, K> {
         let Self(cm) = self;
 
@@ -172,7 +236,7 @@ impl<const N: usize, const M: Orientation> Array<N, M> {
     }
 
     pub fn truncate<const K: usize>(&self) -> Result<Array<K, M>, CreationError> {
-        debug_assert!(K < N, "Cannot truncate to longer array");
+        debug_assert!(K <= N, "Cannot truncate to longer array");
 
         let Self(long_array) = self;
         let short_array: [(Element, Orientation); K] = long_array[0..K].try_into().unwrap();
@@ -207,6 +271,31 @@ impl<const N: usize, const M: Orientation> Array<N, M> {
 
         Self(array)
     }
+
+    /// Whether the permutation is even, i.e. made of an even number of swaps.
+    pub fn is_even(&self) -> bool {
+        let Self(array) = self;
+
+        let inversions = (0..N)
+            .flat_map(|ix| (ix + 1..N).map(move |jx| (ix, jx)))
+            .filter(|&(ix, jx)| array[ix].0 > array[jx].0)
+            .count();
+
+        inversions % 2 == 0
+    }
+
+    /// A random array as from `random`, but with an even permutation, as for puzzles which can
+    /// only make three-cycles of their pieces.
+    pub fn random_even() -> Self {
+        let random = Self::random();
+        let Self(mut array) = random;
+
+        if !random.is_even() {
+            array.swap(0, 1);
+        }
+
+        Self(array)
+    }
 }
 
 impl<const N: usize, const M: Orientation> Product for Array<N, M> {
@@ -229,7 +318,7 @@ impl<const N: usize, const M: Orientation> TryFrom<[(Element, Orientation); N]>
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Coordinate<const N: usize, const M: Orientation>(
     pub OrientationCoord<N, M>,
     pub PermutationCoord<N>,
@@ -268,7 +357,7 @@ impl<const N: usize, const M: Orientation> TryFrom<usize> for Coordinate<N, M> {
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub struct OrientationCoord<const N: usize, const M: Orientation>(OCoordWidth);
 
 impl<const N: usize, const M: Orientation> OrientationCoord<N, M> {
@@ -325,7 +414,7 @@ impl<const N: usize, const M: Orientation> TryFrom<usize> for OrientationCoord<N
     }
 }
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub struct PermutationCoord<const N: usize>(PCoordWidth);
 
 impl<const N: usize> PermutationCoord<N> {
@@ -385,10 +474,127 @@ impl<const N: usize> TryFrom<usize> for PermutationCoord<N> {
     }
 }
 
+/// A permutation coordinate wide enough for up to 34 elements, such as the 30 edges of the
+/// Megaminx. It is too large to index a table, so it converts only to and from `u128`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct WidePermutationCoord<const N: usize>(WideCoordWidth);
+
+impl<const N: usize> WidePermutationCoord<N> {
+    pub const BOUND: u128 = wide_factorial(N);
+
+    const fn raw_array(self) -> [Element; N] {
+        let WidePermutationCoord(mut t) = self;
+        let mut pm: [Element; N] = [0; N];
+        let mut ix: Element = N as Element - 2;
+
+        // ix from N-2 to 0, with wrapping subtraction because it is unsigned
+        while ix != Element::MAX {
+            let r = N as WideCoordWidth - ix as WideCoordWidth;
+            pm[ix as usize] = (t % r) as Element;
+            t /= r;
+
+            let mut jx: usize = ix as usize + 1;
+            while jx < N {
+                if pm[jx] >= pm[ix as usize] {
+                    pm[jx] += 1;
+                };
+                jx += 1;
+            }
+
+            ix = ix.wrapping_sub(1);
+        }
+
+        pm
+    }
+
+    pub fn array<const M: Orientation>(self) -> Array<N, M> {
+        Array(self.raw_array().map(|x| (x, 0)))
+    }
+
+    pub fn permute<const M: Orientation>(self, array: &Array<N, M>) -> Self {
+        self.array().permute(array).wide_p_coordinate()
+    }
+}
+
+impl<const N: usize> From<WidePermutationCoord<N>> for u128 {
+    fn from(WidePermutationCoord(t): WidePermutationCoord<N>) -> Self {
+        t
+    }
+}
+
+impl<const N: usize> TryFrom<u128> for WidePermutationCoord<N> {
+    type Error = ();
+
+    fn try_from(value: u128) -> Result<Self, Self::Error> {
+        if value < wide_factorial(N) {
+            Ok(Self(value))
+        } else {
+            Err(())
+        }
+    }
+}
+
+/// An orientation coordinate wide enough for the 20 corners and 30 edges of the Megaminx. Like
+/// `WidePermutationCoord` it converts only to and from `u128`.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct WideOrientationCoord<const N: usize, const M: Orientation>(WideCoordWidth);
+
+impl<const N: usize, const M: Orientation> WideOrientationCoord<N, M> {
+    pub const BOUND: u128 = wide_power(M, N - 1);
+
+    fn raw_array(self) -> [Orientation; N] {
+        let WideOrientationCoord(mut t) = self;
+
+        let mut s = 0i8;
+        let mut or = [0; N];
+
+        for ix in (0..N - 1).rev() {
+            let r = (t % M as WideCoordWidth) as Orientation;
+            or[ix] = r;
+            s -= r as i8;
+            if s < 0 {
+                s += M as i8
+            };
+            t /= M as WideCoordWidth;
+        }
+
+        or[N - 1] = s as Orientation;
+
+        or
+    }
+
+    pub fn array(self) -> Array<N, M> {
+        let mut nats = 0..;
+        Array(self.raw_array().map(|x| (nats.next().unwrap(), x)))
+    }
+
+    pub fn permute(self, array: &Array<N, M>) -> Self {
+        self.array().permute(array).wide_o_coordinate()
+    }
+}
+
+impl<const N: usize, const M: Orientation> From<WideOrientationCoord<N, M>> for u128 {
+    fn from(WideOrientationCoord(t): WideOrientationCoord<N, M>) -> Self {
+        t
+    }
+}
+
+impl<const N: usize, const M: Orientation> TryFrom<u128> for WideOrientationCoord<N, M> {
+    type Error = ();
+
+    fn try_from(value: u128) -> Result<Self, Self::Error> {
+        if value < wide_power(M, N - 1) {
+            Ok(Self(value))
+        } else {
+            Err(())
+        }
+    }
+}
+
 // Due to const generics limitations, currently the elements of interest must be the last `K`
 // elements in the array representation. This should change when Rust improves const generics
 // support.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct CombinationCoord<const N: usize, const K: usize>(CCoordWidth);
 
 impl<const N: usize, const K: usize> Default for CombinationCoord<N, K> {
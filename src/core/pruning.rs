@@ -14,10 +14,36 @@ where
     [Depth; N]: Sized,
 {
     pub fn new<T, const M: usize>(generators: &[T; M], transition: impl Fn(S, &T) -> S) -> Self {
+        Self::from_goals(std::iter::once(S::default()), generators, transition)
+    }
+
+    /// Construct a table of the distance from each position to the nearest of `goals`, for use
+    /// as the heuristic of a search with several goal positions.
+    pub fn from_goals<T, const M: usize>(
+        goals: impl IntoIterator<Item = S>,
+        generators: &[T; M],
+        transition: impl Fn(S, &T) -> S,
+    ) -> Self {
+        Self::with_costs(goals, generators, |_| 1, transition)
+    }
+
+    /// Construct a table of the least total cost of reaching the nearest of `goals` from each
+    /// position, where each generator has the cost given by `cost`, which must be at least one.
+    pub fn with_costs<T, const M: usize>(
+        goals: impl IntoIterator<Item = S>,
+        generators: &[T; M],
+        cost: impl Fn(&T) -> Depth,
+        transition: impl Fn(S, &T) -> S,
+    ) -> Self {
         let mut table: Box<[u8; N]> = vec![Depth::MAX; N].into_boxed_slice().try_into().unwrap();
 
-        table[S::default().into()] = 0;
+        for goal in goals {
+            table[goal.into()] = 0;
+        }
 
+        // The positions of each depth are final once all shallower ones have been expanded, so
+        // the search is over once the depth passes the deepest one reached.
+        let mut deepest = 0;
         (0..).find(|depth| {
             let positions: Vec<S> = table
                 .iter()
@@ -29,13 +55,15 @@ where
             for position in &positions {
                 for generator in generators.iter() {
                     let ix = transition(*position, generator).into();
-                    if table[ix] > *depth + 1 {
-                        table[ix] = depth + 1;
+                    let next = *depth + cost(generator);
+                    if table[ix] > next {
+                        table[ix] = next;
+                        deepest = deepest.max(next);
                     };
                 }
             }
 
-            positions.is_empty()
+            *depth >= deepest
         });
 
         for ix in 0..table.len() {
@@ -59,3 +87,34 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::definitions::{Array, PermutationCoord};
+
+    #[test]
+    pub fn multiple_goals() {
+        type Coord = PermutationCoord<4>;
+
+        #[rustfmt::skip]
+        let swaps: [Array<4, 1>; 3] = [
+            Array::new([(1, 0), (0, 0), (2, 0), (3, 0)]),
+            Array::new([(0, 0), (2, 0), (1, 0), (3, 0)]),
+            Array::new([(0, 0), (1, 0), (3, 0), (2, 0)]),
+        ];
+
+        let goals = [Coord::default(), swaps[1].p_coordinate()];
+        let table = Table::<Coord, { Coord::BOUND }>::from_goals(
+            goals.iter().copied(),
+            &swaps,
+            |coord, swap| coord.permute(swap),
+        );
+
+        assert_eq!(table.lookup(goals[0]), 0);
+        assert_eq!(table.lookup(goals[1]), 0);
+        assert_eq!(table.lookup(swaps[0].p_coordinate()), 1);
+        assert_eq!(table.lookup(swaps[1].permute(&swaps[0]).p_coordinate()), 1);
+        assert_eq!(table.lookup(swaps[0].permute(&swaps[2]).p_coordinate()), 2);
+    }
+}
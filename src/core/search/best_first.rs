@@ -0,0 +1,375 @@
+use super::{Depth, Goal, Observer, Search};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
+
+pub type Priority = u32;
+
+/// The cost of the best known path to a vertex. Best-first searches are not bounded in depth
+/// like IDA*, so this is wider than `Depth`.
+pub type Cost = u32;
+
+/// The vertex preceding another on the best known path to it, with the connecting edge.
+type Parent<T> = Option<(T, <T as Search>::Edge)>;
+
+/// The collection of vertices waiting to be expanded by a best-first search, which always yields
+/// one of the items with the lowest priority.
+pub trait OpenList<T> {
+    fn push(&mut self, item: T, priority: Priority);
+
+    fn pop(&mut self) -> Option<T>;
+}
+
+/// An open list backed by a binary heap, suitable for any range of priorities. Among items of
+/// equal priority, the most recently pushed is popped first.
+pub struct HeapList<T>(BinaryHeap<HeapEntry<T>>, usize);
+
+impl<T> HeapList<T> {
+    pub fn new() -> Self {
+        Self(BinaryHeap::new(), 0)
+    }
+}
+
+impl<T> Default for HeapList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> OpenList<T> for HeapList<T> {
+    fn push(&mut self, item: T, priority: Priority) {
+        let Self(heap, count) = self;
+        heap.push(HeapEntry(Reverse(priority), *count, item));
+        *count += 1;
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        self.0.pop().map(|HeapEntry(_, _, item)| item)
+    }
+}
+
+struct HeapEntry<T>(Reverse<Priority>, usize, T);
+
+impl<T> PartialEq for HeapEntry<T> {
+    fn eq(&self, other: &Self) -> bool {
+        (self.0, self.1) == (other.0, other.1)
+    }
+}
+
+impl<T> Eq for HeapEntry<T> {}
+
+impl<T> PartialOrd for HeapEntry<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for HeapEntry<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.0, self.1).cmp(&(other.0, other.1))
+    }
+}
+
+/// An open list with one bucket per priority, which is faster than a heap when the priorities
+/// are small integers, as they are for puzzles with unit edge costs.
+pub struct BucketList<T> {
+    buckets: Vec<Vec<T>>,
+    lowest: usize,
+}
+
+impl<T> BucketList<T> {
+    pub fn new() -> Self {
+        Self {
+            buckets: Vec::new(),
+            lowest: 0,
+        }
+    }
+}
+
+impl<T> Default for BucketList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> OpenList<T> for BucketList<T> {
+    fn push(&mut self, item: T, priority: Priority) {
+        let priority = priority as usize;
+        if self.buckets.len() <= priority {
+            self.buckets.resize_with(priority + 1, Vec::new);
+        }
+        self.buckets[priority].push(item);
+        self.lowest = self.lowest.min(priority);
+    }
+
+    fn pop(&mut self) -> Option<T> {
+        while self.lowest < self.buckets.len() {
+            if let Some(item) = self.buckets[self.lowest].pop() {
+                return Some(item);
+            }
+            self.lowest += 1;
+        }
+        None
+    }
+}
+
+/// The factor by which weighted A* inflates the heuristic, as a fraction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Weight {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl Weight {
+    pub const ONE: Self = Self::new(1, 1);
+
+    pub const fn new(numerator: u32, denominator: u32) -> Self {
+        Self {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// The priority of a vertex at distance `g` from the start with heuristic value `h`, scaled
+    /// by the denominator so that it is an integer.
+    const fn priority(self, g: Cost, h: Depth) -> Priority {
+        g * self.denominator + h as Priority * self.numerator
+    }
+}
+
+/// A* search. If the heuristic is consistent, the path found is a shortest one.
+pub fn a_star<T: Search + Hash, G: Goal<T> + ?Sized>(
+    start: T,
+    goal: &G,
+    heuristic_data: &T::HeuristicData,
+    transition_data: &T::TransitionData,
+    open: impl OpenList<T>,
+    observer: impl Observer,
+) -> Option<Vec<(T, T::Edge)>> {
+    weighted_a_star(
+        start,
+        goal,
+        heuristic_data,
+        transition_data,
+        Weight::ONE,
+        open,
+        observer,
+    )
+}
+
+/// A* search with the heuristic multiplied by `weight`. A weight above one finds a path much
+/// faster, which is at most `weight` times longer than a shortest one if the heuristic is
+/// admissible.
+pub fn weighted_a_star<T: Search + Hash, G: Goal<T> + ?Sized>(
+    start: T,
+    goal: &G,
+    heuristic_data: &T::HeuristicData,
+    transition_data: &T::TransitionData,
+    weight: Weight,
+    mut open: impl OpenList<T>,
+    mut observer: impl Observer,
+) -> Option<Vec<(T, T::Edge)>> {
+    let mut best: HashMap<T, (Cost, Parent<T>)> = HashMap::new();
+    let mut closed = HashSet::new();
+
+    let estimate = start.heuristic(heuristic_data);
+    observer.heuristic_evaluated(estimate);
+    best.insert(start, (0, None));
+    open.push(start, weight.priority(0, estimate));
+
+    while let Some(vertex) = open.pop() {
+        if !closed.insert(vertex) {
+            continue;
+        }
+
+        let depth = best[&vertex].0;
+        if goal.is_goal(&vertex) {
+            let path = reconstruct(vertex, |v| best[v].1);
+            observer.solution_found(path.len());
+            return Some(path);
+        }
+
+        observer.node_expanded(depth.min(Depth::MAX as Cost) as Depth);
+        for (next, edge) in vertex.transition(transition_data) {
+            let cost = depth + T::cost(edge) as Cost;
+            let improved = match best.get(&next) {
+                Some((known, _)) => cost < *known,
+                None => true,
+            };
+            if improved && !closed.contains(&next) {
+                let estimate = next.heuristic(heuristic_data);
+                observer.heuristic_evaluated(estimate);
+                best.insert(next, (cost, Some((vertex, edge))));
+                open.push(next, weight.priority(cost, estimate));
+            }
+        }
+    }
+
+    None
+}
+
+/// Breadth-first search which keeps only the `width` most promising vertices of each layer, as
+/// ranked by the heuristic. The layers are by number of edges, regardless of their costs. This
+/// uses bounded memory and is fast, but it may return a long path or none at all.
+pub fn beam_search<T: Search + Hash, G: Goal<T> + ?Sized>(
+    start: T,
+    goal: &G,
+    heuristic_data: &T::HeuristicData,
+    transition_data: &T::TransitionData,
+    width: usize,
+    max_depth: Depth,
+    mut observer: impl Observer,
+) -> Option<Vec<(T, T::Edge)>> {
+    let mut parents: HashMap<T, Parent<T>> = HashMap::new();
+    parents.insert(start, None);
+
+    if goal.is_goal(&start) {
+        observer.solution_found(0);
+        return Some(Vec::new());
+    }
+
+    let mut beam = vec![start];
+    for depth in 0..max_depth {
+        observer.iteration_started(depth + 1);
+
+        let mut layer = Vec::new();
+        for &vertex in &beam {
+            observer.node_expanded(depth);
+            for (next, edge) in vertex.transition(transition_data) {
+                if parents.contains_key(&next) {
+                    continue;
+                }
+                parents.insert(next, Some((vertex, edge)));
+
+                if goal.is_goal(&next) {
+                    let path = reconstruct(next, |v| parents[v]);
+                    observer.solution_found(path.len());
+                    return Some(path);
+                }
+
+                let estimate = next.heuristic(heuristic_data);
+                observer.heuristic_evaluated(estimate);
+                layer.push((estimate, next));
+            }
+        }
+
+        layer.sort_by_key(|(estimate, _)| *estimate);
+        for _ in width.min(layer.len())..layer.len() {
+            observer.node_pruned(depth + 1);
+        }
+        layer.truncate(width);
+
+        observer.iteration_completed(depth + 1);
+        if layer.is_empty() {
+            break;
+        }
+        beam = layer.into_iter().map(|(_, vertex)| vertex).collect();
+    }
+
+    None
+}
+
+/// Follow the parent links back from `end` to the start.
+fn reconstruct<T: Search>(
+    end: T,
+    parent: impl Fn(&T) -> Option<(T, T::Edge)>,
+) -> Vec<(T, T::Edge)> {
+    let mut path = Vec::new();
+    let mut vertex = end;
+    while let Some((previous, edge)) = parent(&vertex) {
+        path.push((vertex, edge));
+        vertex = previous;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::kociemba::{phase1, Phase1};
+    use crate::core::search::{ida_iter, Solved};
+    use crate::metric::Htm::{self, *};
+    use crate::puzzle::Cube3x3;
+
+    fn solves(scramble: &[crate::metric::Htm], path: Vec<(Phase1, usize)>) -> bool {
+        let cube = Cube3x3::from_slice(scramble);
+        let cube = cube.apply_seq(path.into_iter().map(|(_, e)| Htm::ALL[e]));
+        Phase1::from(&cube) == Phase1::default()
+    }
+
+    #[test]
+    pub fn phase1() {
+        let table = phase1::Table::new();
+        let pruning = phase1::PruningTable::new(&table);
+        let scramble = [R1, F3, L2, U1, B1, D3, R2, F1];
+        let position: Phase1 = scramble.iter().collect();
+
+        let optimal = ida_iter(position, &Solved, &pruning, &table, None)
+            .next()
+            .unwrap()
+            .len();
+
+        let path = a_star(position, &Solved, &pruning, &table, BucketList::new(), ()).unwrap();
+        assert_eq!(path.len(), optimal);
+        assert!(solves(&scramble, path));
+
+        let path = a_star(position, &Solved, &pruning, &table, HeapList::new(), ()).unwrap();
+        assert_eq!(path.len(), optimal);
+
+        let weight = Weight::new(3, 2);
+        let path = weighted_a_star(
+            position,
+            &Solved,
+            &pruning,
+            &table,
+            weight,
+            HeapList::new(),
+            (),
+        )
+        .unwrap();
+        assert!(path.len() * 2 <= optimal * 3);
+        assert!(solves(&scramble, path));
+
+        let path = beam_search(position, &Solved, &pruning, &table, 64, 20, ()).unwrap();
+        assert!(path.len() >= optimal);
+        assert!(solves(&scramble, path));
+    }
+
+    /// A path of vertices, each leading only to the next.
+    #[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+    struct Line(u16);
+
+    impl Search for Line {
+        type Iter = std::option::IntoIter<(Self, Self::Edge)>;
+        type Edge = ();
+        type HeuristicData = ();
+        type TransitionData = ();
+
+        fn heuristic(self, _: &Self::HeuristicData) -> Depth {
+            0
+        }
+
+        fn transition(self, _: &Self::TransitionData) -> Self::Iter {
+            Some((Line(self.0 + 1), ())).into_iter()
+        }
+    }
+
+    #[test]
+    pub fn long_paths() {
+        // Far beyond the largest depth
+        let goal = |line: &Line| line.0 == 1000;
+        let path = a_star(Line(0), &goal, &(), &(), BucketList::new(), ()).unwrap();
+        assert_eq!(path.len(), 1000);
+        let path = weighted_a_star(
+            Line(0),
+            &goal,
+            &(),
+            &(),
+            Weight::new(3, 1),
+            HeapList::new(),
+            (),
+        );
+        assert_eq!(path.unwrap().len(), 1000);
+    }
+}
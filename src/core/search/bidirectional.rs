@@ -0,0 +1,198 @@
+use super::{Depth, Observer, Search};
+use std::collections::hash_map::{Entry, HashMap};
+use std::hash::Hash;
+
+/// The predecessors of a vertex on its shortest paths from the roots, with the connecting edges.
+type Parents<T> = Vec<(T, <T as Search>::Edge)>;
+
+/// The vertices reached from one side of a bidirectional search, with every shortest way of
+/// reaching each of them.
+struct Frontier<T: Search> {
+    visited: HashMap<T, (Depth, Parents<T>)>,
+    layer: Vec<T>,
+    depth: Depth,
+}
+
+impl<T: Search + Hash> Frontier<T> {
+    fn new(roots: impl IntoIterator<Item = T>) -> Self {
+        let mut visited = HashMap::new();
+        let mut layer = Vec::new();
+
+        for root in roots {
+            if visited.insert(root, (0, Vec::new())).is_none() {
+                layer.push(root);
+            }
+        }
+
+        Self {
+            visited,
+            layer,
+            depth: 0,
+        }
+    }
+
+    /// Replace the outermost layer by all of its unvisited successors, returning those which
+    /// were already reached by the other side.
+    fn expand(
+        &mut self,
+        other: &Self,
+        transition_data: &T::TransitionData,
+        observer: &mut impl Observer,
+    ) -> Vec<T> {
+        let depth = self.depth + 1;
+        let mut layer = Vec::new();
+
+        for &vertex in &self.layer {
+            observer.node_expanded(self.depth);
+            for (next, edge) in vertex.transition(transition_data) {
+                match self.visited.entry(next) {
+                    Entry::Vacant(entry) => {
+                        entry.insert((depth, vec![(vertex, edge)]));
+                        layer.push(next);
+                    }
+                    Entry::Occupied(mut entry) if entry.get().0 == depth => {
+                        entry.get_mut().1.push((vertex, edge));
+                    }
+                    Entry::Occupied(_) => {}
+                }
+            }
+        }
+
+        self.layer = layer;
+        self.depth = depth;
+
+        self.layer
+            .iter()
+            .filter(|vertex| other.visited.contains_key(vertex))
+            .copied()
+            .collect()
+    }
+
+    /// Every shortest sequence of edges leading from a root to `vertex`.
+    fn paths(&self, vertex: &T) -> Vec<Vec<T::Edge>> {
+        let (_, parents) = &self.visited[vertex];
+
+        if parents.is_empty() {
+            return vec![Vec::new()];
+        }
+
+        parents
+            .iter()
+            .flat_map(|(parent, edge)| {
+                self.paths(parent).into_iter().map(move |mut path| {
+                    path.push(*edge);
+                    path
+                })
+            })
+            .collect()
+    }
+}
+
+/// Find every shortest path from `start` to any of `goals` of length at most `max_depth`, by
+/// expanding breadth-first from both ends and joining the two where they meet. This needs no
+/// heuristic, so it is well suited to short distances on puzzles without good pruning tables.
+///
+/// The goal side is expanded with the same transitions as the start side, so the edges must be
+/// invertible: `inverse` maps each edge to one which undoes it.
+pub fn bidirectional<T: Search + Hash>(
+    start: T,
+    goals: impl IntoIterator<Item = T>,
+    transition_data: &T::TransitionData,
+    inverse: impl Fn(T::Edge) -> T::Edge,
+    max_depth: Depth,
+    mut observer: impl Observer,
+) -> Vec<Vec<T::Edge>> {
+    let mut forward = Frontier::new(std::iter::once(start));
+    let mut backward = Frontier::new(goals);
+
+    let mut meetings = if backward.visited.contains_key(&start) {
+        vec![start]
+    } else {
+        Vec::new()
+    };
+
+    while meetings.is_empty() {
+        let bound = forward.depth + backward.depth;
+        if bound >= max_depth || forward.layer.is_empty() || backward.layer.is_empty() {
+            return Vec::new();
+        }
+
+        observer.iteration_started(bound + 1);
+        meetings = if forward.layer.len() <= backward.layer.len() {
+            forward.expand(&backward, transition_data, &mut observer)
+        } else {
+            backward.expand(&forward, transition_data, &mut observer)
+        };
+        observer.iteration_completed(bound + 1);
+    }
+
+    let mut solutions = Vec::new();
+    for meeting in &meetings {
+        let tails = backward
+            .paths(meeting)
+            .into_iter()
+            .map(|path| path.into_iter().rev().map(&inverse).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        for head in forward.paths(meeting) {
+            for tail in &tails {
+                let mut path = head.clone();
+                path.extend_from_slice(tail);
+                observer.solution_found(path.len());
+                solutions.push(path);
+            }
+        }
+    }
+
+    solutions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::kociemba::{phase1, Phase1};
+    use crate::core::search::{ida_iter, Solved};
+    use crate::metric::Htm::{self, *};
+    use crate::puzzle::Cube3x3;
+    use std::convert::TryFrom;
+
+    #[test]
+    pub fn full_cube() {
+        let position = Cube3x3::from_slice(&[R1, U1, R3, U3]);
+        let solutions = bidirectional(position, vec![Cube3x3::default()], &(), Htm::inverse, 6, ());
+        assert_eq!(solutions, vec![vec![U1, R1, U3, R3]]);
+
+        let position = Cube3x3::from_slice(&[R1, L1]);
+        let mut solutions =
+            bidirectional(position, vec![Cube3x3::default()], &(), Htm::inverse, 6, ());
+        solutions.sort_by_key(|path| path.iter().map(|&htm| htm as usize).collect::<Vec<_>>());
+        assert_eq!(solutions, vec![vec![R3, L3], vec![L3, R3]]);
+
+        let position = Cube3x3::from_slice(&[F1, B2, D1, R3, U2]);
+        assert!(
+            bidirectional(position, vec![Cube3x3::default()], &(), Htm::inverse, 4, ()).is_empty()
+        );
+    }
+
+    #[test]
+    pub fn coordinates() {
+        let table = phase1::Table::new();
+        let pruning = phase1::PruningTable::new(&table);
+        let inverse = |ix: usize| Htm::try_from(ix).unwrap().inverse() as usize;
+
+        let position: Phase1 = [R1, F3, L2, U1, B1].iter().collect();
+        let solutions = bidirectional(position, vec![Phase1::default()], &table, inverse, 8, ());
+        let length = ida_iter(position, &Solved, &pruning, &table, None)
+            .next()
+            .unwrap()
+            .len();
+
+        assert!(!solutions.is_empty());
+        assert!(solutions.iter().all(|path| path.len() == length));
+        for path in solutions {
+            let cube = Cube3x3::from_slice(&[R1, F3, L2, U1, B1]);
+            let cube = cube.apply_seq(path.into_iter().map(|e| Htm::ALL[e]));
+            assert_eq!(Phase1::from(&cube), Phase1::default());
+        }
+    }
+}
@@ -0,0 +1,183 @@
+use super::{Depth, Goal, Observer, Search};
+
+/// Relations between edges which make different paths equivalent, used to remove duplicates when
+/// enumerating every path of a given length.
+pub trait Equivalence<E> {
+    /// Whether following `a` by `b` is the same as following `b` by `a`. This is expected to be
+    /// an equivalence relation, as with turns of faces on the same axis.
+    fn commutes(&self, a: E, b: E) -> bool;
+
+    /// Whether following `a` by `b` can be replaced by a shorter path, as with two turns of the
+    /// same face.
+    fn redundant(&self, a: E, b: E) -> bool;
+}
+
+/// The trivial equivalence, under which every sequence of edges is distinct.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Distinct;
+
+impl<E> Equivalence<E> for Distinct {
+    fn commutes(&self, _: E, _: E) -> bool {
+        false
+    }
+
+    fn redundant(&self, _: E, _: E) -> bool {
+        false
+    }
+}
+
+/// An equivalence given by a pair of functions, e.g. `Relations(Htm::commutes, Htm::same_face)`.
+#[derive(Clone, Copy, Debug)]
+pub struct Relations<C, R>(pub C, pub R);
+
+impl<E, C: Fn(E, E) -> bool, R: Fn(E, E) -> bool> Equivalence<E> for Relations<C, R> {
+    fn commutes(&self, a: E, b: E) -> bool {
+        (self.0)(a, b)
+    }
+
+    fn redundant(&self, a: E, b: E) -> bool {
+        (self.1)(a, b)
+    }
+}
+
+/// Whether `path` followed by `edge` is the canonical representative of its class: it contains no
+/// redundant pair of edges which could be made adjacent, and `edge` cannot be commuted backwards
+/// past a greater edge.
+fn canonical<E: Copy + Ord>(path: &[E], edge: E, equivalence: &impl Equivalence<E>) -> bool {
+    if let Some(&last) = path.last() {
+        if equivalence.redundant(last, edge) {
+            return false;
+        }
+    }
+
+    for &previous in path.iter().rev() {
+        if !equivalence.commutes(previous, edge) {
+            break;
+        }
+        if previous > edge || equivalence.redundant(previous, edge) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// The fixed parameters of an exhaustive search for paths of a given length.
+pub(super) struct Enumeration<'a, T: Search, G: ?Sized, Q, O> {
+    pub goal: &'a G,
+    pub heuristic_data: &'a T::HeuristicData,
+    pub transition_data: &'a T::TransitionData,
+    pub length: Depth,
+    pub equivalence: &'a Q,
+    pub observer: O,
+}
+
+impl<T, G, Q, O> Enumeration<'_, T, G, Q, O>
+where
+    T: Search,
+    T::Edge: Ord,
+    G: Goal<T> + ?Sized,
+    Q: Equivalence<T::Edge>,
+    O: Observer,
+{
+    /// Call `visit` with every canonical extension of `path`, which has cost `depth`, by a path
+    /// from `vertex` to the goal, such that the total cost is exactly `length`.
+    pub fn run(
+        &mut self,
+        vertex: T,
+        depth: Depth,
+        path: &mut Vec<T::Edge>,
+        visit: &mut impl FnMut(&[T::Edge]),
+    ) {
+        if depth == self.length {
+            if self.goal.is_goal(&vertex) {
+                self.observer.solution_found(path.len());
+                visit(path);
+            }
+            return;
+        }
+
+        let estimate = vertex.heuristic(self.heuristic_data);
+        self.observer.heuristic_evaluated(estimate);
+        if depth + estimate > self.length {
+            self.observer.node_pruned(depth);
+            return;
+        }
+
+        self.observer.node_expanded(depth);
+        for (next, edge) in vertex.transition(self.transition_data) {
+            let cost = T::cost(edge);
+            if depth + cost <= self.length && canonical(path, edge, self.equivalence) {
+                path.push(edge);
+                self.run(next, depth + cost, path, visit);
+                path.pop();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::kociemba::{phase1, Phase1};
+    use crate::core::search::{ida_iter, Solved};
+    use crate::metric::Htm::{self, *};
+    use crate::puzzle::Cube3x3;
+    use std::convert::TryFrom;
+
+    #[test]
+    pub fn full_cube() {
+        let htm = Relations(Htm::commutes, Htm::same_face);
+
+        let position = Cube3x3::from_slice(&[R1, L1]);
+        let mut solutions = position.solutions(&Solved, &(), &(), 2, &Distinct, ());
+        solutions.sort();
+        assert_eq!(solutions, vec![vec![R3, L3], vec![L3, R3]]);
+        assert_eq!(
+            position.solutions(&Solved, &(), &(), 2, &htm, ()),
+            vec![vec![R3, L3]]
+        );
+
+        let position = Cube3x3::from_slice(&[U1, D1, U3]);
+        assert_eq!(position.count_solutions(&Solved, &(), &(), 1, &htm, ()), 1);
+        assert_eq!(position.count_solutions(&Solved, &(), &(), 3, &htm, ()), 0);
+        assert!(position.count_solutions(&Solved, &(), &(), 3, &Distinct, ()) > 0);
+    }
+
+    #[test]
+    pub fn coordinates() {
+        let table = phase1::Table::new();
+        let pruning = phase1::PruningTable::new(&table);
+        let htm = |ix: usize| Htm::try_from(ix).unwrap();
+        let equivalence = Relations(
+            |a, b| htm(a).commutes(htm(b)),
+            |a, b| htm(a).same_face(htm(b)),
+        );
+
+        let scramble = [F1, B1, R1, L1];
+        let position: Phase1 = scramble.iter().collect();
+        let length = ida_iter(position, &Solved, &pruning, &table, None)
+            .next()
+            .unwrap()
+            .len() as Depth;
+        let all = position.solutions(&Solved, &pruning, &table, length, &Distinct, ());
+        let canonical = position.solutions(&Solved, &pruning, &table, length, &equivalence, ());
+
+        assert!(!canonical.is_empty());
+        assert!(canonical.len() < all.len());
+        assert!(canonical.iter().all(|path| all.contains(path)));
+        assert_eq!(
+            position.count_solutions(&Solved, &pruning, &table, length, &equivalence, ()),
+            canonical.len()
+        );
+        assert_eq!(
+            position.optimal_solutions(&Solved, &pruning, &table, None, &equivalence, ()),
+            canonical
+        );
+        for path in canonical {
+            let cube = Cube3x3::from_slice(&scramble);
+            let cube = cube.apply_seq(path.into_iter().map(|e| Htm::ALL[e]));
+            assert_eq!(Phase1::from(&cube), Phase1::default());
+        }
+    }
+}
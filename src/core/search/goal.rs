@@ -0,0 +1,49 @@
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// The set of vertices at which a search may stop. Besides the explicit types below, any closure
+/// `Fn(&T) -> bool` is a goal, so a goal may be an arbitrary predicate such as "solved up to a
+/// final turn of one face".
+pub trait Goal<T> {
+    fn is_goal(&self, vertex: &T) -> bool;
+}
+
+/// The goal consisting only of the default vertex, which for puzzles is the solved state.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Solved;
+
+impl<T: Default + Eq> Goal<T> for Solved {
+    fn is_goal(&self, vertex: &T) -> bool {
+        *vertex == T::default()
+    }
+}
+
+impl<T, F: Fn(&T) -> bool> Goal<T> for F {
+    fn is_goal(&self, vertex: &T) -> bool {
+        self(vertex)
+    }
+}
+
+impl<T: Eq> Goal<T> for [T] {
+    fn is_goal(&self, vertex: &T) -> bool {
+        self.contains(vertex)
+    }
+}
+
+impl<T: Eq, const N: usize> Goal<T> for [T; N] {
+    fn is_goal(&self, vertex: &T) -> bool {
+        self.contains(vertex)
+    }
+}
+
+impl<T: Eq> Goal<T> for Vec<T> {
+    fn is_goal(&self, vertex: &T) -> bool {
+        self.contains(vertex)
+    }
+}
+
+impl<T: Eq + Hash> Goal<T> for HashSet<T> {
+    fn is_goal(&self, vertex: &T) -> bool {
+        self.contains(vertex)
+    }
+}
@@ -0,0 +1,554 @@
+mod best_first;
+mod bidirectional;
+mod enumerate;
+mod goal;
+mod observer;
+
+pub use best_first::{
+    a_star, beam_search, weighted_a_star, BucketList, HeapList, OpenList, Priority, Weight,
+};
+pub use bidirectional::bidirectional;
+pub use enumerate::{Distinct, Equivalence, Relations};
+pub use goal::{Goal, Solved};
+pub use observer::{Observer, Statistics};
+
+use enumerate::Enumeration;
+
+pub type Depth = u8;
+
+pub trait Search: Copy + Default + Eq + Sized {
+    type Edge: Copy;
+    type HeuristicData;
+    type TransitionData;
+    type Iter: Iterator<Item = (Self, Self::Edge)>;
+
+    /// A domain-specific heuristic which gives a *lower bound* on the distance from any vertex to
+    /// the goal vertex. If this is not a true lower bound, then suboptimal paths may be
+    /// returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - Any required data for the heuristic function, such as a pre-computed table
+    fn heuristic(self, data: &Self::HeuristicData) -> Depth;
+
+    /// A transition function which calculates the next vertices of the graph to search given the
+    /// current vertex.
+    fn transition(self, data: &Self::TransitionData) -> Self::Iter; //Vec<(Self, Self::Edge)>;
+
+    /// The cost of following `edge`, in which depths and path lengths are measured. This is one
+    /// for every edge unless overridden, e.g. for half turns in the quarter turn metric, and the
+    /// heuristic must be a lower bound on the total cost.
+    fn cost(_edge: Self::Edge) -> Depth {
+        1
+    }
+
+    /// A basic IDA* implementation, if the provided heuristic is a true lower bound, the paths it
+    /// finds are the shortest possible. The `observer` is notified of the progress of the search.
+    ///
+    /// The heuristic must be a lower bound on the distance to the nearest vertex of `goal`, e.g.
+    /// a pruning table seeded from every goal vertex.
+    fn ida_star<G: Goal<Self> + ?Sized>(
+        &self,
+        goal: &G,
+        heuristic_data: &Self::HeuristicData,
+        transition_data: &Self::TransitionData,
+        max_depth: Depth,
+        mut observer: impl Observer,
+    ) -> Option<(Vec<Self>, Vec<Self::Edge>)> {
+        let (mut path, mut edges) = (0..=max_depth).find_map(|depth| {
+            observer.iteration_started(depth);
+            let res = self.dfs(
+                goal,
+                heuristic_data,
+                transition_data,
+                0,
+                depth,
+                &mut observer,
+            );
+            if res.is_none() {
+                observer.iteration_completed(depth);
+            };
+            res
+        })?;
+        path.reverse();
+        edges.reverse();
+        Some((path, edges))
+    }
+
+    /// A depth-specific DFS implementation intended as a subroutine for IDA*.
+    fn dfs<G: Goal<Self> + ?Sized>(
+        &self,
+        goal: &G,
+        heuristic_data: &Self::HeuristicData,
+        transition_data: &Self::TransitionData,
+        depth: Depth,
+        max_depth: Depth,
+        observer: &mut impl Observer,
+    ) -> Option<(Vec<Self>, Vec<Self::Edge>)> {
+        if goal.is_goal(self) {
+            observer.solution_found(depth as usize);
+            return Some((vec![*self], Vec::new()));
+        }
+
+        let estimate = self.heuristic(heuristic_data);
+        observer.heuristic_evaluated(estimate);
+
+        if depth + estimate < max_depth {
+            observer.node_expanded(depth);
+            self.transition(transition_data)
+                .filter(|&(_, edge)| depth + Self::cost(edge) <= max_depth)
+                .find_map(|(vertex, edge)| {
+                    let (mut path, mut edges) = vertex.dfs(
+                        goal,
+                        heuristic_data,
+                        transition_data,
+                        depth + Self::cost(edge),
+                        max_depth,
+                        observer,
+                    )?;
+                    path.push(*self);
+                    edges.push(edge);
+                    Some((path, edges))
+                })
+        } else {
+            observer.node_pruned(depth);
+            None
+        }
+    }
+
+    /// Every path of total cost exactly `length` from this vertex to `goal`. Paths which differ
+    /// only by the order of commuting edges are reported once, and paths containing a redundant
+    /// pair of edges are left out, as given by `equivalence`; with `Distinct` every path is
+    /// reported.
+    fn solutions<G, Q>(
+        &self,
+        goal: &G,
+        heuristic_data: &Self::HeuristicData,
+        transition_data: &Self::TransitionData,
+        length: Depth,
+        equivalence: &Q,
+        observer: impl Observer,
+    ) -> Vec<Vec<Self::Edge>>
+    where
+        G: Goal<Self> + ?Sized,
+        Q: Equivalence<Self::Edge>,
+        Self::Edge: Ord,
+    {
+        let mut solutions = Vec::new();
+        Enumeration {
+            goal,
+            heuristic_data,
+            transition_data,
+            length,
+            equivalence,
+            observer,
+        }
+        .run(*self, 0, &mut Vec::new(), &mut |path| {
+            solutions.push(path.to_vec())
+        });
+        solutions
+    }
+
+    /// Every shortest path from this vertex to `goal` no longer than `max_depth`, deduplicated as
+    /// in `solutions`. The lengths are tried in increasing order, as in `ida_star`.
+    fn optimal_solutions<G, Q>(
+        &self,
+        goal: &G,
+        heuristic_data: &Self::HeuristicData,
+        transition_data: &Self::TransitionData,
+        max_depth: Option<Depth>,
+        equivalence: &Q,
+        mut observer: impl Observer,
+    ) -> Vec<Vec<Self::Edge>>
+    where
+        G: Goal<Self> + ?Sized,
+        Q: Equivalence<Self::Edge>,
+        Self::Edge: Ord,
+    {
+        let max_depth = max_depth.unwrap_or(Depth::MAX);
+        for length in self.heuristic(heuristic_data)..=max_depth {
+            observer.iteration_started(length);
+            let solutions = self.solutions(
+                goal,
+                heuristic_data,
+                transition_data,
+                length,
+                equivalence,
+                &mut observer,
+            );
+            observer.iteration_completed(length);
+            if !solutions.is_empty() {
+                return solutions;
+            }
+        }
+        Vec::new()
+    }
+
+    /// The number of paths `solutions` would report, without storing them.
+    fn count_solutions<G, Q>(
+        &self,
+        goal: &G,
+        heuristic_data: &Self::HeuristicData,
+        transition_data: &Self::TransitionData,
+        length: Depth,
+        equivalence: &Q,
+        observer: impl Observer,
+    ) -> usize
+    where
+        G: Goal<Self> + ?Sized,
+        Q: Equivalence<Self::Edge>,
+        Self::Edge: Ord,
+    {
+        let mut count = 0;
+        Enumeration {
+            goal,
+            heuristic_data,
+            transition_data,
+            length,
+            equivalence,
+            observer,
+        }
+        .run(*self, 0, &mut Vec::new(), &mut |_| count += 1);
+        count
+    }
+}
+
+/// Iterate over every path from `start` to the goal in order of increasing length, by repeatedly
+/// running `dfs_iter` with an increasing depth.
+pub fn ida_iter<'a, T: 'a + Search, G: Goal<T> + ?Sized>(
+    start: T,
+    goal: &'a G,
+    heuristic_data: &'a T::HeuristicData,
+    transition_data: &'a T::TransitionData,
+    max_depth: Option<Depth>,
+) -> IDAIterator<'a, T, G> {
+    let max_depth = max_depth.unwrap_or(Depth::MAX);
+
+    IDAIterator {
+        search: Some(dfs_iter(start, goal, heuristic_data, transition_data, 0)),
+        max_depth,
+    }
+}
+
+/// The edges of a shortest path from `start` to a vertex of heuristic zero, found by following
+/// at each vertex an edge which lowers the heuristic by its cost. This needs no search when the
+/// heuristic is the exact distance, as from a pruning table of every reachable position, and
+/// panics if the heuristic is not exact.
+pub fn walk<T: Search>(
+    start: T,
+    heuristic_data: &T::HeuristicData,
+    transition_data: &T::TransitionData,
+) -> Vec<T::Edge> {
+    let mut vertex = start;
+    let mut path = Vec::new();
+
+    let mut distance = vertex.heuristic(heuristic_data);
+    while distance > 0 {
+        let (next, edge) = vertex
+            .transition(transition_data)
+            .find(|&(next, edge)| next.heuristic(heuristic_data) + T::cost(edge) == distance)
+            .expect("The heuristic is not the exact distance");
+
+        path.push(edge);
+        vertex = next;
+        distance -= T::cost(edge);
+    }
+
+    path
+}
+
+pub struct IDAIterator<'a, T: Search, G: Goal<T> + ?Sized = Solved, O: Observer = ()> {
+    search: Option<DFSIterator<'a, T, G, O>>,
+    max_depth: Depth,
+}
+
+impl<'a, T: Search, G: Goal<T> + ?Sized, O: Observer> IDAIterator<'a, T, G, O> {
+    /// Report the progress of every iteration of the search to `observer`.
+    pub fn with_observer<P: Observer>(self, observer: P) -> IDAIterator<'a, T, G, P> {
+        IDAIterator {
+            search: self.search.map(|search| search.with_observer(observer)),
+            max_depth: self.max_depth,
+        }
+    }
+}
+
+impl<T: Search, G: Goal<T> + ?Sized, O: Observer> Iterator for IDAIterator<'_, T, G, O> {
+    type Item = Vec<(T, T::Edge)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(path) = self.search.as_mut()?.next() {
+                break Some(path);
+            }
+
+            let search = self.search.take()?;
+            if search.target_depth >= self.max_depth {
+                break None;
+            }
+
+            let DFSIterator {
+                start,
+                goal,
+                heuristic_data,
+                transition_data,
+                target_depth,
+                ..
+            } = search;
+            let observer = search.into_observer();
+
+            let next = dfs_iter(
+                start,
+                goal,
+                heuristic_data,
+                transition_data,
+                target_depth + 1,
+            );
+            self.search = Some(next.with_observer(observer));
+        }
+    }
+}
+
+pub fn dfs_iter<'a, T: Search, G: Goal<T> + ?Sized>(
+    start: T,
+    goal: &'a G,
+    heuristic_data: &'a T::HeuristicData,
+    transition_data: &'a T::TransitionData,
+    depth: Depth,
+) -> DFSIterator<'a, T, G> {
+    let future = start.transition(transition_data);
+    DFSIterator {
+        start,
+        goal,
+        future,
+        heuristic_data,
+        transition_data,
+        current_depth: 0,
+        target_depth: depth,
+        path: Vec::new(),
+        nodes: 0,
+        observer: (),
+        started: false,
+        exhausted: false,
+    }
+}
+
+pub struct DFSIterator<'a, T: Search, G: Goal<T> + ?Sized = Solved, O: Observer = ()> {
+    start: T,
+    goal: &'a G,
+    future: T::Iter,
+    heuristic_data: &'a T::HeuristicData,
+    transition_data: &'a T::TransitionData,
+    current_depth: Depth,
+    target_depth: Depth,
+    path: Vec<(T, T::Edge, T::Iter)>,
+    nodes: usize,
+    observer: O,
+    started: bool,
+    exhausted: bool,
+}
+
+impl<'a, T: Search, G: Goal<T> + ?Sized, O: Observer> DFSIterator<'a, T, G, O> {
+    pub fn nodes(&self) -> usize {
+        self.nodes
+    }
+
+    /// Report the progress of the search to `observer`.
+    pub fn with_observer<P: Observer>(self, observer: P) -> DFSIterator<'a, T, G, P> {
+        DFSIterator {
+            start: self.start,
+            goal: self.goal,
+            future: self.future,
+            heuristic_data: self.heuristic_data,
+            transition_data: self.transition_data,
+            current_depth: self.current_depth,
+            target_depth: self.target_depth,
+            path: self.path,
+            nodes: self.nodes,
+            observer,
+            started: self.started,
+            exhausted: self.exhausted,
+        }
+    }
+
+    pub fn into_observer(self) -> O {
+        self.observer
+    }
+
+    /// Check `vertex` if it is at the target depth, or descend into it if the goal may be reached
+    /// within the target depth from there.
+    fn visit(&mut self, vertex: T, edge: T::Edge) -> Option<<Self as Iterator>::Item> {
+        let depth = self.current_depth + T::cost(edge);
+
+        if depth == self.target_depth {
+            self.nodes += 1;
+            if self.goal.is_goal(&vertex) {
+                let mut path = self
+                    .path
+                    .iter()
+                    .map(|(x, y, _)| (*x, *y))
+                    .collect::<Vec<_>>();
+                path.push((vertex, edge));
+                self.observer.solution_found(path.len());
+                return Some(path);
+            }
+        } else if depth < self.target_depth {
+            let estimate = vertex.heuristic(self.heuristic_data);
+            self.observer.heuristic_evaluated(estimate);
+
+            if estimate + depth <= self.target_depth {
+                self.observer.node_expanded(depth);
+                let past =
+                    std::mem::replace(&mut self.future, vertex.transition(self.transition_data));
+                self.path.push((vertex, edge, past));
+                self.current_depth = depth;
+            } else {
+                self.observer.node_pruned(depth);
+            }
+        }
+
+        None
+    }
+
+    /// The search of depth zero consists only of checking whether the start is the goal.
+    fn trivial(&mut self) -> Option<<Self as Iterator>::Item> {
+        self.exhausted = true;
+        self.nodes += 1;
+
+        let solved = self.goal.is_goal(&self.start);
+        if solved {
+            self.observer.solution_found(0);
+        };
+        self.observer.iteration_completed(0);
+
+        solved.then(Vec::new)
+    }
+}
+
+impl<T: Search, G: Goal<T> + ?Sized, O: Observer> Iterator for DFSIterator<'_, T, G, O> {
+    type Item = Vec<(T, T::Edge)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.exhausted {
+            return None;
+        }
+
+        if !self.started {
+            self.started = true;
+            self.observer.iteration_started(self.target_depth);
+            if self.target_depth == 0 {
+                return self.trivial();
+            }
+            self.observer.node_expanded(0);
+        }
+
+        loop {
+            if let Some((vertex, edge)) = self.future.next() {
+                if let Some(path) = self.visit(vertex, edge) {
+                    break Some(path);
+                }
+            } else if let Some((_vertex, edge, past)) = self.path.pop() {
+                self.future = past;
+                self.current_depth -= T::cost(edge);
+            } else {
+                self.exhausted = true;
+                self.observer.iteration_completed(self.target_depth);
+                break None;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The cyclic group of order 12 generated by steps of one and five in either direction.
+    #[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+    struct Clock(u8);
+
+    impl Search for Clock {
+        type Edge = i8;
+        type HeuristicData = ();
+        type TransitionData = ();
+        type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+
+        fn heuristic(self, _: &()) -> Depth {
+            0
+        }
+
+        fn transition(self, _: &()) -> Self::Iter {
+            let Clock(x) = self;
+            [1, -1, 5, -5]
+                .iter()
+                .map(|&step| (Clock(((x as i8 + step).rem_euclid(12)) as u8), step))
+                .collect::<Vec<_>>()
+                .into_iter()
+        }
+    }
+
+    #[test]
+    pub fn ida_iter_lengths() {
+        let lengths = ida_iter(Clock(0), &Solved, &(), &(), Some(2))
+            .map(|path| path.len())
+            .collect::<Vec<_>>();
+        assert_eq!(lengths, vec![0, 2, 2, 2, 2]);
+
+        let path = ida_iter(Clock(3), &Solved, &(), &(), None).next().unwrap();
+        assert_eq!(path.len(), 3);
+    }
+
+    #[test]
+    pub fn goals() {
+        let path = ida_iter(Clock(3), &[Clock(5), Clock(11)], &(), &(), None)
+            .next()
+            .unwrap();
+        assert_eq!(path.len(), 2);
+        assert!(matches!(
+            path.last(),
+            Some((Clock(5), _)) | Some((Clock(11), _))
+        ));
+
+        let quarter = |&Clock(x): &Clock| x % 3 == 0;
+        assert_eq!(
+            ida_iter(Clock(4), &quarter, &(), &(), None)
+                .next()
+                .unwrap()
+                .len(),
+            1
+        );
+
+        let (path, _) = Clock(7).ida_star(&vec![Clock(7)], &(), &(), 4, ()).unwrap();
+        assert_eq!(path, vec![Clock(7)]);
+    }
+
+    #[test]
+    pub fn statistics() {
+        let mut stats = Statistics::new();
+        let path = ida_iter(Clock(6), &Solved, &(), &(), None)
+            .with_observer(&mut stats)
+            .next()
+            .unwrap();
+
+        assert_eq!(path.len(), 2);
+        assert_eq!(stats.solutions, 1);
+        // The iteration which found the solution is recorded although it was not completed
+        let bounds = stats.iterations.iter().map(|&(bound, _)| bound);
+        assert_eq!(bounds.collect::<Vec<_>>(), vec![0, 1, 2]);
+        assert_eq!(stats.expanded, vec![2, 1]);
+
+        let mut stats = Statistics::new();
+        let (_, edges) = Clock(6).ida_star(&Solved, &(), &(), 4, &mut stats).unwrap();
+        assert_eq!(edges.len(), 2);
+        assert_eq!(stats.solutions, 1);
+        assert_eq!(stats.iterations.len(), 3);
+
+        // The trivial search is recorded once, although it both finds a solution and completes
+        let mut stats = Statistics::new();
+        let path = ida_iter(Clock(0), &Solved, &(), &(), None)
+            .with_observer(&mut stats)
+            .next()
+            .unwrap();
+        assert!(path.is_empty());
+        assert_eq!(stats.iterations.len(), 1);
+    }
+}
@@ -0,0 +1,161 @@
+use super::Depth;
+use std::time::Duration;
+#[cfg(feature = "timing")]
+use std::time::Instant;
+
+/// Receives events from a running search. Every method has an empty default implementation, so
+/// an observer only needs to override the events it is interested in.
+pub trait Observer {
+    /// Called when a new iteration of an iterative deepening search begins with the given bound.
+    fn iteration_started(&mut self, _bound: Depth) {}
+
+    /// Called when every vertex within the given bound has been searched.
+    fn iteration_completed(&mut self, _bound: Depth) {}
+
+    /// Called when the successors of a vertex at the given depth are generated.
+    fn node_expanded(&mut self, _depth: Depth) {}
+
+    /// Called with the result of every evaluation of the heuristic.
+    fn heuristic_evaluated(&mut self, _value: Depth) {}
+
+    /// Called when a vertex at the given depth is discarded because of its heuristic value.
+    fn node_pruned(&mut self, _depth: Depth) {}
+
+    /// Called with the length of every path to the goal that the search reports.
+    fn solution_found(&mut self, _length: usize) {}
+}
+
+/// The observer which ignores every event.
+impl Observer for () {}
+
+impl<O: Observer + ?Sized> Observer for &mut O {
+    fn iteration_started(&mut self, bound: Depth) {
+        (**self).iteration_started(bound)
+    }
+
+    fn iteration_completed(&mut self, bound: Depth) {
+        (**self).iteration_completed(bound)
+    }
+
+    fn node_expanded(&mut self, depth: Depth) {
+        (**self).node_expanded(depth)
+    }
+
+    fn heuristic_evaluated(&mut self, value: Depth) {
+        (**self).heuristic_evaluated(value)
+    }
+
+    fn node_pruned(&mut self, depth: Depth) {
+        (**self).node_pruned(depth)
+    }
+
+    fn solution_found(&mut self, length: usize) {
+        (**self).solution_found(length)
+    }
+}
+
+/// Measures the wall-clock time of an iteration. Without the `timing` feature every iteration
+/// takes no time, as `Instant::now` panics on targets without a clock such as
+/// `wasm32-unknown-unknown`.
+#[derive(Clone, Copy, Debug)]
+struct Stopwatch {
+    #[cfg(feature = "timing")]
+    start: Instant,
+}
+
+impl Stopwatch {
+    fn start() -> Self {
+        Self {
+            #[cfg(feature = "timing")]
+            start: Instant::now(),
+        }
+    }
+
+    #[cfg(feature = "timing")]
+    fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    #[cfg(not(feature = "timing"))]
+    fn elapsed(&self) -> Duration {
+        Duration::default()
+    }
+}
+
+/// An observer which collects summary statistics of a search, for comparing heuristics and
+/// tables against each other.
+#[derive(Clone, Debug, Default)]
+pub struct Statistics {
+    /// The number of vertices expanded at each depth of the search tree.
+    pub expanded: Vec<usize>,
+    pub heuristic_evaluations: usize,
+    pub prunes: usize,
+    pub solutions: usize,
+    /// The bound and wall-clock time of every iteration. An iteration which finds a solution is
+    /// recorded up to its first solution, and again in full if it is completed.
+    pub iterations: Vec<(Depth, Duration)>,
+    iteration: Option<(Depth, Stopwatch)>,
+    recorded: bool,
+}
+
+impl Statistics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The total number of vertices expanded over all depths.
+    pub fn nodes(&self) -> usize {
+        self.expanded.iter().sum()
+    }
+
+    /// The total time spent in recorded iterations.
+    pub fn elapsed(&self) -> Duration {
+        self.iterations.iter().map(|(_, time)| *time).sum()
+    }
+
+    /// Record the time of the current iteration so far, replacing the record of it made when it
+    /// found a solution.
+    fn record(&mut self, bound: Depth, time: Duration) {
+        match self.iterations.last_mut() {
+            Some(last) if self.recorded => *last = (bound, time),
+            _ => self.iterations.push((bound, time)),
+        }
+        self.recorded = true;
+    }
+}
+
+impl Observer for Statistics {
+    fn iteration_started(&mut self, bound: Depth) {
+        self.iteration = Some((bound, Stopwatch::start()));
+        self.recorded = false;
+    }
+
+    fn iteration_completed(&mut self, bound: Depth) {
+        if let Some((_, start)) = self.iteration.take() {
+            self.record(bound, start.elapsed());
+        }
+    }
+
+    fn node_expanded(&mut self, depth: Depth) {
+        let depth = depth as usize;
+        if self.expanded.len() <= depth {
+            self.expanded.resize(depth + 1, 0);
+        }
+        self.expanded[depth] += 1;
+    }
+
+    fn heuristic_evaluated(&mut self, _value: Depth) {
+        self.heuristic_evaluations += 1;
+    }
+
+    fn node_pruned(&mut self, _depth: Depth) {
+        self.prunes += 1;
+    }
+
+    fn solution_found(&mut self, _length: usize) {
+        self.solutions += 1;
+        if let (Some((bound, start)), false) = (self.iteration, self.recorded) {
+            self.record(bound, start.elapsed());
+        }
+    }
+}
@@ -9,5 +9,6 @@
 pub mod algorithm;
 pub mod core;
 pub mod metric;
+pub mod notation;
 pub mod puzzle;
 pub mod util;
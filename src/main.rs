@@ -9,9 +9,9 @@ fn random() {
 
     let tables = kociemba::generate_tables();
     let position = cubing::puzzle::Cube3x3::random_state();
-    let mut solution = position.kociemba(&tables, None);
-    solution.reverse();
-    println!("{}", Htm::format_seq(solution.into_iter()));
+    let solution = position.kociemba(&tables, None);
+    let scramble = Htm::invert_seq(&solution);
+    println!("{}", Htm::format_seq(scramble.into_iter()));
 }
 
 #[allow(dead_code)]
@@ -30,7 +30,7 @@ fn kociemba() {
     use kociemba::Phase1;
     let mut solver = cubing::core::search::dfs_iter(
         Phase1::from(&position),
-        Phase1::default(),
+        &cubing::core::search::Solved,
         &tables.1,
         &tables.0,
         11,
@@ -46,12 +46,15 @@ fn kociemba() {
         solver.nodes() as f64 / time.as_secs_f64(),
     );
 
+    use cubing::core::search::Statistics;
+    let (mut phase_1, mut phase_2) = (Statistics::new(), Statistics::new());
+
     println!("Solving superflip...",);
     let now = Instant::now();
-    let solution = position.kociemba(&tables, Some(22));
+    let solution = position.kociemba_observed(&tables, Some(22), &mut phase_1, &mut phase_2);
     println!("Solved in {:?}", now.elapsed());
-    println!(
-        "[{}]",
-        cubing::metric::Htm::format_seq(solution.into_iter())
-    );
+    println!("Phase 1: {:?}", phase_1);
+    println!("Phase 2: {:?}", phase_2);
+    let solution = cubing::notation::Alg::from(solution);
+    println!("[{}] ({} HTM)", solution, solution.htm());
 }
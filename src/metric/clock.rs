@@ -0,0 +1,163 @@
+use crate::notation::{parse_tokens, ParseError, Reason};
+use std::str::FromStr;
+
+/// The pins of the Clock which are up on the front. The WCA names a single pin at a corner, the
+/// two along a side and all four, and the others are written `/` for UR and DL, `\` for UL and
+/// DR, and in lower case for all but one pin, so that `ul` has every pin up but UL.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Pins {
+    UR,
+    DR,
+    DL,
+    UL,
+    U,
+    R,
+    D,
+    L,
+    All,
+    Slash,
+    Backslash,
+    NotUR,
+    NotDR,
+    NotDL,
+    NotUL,
+}
+
+const NAMES: [&str; Pins::COUNT] = [
+    "UR", "DR", "DL", "UL", "U", "R", "D", "L", "ALL", "/", "\\", "ur", "dr", "dl", "ul",
+];
+
+impl Pins {
+    pub const COUNT: usize = 15;
+
+    /// Every configuration with at least one pin up, starting with those named by the WCA.
+    #[rustfmt::skip]
+    pub const ALL: [Self; Self::COUNT] = {
+        use Pins::*;
+        [UR, DR, DL, UL, U, R, D, L, All, Slash, Backslash, NotUR, NotDR, NotDL, NotUL]
+    };
+
+    /// The configurations named by the WCA, in the order in which its scrambles turn them.
+    #[rustfmt::skip]
+    pub const WCA: [Self; 9] = {
+        use Pins::*;
+        [UR, DR, DL, UL, U, R, D, L, All]
+    };
+
+    /// The pins which are up, as a mask of the corners UR, DR, DL and UL from the lowest bit.
+    pub const fn mask(self) -> u8 {
+        match self {
+            Pins::UR => 0b0001,
+            Pins::DR => 0b0010,
+            Pins::DL => 0b0100,
+            Pins::UL => 0b1000,
+            Pins::U => 0b1001,
+            Pins::R => 0b0011,
+            Pins::D => 0b0110,
+            Pins::L => 0b1100,
+            Pins::All => 0b1111,
+            Pins::Slash => 0b0101,
+            Pins::Backslash => 0b1010,
+            Pins::NotUR => 0b1110,
+            Pins::NotDR => 0b1101,
+            Pins::NotDL => 0b1011,
+            Pins::NotUL => 0b0111,
+        }
+    }
+}
+
+impl std::fmt::Display for Pins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", NAMES[*self as usize])
+    }
+}
+
+/// The moves of the Clock in the notation of the WCA, with the other settings of the pins named
+/// as in `Pins`. `UR3+` pushes up the pins of `UR` and the others down, then turns a wheel by
+/// three hours, which turns the dials on the front connected to the pins clockwise, and `y2`
+/// turns the puzzle over from left to right.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Clock {
+    /// The pins and the number of hours clockwise, from -5 to 6.
+    Turn(Pins, i8),
+    Flip,
+}
+
+impl Clock {
+    /// The turn by `amount` hours clockwise, which may be any number and is reduced to the range
+    /// from -5 to 6.
+    pub const fn turn(pins: Pins, amount: i8) -> Self {
+        let amount = amount.rem_euclid(12);
+        Clock::Turn(pins, if amount > 6 { amount - 12 } else { amount })
+    }
+
+    pub const fn inverse(self) -> Self {
+        match self {
+            Clock::Turn(pins, amount) => Self::turn(pins, -amount),
+            Clock::Flip => Clock::Flip,
+        }
+    }
+
+    pub fn invert_seq(sequence: &[Self]) -> Vec<Self> {
+        sequence.iter().rev().map(|turn| turn.inverse()).collect()
+    }
+
+    /// Parse moves separated by whitespace, e.g. `UR3+ DL2- y2 ALL0+`.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        parse_tokens(str, Self::from_str)
+    }
+
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        iter.map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Clock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Clock::Turn(pins, amount) if amount < 0 => write!(f, "{}{}-", pins, -amount),
+            Clock::Turn(pins, amount) => write!(f, "{}{}+", pins, amount),
+            Clock::Flip => write!(f, "y2"),
+        }
+    }
+}
+
+impl FromStr for Clock {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseError::new(value, 0, value, reason);
+
+        if value == "y2" {
+            return Ok(Clock::Flip);
+        }
+
+        let digits = value
+            .find(|c: char| c.is_ascii_digit() || c == '+' || c == '-')
+            .unwrap_or(value.len());
+        let pins = NAMES
+            .iter()
+            .position(|&name| name == &value[..digits])
+            .map(|ix| Pins::ALL[ix])
+            .ok_or_else(|| error(Reason::UnknownFace))?;
+
+        let amount = &value[digits..];
+        let sign = match amount.chars().last() {
+            Some('+') => 1,
+            Some('-') => -1,
+            _ => return Err(error(Reason::BadAmount)),
+        };
+        let hours = match &amount[..amount.len() - 1] {
+            "" => None,
+            digits if digits.chars().all(|c| c.is_ascii_digit()) => digits.parse::<i8>().ok(),
+            _ => None,
+        }
+        .filter(|hours| (0..=6).contains(hours))
+        .ok_or_else(|| error(Reason::BadAmount))?;
+
+        Ok(Self::turn(pins, sign * hours))
+    }
+}
@@ -0,0 +1,263 @@
+use super::htm::{Corners, Face};
+use super::Htm;
+use crate::core::definitions as def;
+use crate::notation::{parse_tokens, ParseError, Reason};
+use std::str::FromStr;
+
+/// The wings, two for each edge of the 3x3x3 and in the same order. The first of the two is on
+/// the left, looking at the second face of the edge with the first one above it. Wings cannot
+/// flip in place, so only their permutation is tracked.
+pub type Wings = def::Array<24, 1>;
+
+/// The centres, four on each face in the order of `Face::ALL`. The centres of a face are
+/// interchangeable once solved, so a centre is in place whenever it is on its own face.
+pub type Centres = def::Array<24, 1>;
+
+/// Which of the layers at a face are turned: the outer layer alone, the inner layer alone
+/// (`2R` in SiGN) or both (`Rw` for the WCA, `r` in SiGN).
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub enum Layers {
+    Outer,
+    Inner,
+    Wide,
+}
+
+/// A turn of one or both layers at a face of the 4x4x4, by `amount` quarter turns clockwise.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Cube4x4 {
+    pub face: Face,
+    pub layers: Layers,
+    pub amount: u8,
+}
+
+const fn from_permutation(permutation: [u8; 24]) -> def::Array<24, 1> {
+    let mut elements = [(0, 0); 24];
+
+    let mut ix = 0;
+    while ix < 24 {
+        elements[ix].0 = permutation[ix];
+        ix += 1;
+    }
+
+    def::Array::new(elements)
+}
+
+impl Cube4x4 {
+    /// The number of turns of a single layer, which are the generators of the solvers.
+    pub const COUNT: usize = 36;
+
+    /// The turns of the outer layers, as for `Htm`, followed by those of the inner layers.
+    pub const ALL: [Self; Self::COUNT] = {
+        let mut all = [Self::new(Face::U, Layers::Outer, 1); Self::COUNT];
+
+        let mut ix = 0;
+        while ix < Self::COUNT {
+            let layers = if ix < Htm::COUNT {
+                Layers::Outer
+            } else {
+                Layers::Inner
+            };
+            all[ix] = Self::new(Face::ALL[ix % Htm::COUNT / 3], layers, ix as u8 % 3 + 1);
+            ix += 1;
+        }
+
+        all
+    };
+
+    #[rustfmt::skip]
+    pub const WING_MOVES: [Wings; Self::COUNT] = [
+        from_permutation([6, 7, 0, 1, 2, 3, 4, 5, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // U
+        from_permutation([4, 5, 6, 7, 0, 1, 2, 3, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // U2
+        from_permutation([2, 3, 4, 5, 6, 7, 0, 1, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // U'
+        from_permutation([16, 17, 2, 3, 4, 5, 6, 7, 22, 23, 10, 11, 12, 13, 14, 15, 8, 9, 18, 19, 20, 21, 0, 1]), // R
+        from_permutation([8, 9, 2, 3, 4, 5, 6, 7, 0, 1, 10, 11, 12, 13, 14, 15, 22, 23, 18, 19, 20, 21, 16, 17]), // R2
+        from_permutation([22, 23, 2, 3, 4, 5, 6, 7, 16, 17, 10, 11, 12, 13, 14, 15, 0, 1, 18, 19, 20, 21, 8, 9]), // R'
+        from_permutation([0, 1, 19, 18, 4, 5, 6, 7, 8, 9, 17, 16, 12, 13, 14, 15, 3, 2, 11, 10, 20, 21, 22, 23]), // F
+        from_permutation([0, 1, 10, 11, 4, 5, 6, 7, 8, 9, 2, 3, 12, 13, 14, 15, 18, 19, 16, 17, 20, 21, 22, 23]), // F2
+        from_permutation([0, 1, 17, 16, 4, 5, 6, 7, 8, 9, 19, 18, 12, 13, 14, 15, 11, 10, 3, 2, 20, 21, 22, 23]), // F'
+        from_permutation([0, 1, 2, 3, 20, 21, 6, 7, 8, 9, 10, 11, 18, 19, 14, 15, 16, 17, 4, 5, 12, 13, 22, 23]), // L
+        from_permutation([0, 1, 2, 3, 12, 13, 6, 7, 8, 9, 10, 11, 4, 5, 14, 15, 16, 17, 20, 21, 18, 19, 22, 23]), // L2
+        from_permutation([0, 1, 2, 3, 18, 19, 6, 7, 8, 9, 10, 11, 20, 21, 14, 15, 16, 17, 12, 13, 4, 5, 22, 23]), // L'
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 10, 11, 12, 13, 14, 15, 8, 9, 16, 17, 18, 19, 20, 21, 22, 23]), // D
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 12, 13, 14, 15, 8, 9, 10, 11, 16, 17, 18, 19, 20, 21, 22, 23]), // D2
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 14, 15, 8, 9, 10, 11, 12, 13, 16, 17, 18, 19, 20, 21, 22, 23]), // D'
+        from_permutation([0, 1, 2, 3, 4, 5, 23, 22, 8, 9, 10, 11, 12, 13, 21, 20, 16, 17, 18, 19, 7, 6, 15, 14]), // B
+        from_permutation([0, 1, 2, 3, 4, 5, 14, 15, 8, 9, 10, 11, 12, 13, 6, 7, 16, 17, 18, 19, 22, 23, 20, 21]), // B2
+        from_permutation([0, 1, 2, 3, 4, 5, 21, 20, 8, 9, 10, 11, 12, 13, 23, 22, 16, 17, 18, 19, 15, 14, 7, 6]), // B'
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 22, 17, 19, 20, 18, 21, 23]), // 2U
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 21, 22, 19, 20, 17, 18, 23]), // 2U2
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 18, 21, 19, 20, 22, 17, 23]), // 2U'
+        from_permutation([0, 1, 2, 10, 4, 5, 3, 7, 8, 9, 15, 11, 12, 13, 14, 6, 16, 17, 18, 19, 20, 21, 22, 23]), // 2R
+        from_permutation([0, 1, 2, 15, 4, 5, 10, 7, 8, 9, 6, 11, 12, 13, 14, 3, 16, 17, 18, 19, 20, 21, 22, 23]), // 2R2
+        from_permutation([0, 1, 2, 6, 4, 5, 15, 7, 8, 9, 3, 11, 12, 13, 14, 10, 16, 17, 18, 19, 20, 21, 22, 23]), // 2R'
+        from_permutation([5, 1, 2, 3, 4, 12, 6, 7, 8, 0, 10, 11, 9, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // 2F
+        from_permutation([12, 1, 2, 3, 4, 9, 6, 7, 8, 5, 10, 11, 0, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // 2F2
+        from_permutation([9, 1, 2, 3, 4, 0, 6, 7, 8, 12, 10, 11, 5, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // 2F'
+        from_permutation([0, 1, 7, 3, 4, 5, 6, 14, 8, 9, 10, 2, 12, 13, 11, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // 2L
+        from_permutation([0, 1, 14, 3, 4, 5, 6, 11, 8, 9, 10, 7, 12, 13, 2, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // 2L2
+        from_permutation([0, 1, 11, 3, 4, 5, 6, 2, 8, 9, 10, 14, 12, 13, 7, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // 2L'
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 19, 17, 18, 20, 23, 21, 22, 16]), // 2D
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 20, 17, 18, 23, 16, 21, 22, 19]), // 2D2
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 23, 17, 18, 16, 19, 21, 22, 20]), // 2D'
+        from_permutation([0, 8, 2, 3, 1, 5, 6, 7, 13, 9, 10, 11, 12, 4, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // 2B
+        from_permutation([0, 13, 2, 3, 8, 5, 6, 7, 4, 9, 10, 11, 12, 1, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // 2B2
+        from_permutation([0, 4, 2, 3, 13, 5, 6, 7, 1, 9, 10, 11, 12, 8, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // 2B'
+    ];
+
+    #[rustfmt::skip]
+    pub const CENTRE_MOVES: [Centres; Self::COUNT] = [
+        from_permutation([2, 0, 3, 1, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // U
+        from_permutation([3, 2, 1, 0, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // U2
+        from_permutation([1, 3, 0, 2, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // U'
+        from_permutation([0, 1, 2, 3, 6, 4, 7, 5, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // R
+        from_permutation([0, 1, 2, 3, 7, 6, 5, 4, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // R2
+        from_permutation([0, 1, 2, 3, 5, 7, 4, 6, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // R'
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 10, 8, 11, 9, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // F
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 11, 10, 9, 8, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // F2
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 9, 11, 8, 10, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23]), // F'
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 12, 14, 16, 17, 18, 19, 20, 21, 22, 23]), // L
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 15, 14, 13, 12, 16, 17, 18, 19, 20, 21, 22, 23]), // L2
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 14, 12, 15, 13, 16, 17, 18, 19, 20, 21, 22, 23]), // L'
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 17, 19, 16, 18, 20, 21, 22, 23]), // D
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 19, 18, 17, 16, 20, 21, 22, 23]), // D2
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 18, 16, 19, 17, 20, 21, 22, 23]), // D'
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 21, 23, 20, 22]), // B
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 23, 22, 21, 20]), // B2
+        from_permutation([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 22, 20, 23, 21]), // B'
+        from_permutation([0, 1, 2, 3, 4, 23, 6, 21, 8, 5, 10, 7, 12, 11, 14, 9, 16, 17, 18, 19, 20, 13, 22, 15]), // 2U
+        from_permutation([0, 1, 2, 3, 4, 15, 6, 13, 8, 23, 10, 21, 12, 7, 14, 5, 16, 17, 18, 19, 20, 11, 22, 9]), // 2U2
+        from_permutation([0, 1, 2, 3, 4, 9, 6, 11, 8, 15, 10, 13, 12, 21, 14, 23, 16, 17, 18, 19, 20, 7, 22, 5]), // 2U'
+        from_permutation([0, 1, 10, 11, 4, 5, 6, 7, 8, 9, 19, 18, 12, 13, 14, 15, 16, 17, 22, 23, 20, 21, 3, 2]), // 2R
+        from_permutation([0, 1, 19, 18, 4, 5, 6, 7, 8, 9, 23, 22, 12, 13, 14, 15, 16, 17, 3, 2, 20, 21, 11, 10]), // 2R2
+        from_permutation([0, 1, 23, 22, 4, 5, 6, 7, 8, 9, 2, 3, 12, 13, 14, 15, 16, 17, 11, 10, 20, 21, 18, 19]), // 2R'
+        from_permutation([12, 1, 13, 3, 2, 0, 6, 7, 8, 9, 10, 11, 18, 16, 14, 15, 4, 17, 5, 19, 20, 21, 22, 23]), // 2F
+        from_permutation([18, 1, 16, 3, 13, 12, 6, 7, 8, 9, 10, 11, 5, 4, 14, 15, 2, 17, 0, 19, 20, 21, 22, 23]), // 2F2
+        from_permutation([5, 1, 4, 3, 16, 18, 6, 7, 8, 9, 10, 11, 0, 2, 14, 15, 13, 17, 12, 19, 20, 21, 22, 23]), // 2F'
+        from_permutation([21, 20, 2, 3, 4, 5, 6, 7, 0, 1, 10, 11, 12, 13, 14, 15, 9, 8, 18, 19, 16, 17, 22, 23]), // 2L
+        from_permutation([17, 16, 2, 3, 4, 5, 6, 7, 21, 20, 10, 11, 12, 13, 14, 15, 1, 0, 18, 19, 9, 8, 22, 23]), // 2L2
+        from_permutation([8, 9, 2, 3, 4, 5, 6, 7, 17, 16, 10, 11, 12, 13, 14, 15, 20, 21, 18, 19, 1, 0, 22, 23]), // 2L'
+        from_permutation([0, 1, 2, 3, 8, 5, 10, 7, 14, 9, 12, 11, 20, 13, 22, 15, 16, 17, 18, 19, 6, 21, 4, 23]), // 2D
+        from_permutation([0, 1, 2, 3, 14, 5, 12, 7, 22, 9, 20, 11, 6, 13, 4, 15, 16, 17, 18, 19, 10, 21, 8, 23]), // 2D2
+        from_permutation([0, 1, 2, 3, 22, 5, 20, 7, 4, 9, 6, 11, 10, 13, 8, 15, 16, 17, 18, 19, 12, 21, 14, 23]), // 2D'
+        from_permutation([0, 7, 2, 6, 4, 5, 17, 19, 8, 9, 10, 11, 12, 13, 1, 3, 16, 15, 18, 14, 20, 21, 22, 23]), // 2B
+        from_permutation([0, 19, 2, 17, 4, 5, 15, 14, 8, 9, 10, 11, 12, 13, 7, 6, 16, 3, 18, 1, 20, 21, 22, 23]), // 2B2
+        from_permutation([0, 14, 2, 15, 4, 5, 3, 1, 8, 9, 10, 11, 12, 13, 19, 17, 16, 6, 18, 7, 20, 21, 22, 23]), // 2B'
+    ];
+
+    pub const fn new(face: Face, layers: Layers, amount: u8) -> Self {
+        Self {
+            face,
+            layers,
+            amount,
+        }
+    }
+
+    pub const fn from_htm(htm: Htm) -> Self {
+        Self::new(htm.face(), Layers::Outer, htm.amount())
+    }
+
+    pub const fn inverse(self) -> Self {
+        Self::new(self.face, self.layers, 4 - self.amount)
+    }
+
+    /// The indices in `ALL` of the single layers turned, of which there are two for a wide turn.
+    pub fn generators(self) -> impl Iterator<Item = usize> {
+        let ix = self.face as usize * 3 + self.amount as usize - 1;
+
+        let (outer, inner) = match self.layers {
+            Layers::Outer => (Some(ix), None),
+            Layers::Inner => (None, Some(Htm::COUNT + ix)),
+            Layers::Wide => (Some(ix), Some(Htm::COUNT + ix)),
+        };
+        outer.into_iter().chain(inner)
+    }
+
+    /// The movement of the corners, which only the outer layers carry.
+    pub fn corners(self) -> &'static Corners {
+        match self.layers {
+            Layers::Inner => &Corners::IDENTITY,
+            _ => Htm::new(self.face, self.amount).to_corners(),
+        }
+    }
+
+    pub fn invert_seq(sequence: &[Self]) -> Vec<Self> {
+        sequence.iter().rev().map(|turn| turn.inverse()).collect()
+    }
+
+    /// Parse turns separated by whitespace, e.g. `Rw U2 2R' r`.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        parse_tokens(str, Self::from_str)
+    }
+
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        iter.map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Cube4x4 {
+    /// Inner turns as in SiGN, e.g. `2R`, and wide turns as for the WCA, e.g. `Rw`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let turn = Htm::new(self.face, self.amount).to_string();
+        let (face, suffix) = turn.split_at(1);
+
+        match self.layers {
+            Layers::Outer => write!(f, "{}", turn),
+            Layers::Inner => write!(f, "2{}", turn),
+            Layers::Wide => write!(f, "{}w{}", face, suffix),
+        }
+    }
+}
+
+impl FromStr for Cube4x4 {
+    type Err = ParseError;
+
+    /// Accepts both `Rw` and SiGN's `r` for a wide turn, and `2R` for an inner one.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseError::new(value, 0, value, reason);
+
+        let (inner, rest) = match value.strip_prefix('2') {
+            Some(rest) => (true, rest),
+            None => (false, value),
+        };
+        let mut chars = rest.chars();
+        let letter = chars.next().ok_or_else(|| error(Reason::UnknownFace))?;
+        let face = match letter.to_ascii_uppercase() {
+            'U' => Face::U,
+            'R' => Face::R,
+            'F' => Face::F,
+            'L' => Face::L,
+            'D' => Face::D,
+            'B' => Face::B,
+            _ => return Err(error(Reason::UnknownFace)),
+        };
+
+        let rest = chars.as_str();
+        let (wide, rest) = match rest.strip_prefix('w') {
+            Some(rest) => (true, rest),
+            None => (letter.is_ascii_lowercase(), rest),
+        };
+        let layers = match (inner, wide) {
+            (false, false) => Layers::Outer,
+            (true, false) => Layers::Inner,
+            (false, true) => Layers::Wide,
+            (true, true) => return Err(error(Reason::BadSuffix)),
+        };
+
+        let amount = match rest {
+            "" => 1,
+            "2" => 2,
+            "'" => 3,
+            _ => return Err(error(Reason::BadSuffix)),
+        };
+
+        Ok(Self::new(face, layers, amount))
+    }
+}
+
+impl From<Htm> for Cube4x4 {
+    fn from(htm: Htm) -> Self {
+        Self::from_htm(htm)
+    }
+}
@@ -0,0 +1,144 @@
+use super::htm::Face;
+use super::Htm;
+use crate::notation::{parse_tokens, ParseError, Reason};
+use std::str::FromStr;
+
+const FACES: [char; 6] = ['U', 'R', 'F', 'L', 'D', 'B'];
+
+/// A turn of a block of adjacent layers of a cube of any size, by `amount` quarter turns
+/// clockwise. The layers are counted from `face`, starting at 1 for the outer one, so that `R`
+/// turns the layers 1 to 1, `3R` the layers 3 to 3, `3Rw` the layers 1 to 3 and `2-3R` the layers
+/// 2 to 3.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct CubeN {
+    pub face: Face,
+    pub first: u8,
+    pub last: u8,
+    pub amount: u8,
+}
+
+impl CubeN {
+    /// The turn of the layers `first` to `last` at `face`, which must satisfy
+    /// `1 <= first <= last`.
+    pub const fn new(face: Face, first: u8, last: u8, amount: u8) -> Self {
+        debug_assert!(first >= 1 && first <= last, "Empty range of layers");
+        Self {
+            face,
+            first,
+            last,
+            amount,
+        }
+    }
+
+    pub const fn from_htm(htm: Htm) -> Self {
+        Self::new(htm.face(), 1, 1, htm.amount())
+    }
+
+    pub const fn inverse(self) -> Self {
+        Self::new(self.face, self.first, self.last, 4 - self.amount)
+    }
+
+    /// Whether the layer `layer`, counted from `face`, is turned.
+    pub const fn turns(self, layer: u8) -> bool {
+        self.first <= layer && layer <= self.last
+    }
+
+    pub fn invert_seq(sequence: &[Self]) -> Vec<Self> {
+        sequence.iter().rev().map(|turn| turn.inverse()).collect()
+    }
+
+    /// Parse turns separated by whitespace, e.g. `3Rw U2 2-3F' l`.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        parse_tokens(str, Self::from_str)
+    }
+
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        iter.map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect()
+    }
+}
+
+impl std::fmt::Display for CubeN {
+    /// Single layers as in SiGN, e.g. `3R`, blocks from the outer layer as for the WCA, e.g.
+    /// `3Rw`, and other blocks as ranges, e.g. `2-3R`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let turn = Htm::new(self.face, self.amount).to_string();
+        let (face, suffix) = turn.split_at(1);
+
+        match (self.first, self.last) {
+            (1, 1) => write!(f, "{}", turn),
+            (1, 2) => write!(f, "{}w{}", face, suffix),
+            (1, last) => write!(f, "{}{}w{}", last, face, suffix),
+            (first, last) if first == last => write!(f, "{}{}", first, turn),
+            (first, last) => write!(f, "{}-{}{}", first, last, turn),
+        }
+    }
+}
+
+impl FromStr for CubeN {
+    type Err = ParseError;
+
+    /// Accepts the notation of both SiGN and the WCA: `3r` and `3Rw` are the same turn, as are
+    /// `r` and `Rw`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseError::new(value, 0, value, reason);
+
+        let digits = value
+            .find(|c: char| !c.is_ascii_digit() && c != '-')
+            .unwrap_or(value.len());
+        let (prefix, rest) = value.split_at(digits);
+        let layer = |digits: &str| {
+            digits
+                .parse::<u8>()
+                .ok()
+                .filter(|&layer| layer > 0)
+                .ok_or_else(|| error(Reason::BadLayers))
+        };
+        let range = match prefix.find('-') {
+            _ if prefix.is_empty() => None,
+            Some(ix) => Some((layer(&prefix[..ix])?, layer(&prefix[ix + 1..])?)),
+            None => Some((layer(prefix)?, layer(prefix)?)),
+        };
+
+        let mut chars = rest.chars();
+        let letter = chars.next().ok_or_else(|| error(Reason::UnknownFace))?;
+        let face = FACES
+            .iter()
+            .position(|&face| face == letter.to_ascii_uppercase())
+            .ok_or_else(|| error(Reason::UnknownFace))?;
+
+        let rest = chars.as_str();
+        let (wide, rest) = match rest.strip_prefix('w') {
+            Some(rest) if letter.is_ascii_uppercase() => (true, rest),
+            Some(_) => return Err(error(Reason::BadSuffix)),
+            None => (letter.is_ascii_lowercase(), rest),
+        };
+
+        let (first, last) = match (range, wide) {
+            (None, false) => (1, 1),
+            (None, true) => (1, 2),
+            (Some((first, last)), _) if prefix.contains('-') => (first, last),
+            (Some((layer, _)), false) => (layer, layer),
+            (Some((layer, _)), true) => (1, layer),
+        };
+        if first > last {
+            return Err(error(Reason::BadLayers));
+        }
+
+        let amount = match rest {
+            "" => 1,
+            "2" => 2,
+            "'" => 3,
+            _ => return Err(error(Reason::BadSuffix)),
+        };
+
+        Ok(Self::new(Face::ALL[face], first, last, amount))
+    }
+}
+
+impl From<Htm> for CubeN {
+    fn from(htm: Htm) -> Self {
+        Self::from_htm(htm)
+    }
+}
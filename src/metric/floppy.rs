@@ -0,0 +1,104 @@
+use crate::core::definitions as def;
+use crate::notation::{parse_tokens, ParseError, Reason};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// The corners, in the order FR, FL, BL and BR, each of which is flipped or not.
+pub type Corners = def::Array<4, 2>;
+
+/// The edges, in the order R, F, L and B, which never move but may be flipped.
+pub type Edges = def::Array<4, 2>;
+
+/// The moves of the 1x3x3 floppy cube, which are the half turns of its four sides. Each swaps
+/// the two corners of its side and flips them together with the edge between them.
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Floppy {
+    R2, F2, L2, B2,
+}
+
+const FACES: [char; 4] = ['R', 'F', 'L', 'B'];
+
+impl Floppy {
+    pub const COUNT: usize = 4;
+
+    #[rustfmt::skip]
+    pub const ALL: [Self; Self::COUNT] = {
+        use Floppy::*;
+        [R2, F2, L2, B2]
+    };
+
+    #[rustfmt::skip]
+    pub const CORNER_MOVES: [Corners; Self::COUNT] = [
+        Corners::new([(3, 1), (1, 0), (2, 0), (0, 1)]), // R2
+        Corners::new([(1, 1), (0, 1), (2, 0), (3, 0)]), // F2
+        Corners::new([(0, 0), (2, 1), (1, 1), (3, 0)]), // L2
+        Corners::new([(0, 0), (1, 0), (3, 1), (2, 1)]), // B2
+    ];
+
+    #[rustfmt::skip]
+    pub const EDGE_MOVES: [Edges; Self::COUNT] = [
+        Edges::new([(0, 1), (1, 0), (2, 0), (3, 0)]), // R2
+        Edges::new([(0, 0), (1, 1), (2, 0), (3, 0)]), // F2
+        Edges::new([(0, 0), (1, 0), (2, 1), (3, 0)]), // L2
+        Edges::new([(0, 0), (1, 0), (2, 0), (3, 1)]), // B2
+    ];
+
+    /// Every move is its own inverse.
+    pub const fn inverse(self) -> Self {
+        self
+    }
+
+    pub fn invert_seq(sequence: &[Self]) -> Vec<Self> {
+        sequence.iter().rev().copied().collect()
+    }
+
+    /// Parse turns separated by whitespace, e.g. `R2 F2 L2`.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        parse_tokens(str, Self::from_str)
+    }
+
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        iter.map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Floppy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}2", FACES[*self as usize])
+    }
+}
+
+impl FromStr for Floppy {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut chars = value.chars();
+        let face = chars
+            .next()
+            .and_then(|c| FACES.iter().position(|&face| face == c))
+            .ok_or_else(|| ParseError::new(value, 0, value, Reason::UnknownFace))?;
+
+        match chars.as_str() {
+            "2" => Ok(Self::ALL[face]),
+            _ => Err(ParseError::new(value, 0, value, Reason::BadSuffix)),
+        }
+    }
+}
+
+impl TryFrom<usize> for Floppy {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::ALL.get(value).copied().ok_or(())
+    }
+}
+
+impl From<Floppy> for usize {
+    fn from(val: Floppy) -> Self {
+        val as usize
+    }
+}
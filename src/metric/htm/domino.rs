@@ -1,12 +1,15 @@
 use super::Htm;
 use crate::core::definitions as def;
+use crate::metric::Metric;
+use crate::notation::{parse_tokens, ParseError, Reason};
 use crate::util;
 use std::convert::TryFrom;
+use std::str::FromStr;
 
-pub type Corners_ = def::Array<8, 1>;
-pub type Edges_ = def::Array<8, 1>;
-pub struct Corners(pub Corners_);
-pub struct Edges(pub Edges_);
+/// The permutations of the corners, of the edges of U and D and of the edges of the E slice,
+/// which are all that moves within the domino group change.
+pub type Corners = def::Array<8, 1>;
+pub type Edges = def::Array<8, 1>;
 pub type Slice = def::Array<4, 1>;
 pub type CornerPermutation = def::PermutationCoord<8>;
 pub type EdgePermutation = def::PermutationCoord<8>;
@@ -16,7 +19,7 @@ pub type SlicePermutation = def::PermutationCoord<4>;
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Domino {
-    U1, U2, U3, R2, F2, L2, D1, D2, D3, B2
+    U1, U2, U3, D1, D2, D3, R2, F2, L2, B2
 }
 
 impl Domino {
@@ -24,30 +27,30 @@ impl Domino {
 
     #[rustfmt::skip]
     pub const CORNER_MOVES: [Corners; Self::COUNT] = [
-        Corners(Corners_::new([(3, 0), (0, 0), (1, 0), (2, 0), (4, 0), (5, 0), (6, 0), (7, 0)])), // U
-        Corners(Corners_::new([(2, 0), (3, 0), (0, 0), (1, 0), (4, 0), (5, 0), (6, 0), (7, 0)])), // U2
-        Corners(Corners_::new([(1, 0), (2, 0), (3, 0), (0, 0), (4, 0), (5, 0), (6, 0), (7, 0)])), // U'
-        Corners(Corners_::new([(0, 0), (1, 0), (2, 0), (3, 0), (5, 0), (6, 0), (7, 0), (4, 0)])), // D
-        Corners(Corners_::new([(0, 0), (1, 0), (2, 0), (3, 0), (6, 0), (7, 0), (4, 0), (5, 0)])), // D2
-        Corners(Corners_::new([(0, 0), (1, 0), (2, 0), (3, 0), (7, 0), (4, 0), (5, 0), (6, 0)])), // D'
-        Corners(Corners_::new([(7, 0), (1, 0), (2, 0), (4, 0), (3, 0), (5, 0), (6, 0), (0, 0)])), // R2
-        Corners(Corners_::new([(5, 0), (4, 0), (2, 0), (3, 0), (1, 0), (0, 0), (6, 0), (7, 0)])), // F2
-        Corners(Corners_::new([(0, 0), (6, 0), (5, 0), (3, 0), (4, 0), (2, 0), (1, 0), (7, 0)])), // L2
-        Corners(Corners_::new([(0, 0), (1, 0), (7, 0), (6, 0), (4, 0), (5, 0), (3, 0), (2, 0)])), // B2
+        Corners::new([(3, 0), (0, 0), (1, 0), (2, 0), (4, 0), (5, 0), (6, 0), (7, 0)]), // U
+        Corners::new([(2, 0), (3, 0), (0, 0), (1, 0), (4, 0), (5, 0), (6, 0), (7, 0)]), // U2
+        Corners::new([(1, 0), (2, 0), (3, 0), (0, 0), (4, 0), (5, 0), (6, 0), (7, 0)]), // U'
+        Corners::new([(0, 0), (1, 0), (2, 0), (3, 0), (5, 0), (6, 0), (7, 0), (4, 0)]), // D
+        Corners::new([(0, 0), (1, 0), (2, 0), (3, 0), (6, 0), (7, 0), (4, 0), (5, 0)]), // D2
+        Corners::new([(0, 0), (1, 0), (2, 0), (3, 0), (7, 0), (4, 0), (5, 0), (6, 0)]), // D'
+        Corners::new([(7, 0), (1, 0), (2, 0), (4, 0), (3, 0), (5, 0), (6, 0), (0, 0)]), // R2
+        Corners::new([(5, 0), (4, 0), (2, 0), (3, 0), (1, 0), (0, 0), (6, 0), (7, 0)]), // F2
+        Corners::new([(0, 0), (6, 0), (5, 0), (3, 0), (4, 0), (2, 0), (1, 0), (7, 0)]), // L2
+        Corners::new([(0, 0), (1, 0), (7, 0), (6, 0), (4, 0), (5, 0), (3, 0), (2, 0)]), // B2
     ];
 
     #[rustfmt::skip]
     pub const EDGE_MOVES: [Edges; Self::COUNT] = [
-        Edges(Edges_::new([(3, 0), (0, 0), (1, 0), (2, 0), (4, 0), (5, 0), (6, 0), (7, 0)])), // U
-        Edges(Edges_::new([(2, 0), (3, 0), (0, 0), (1, 0), (4, 0), (5, 0), (6, 0), (7, 0)])), // U2
-        Edges(Edges_::new([(1, 0), (2, 0), (3, 0), (0, 0), (4, 0), (5, 0), (6, 0), (7, 0)])), // U'
-        Edges(Edges_::new([(0, 0), (1, 0), (2, 0), (3, 0), (5, 0), (6, 0), (7, 0), (4, 0)])), // D
-        Edges(Edges_::new([(0, 0), (1, 0), (2, 0), (3, 0), (6, 0), (7, 0), (4, 0), (5, 0)])), // D2
-        Edges(Edges_::new([(0, 0), (1, 0), (2, 0), (3, 0), (7, 0), (4, 0), (5, 0), (6, 0)])), // D'
-        Edges(Edges_::new([(4, 0), (1, 0), (2, 0), (3, 0), (0, 0), (5, 0), (6, 0), (7, 0)])), // R2
-        Edges(Edges_::new([(0, 0), (5, 0), (2, 0), (3, 0), (4, 0), (1, 0), (6, 0), (7, 0)])), // F2
-        Edges(Edges_::new([(0, 0), (1, 0), (6, 0), (3, 0), (4, 0), (5, 0), (2, 0), (7, 0)])), // L2
-        Edges(Edges_::new([(0, 0), (1, 0), (2, 0), (7, 0), (4, 0), (5, 0), (6, 0), (3, 0)])), // B2
+        Edges::new([(3, 0), (0, 0), (1, 0), (2, 0), (4, 0), (5, 0), (6, 0), (7, 0)]), // U
+        Edges::new([(2, 0), (3, 0), (0, 0), (1, 0), (4, 0), (5, 0), (6, 0), (7, 0)]), // U2
+        Edges::new([(1, 0), (2, 0), (3, 0), (0, 0), (4, 0), (5, 0), (6, 0), (7, 0)]), // U'
+        Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (5, 0), (6, 0), (7, 0), (4, 0)]), // D
+        Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (6, 0), (7, 0), (4, 0), (5, 0)]), // D2
+        Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (7, 0), (4, 0), (5, 0), (6, 0)]), // D'
+        Edges::new([(4, 0), (1, 0), (2, 0), (3, 0), (0, 0), (5, 0), (6, 0), (7, 0)]), // R2
+        Edges::new([(0, 0), (5, 0), (2, 0), (3, 0), (4, 0), (1, 0), (6, 0), (7, 0)]), // F2
+        Edges::new([(0, 0), (1, 0), (6, 0), (3, 0), (4, 0), (5, 0), (2, 0), (7, 0)]), // L2
+        Edges::new([(0, 0), (1, 0), (2, 0), (7, 0), (4, 0), (5, 0), (6, 0), (3, 0)]), // B2
     ];
 
     #[rustfmt::skip]
@@ -65,27 +68,89 @@ impl Domino {
     ];
 
     pub const GENERATORS: [usize; Self::COUNT] = util::count::<{ Self::COUNT }>();
+
+    #[rustfmt::skip]
+    pub const ALL: [Self; Self::COUNT] = {
+        use Domino::*;
+        [U1, U2, U3, D1, D2, D3, R2, F2, L2, B2]
+    };
+
+    pub const fn to_htm(self) -> Htm {
+        use Domino::*;
+
+        match self {
+            U1 => Htm::U1,
+            U2 => Htm::U2,
+            U3 => Htm::U3,
+            D1 => Htm::D1,
+            D2 => Htm::D2,
+            D3 => Htm::D3,
+            R2 => Htm::R2,
+            F2 => Htm::F2,
+            L2 => Htm::L2,
+            B2 => Htm::B2,
+        }
+    }
+
+    pub fn invert_seq(sequence: &[Self]) -> Vec<Self> {
+        sequence.iter().rev().map(|turn| turn.inverse()).collect()
+    }
+
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        Htm::format_seq(iter.map(Self::to_htm))
+    }
+
+    /// Parse turns separated by whitespace, e.g. `U R2 D' F2`, which is also the notation of the
+    /// 3x3x2 and 2x2x3 cuboids with their half turns of the long faces.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        parse_tokens(str, Self::from_str)
+    }
+}
+
+impl Metric for Domino {
+    const COUNT: usize = Self::COUNT;
+
+    const ALL: &'static [Self] = &Self::ALL;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn to_htm(self) -> Htm {
+        self.to_htm()
+    }
+}
+
+impl std::fmt::Display for Domino {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_htm())
+    }
+}
+
+impl FromStr for Domino {
+    type Err = ParseError;
+
+    /// A turn of `Htm` which is in the domino group, where a quarter turn of R, F, L or B has a
+    /// bad suffix.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Self::try_from(value.parse::<Htm>()?)
+            .map_err(|()| ParseError::new(value, 0, value, Reason::BadSuffix))
+    }
 }
 
 impl TryFrom<usize> for Domino {
     type Error = ();
 
     fn try_from(value: usize) -> Result<Self, Self::Error> {
-        use Domino::*;
+        Self::ALL.get(value).copied().ok_or(())
+    }
+}
 
-        match value {
-            0 => Ok(U1),
-            1 => Ok(U2),
-            2 => Ok(U3),
-            3 => Ok(R2),
-            4 => Ok(F2),
-            5 => Ok(L2),
-            6 => Ok(D1),
-            7 => Ok(D2),
-            8 => Ok(D3),
-            9 => Ok(B2),
-            _ => Err(()),
-        }
+impl TryFrom<Htm> for Domino {
+    type Error = ();
+
+    fn try_from(htm: Htm) -> Result<Self, Self::Error> {
+        Self::from_htm(htm).ok_or(())
     }
 }
 
@@ -97,35 +162,50 @@ impl From<Domino> for usize {
 
 impl From<Domino> for Htm {
     fn from(val: Domino) -> Htm {
-        match val {
-            Domino::U1 => Htm::U1,
-            Domino::U2 => Htm::U2,
-            Domino::U3 => Htm::U3,
-            Domino::R2 => Htm::R2,
-            Domino::F2 => Htm::F2,
-            Domino::L2 => Htm::L2,
-            Domino::D1 => Htm::D1,
-            Domino::D2 => Htm::D2,
-            Domino::D3 => Htm::D3,
-            Domino::B2 => Htm::B2,
-        }
+        val.to_htm()
     }
 }
 
-impl From<Domino> for &Corners {
-    fn from(htm: Domino) -> Self {
-        &Domino::CORNER_MOVES[usize::from(htm)]
-    }
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-impl From<Domino> for &Edges {
-    fn from(htm: Domino) -> Self {
-        &Domino::EDGE_MOVES[usize::from(htm)]
+    #[test]
+    pub fn tables() {
+        for turn in Domino::ALL {
+            let ix = turn.index();
+            assert_eq!(Domino::try_from(turn.to_htm()), Ok(turn));
+            assert_eq!(
+                Metric::corners(turn).p_coordinate(),
+                Domino::CORNER_MOVES[ix].p_coordinate()
+            );
+            assert_eq!(
+                Metric::edges(turn).truncate::<8>().unwrap().p_coordinate(),
+                Domino::EDGE_MOVES[ix].p_coordinate()
+            );
+            assert_eq!(
+                Metric::edges(turn).drop::<4>().unwrap().p_coordinate(),
+                Domino::SLICE_MOVES[ix].p_coordinate()
+            );
+        }
     }
-}
 
-impl From<Domino> for &Slice {
-    fn from(htm: Domino) -> Self {
-        &Domino::SLICE_MOVES[usize::from(htm)]
+    #[test]
+    pub fn notation() {
+        // The notation of the 3x3x2 and 2x2x3 cuboids, which have no quarter turns of the sides
+        let turns = Domino::parse("U R2  D' F2 U2").unwrap();
+        assert_eq!(Domino::format_seq(turns.iter().copied()), "U R2 D' F2 U2");
+        assert_eq!(
+            Domino::format_seq(Domino::invert_seq(&turns).into_iter()),
+            "U2 F2 D R2 U'"
+        );
+        assert_eq!(
+            Domino::parse("U R F2").unwrap_err(),
+            ParseError::new("U R F2", 2, "R", Reason::BadSuffix)
+        );
+        assert_eq!(
+            Domino::parse("U M2").unwrap_err().reason,
+            Reason::UnknownFace
+        );
     }
 }
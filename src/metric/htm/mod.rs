@@ -1,7 +1,10 @@
 pub mod domino;
 mod moves;
+pub mod ruf;
 
+use super::Metric;
 use crate::core::definitions as def;
+use crate::notation::{parse_tokens, Axis, ParseError, Reason, Rotation, Slice};
 use crate::util;
 use std::convert::TryFrom;
 use std::str::FromStr;
@@ -13,8 +16,53 @@ pub type EdgePermutation = def::PermutationCoord<12>;
 pub type CornerOrientation = def::OrientationCoord<8, 3>;
 pub type EdgeOrientation = def::OrientationCoord<12, 2>;
 
+/// The faces of the cube, in the order of their turns in `Htm`.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Face {
+    U,
+    R,
+    F,
+    L,
+    D,
+    B,
+}
+
+impl Face {
+    pub const ALL: [Self; 6] = [Face::U, Face::R, Face::F, Face::L, Face::D, Face::B];
+
+    /// The face on the other side of the cube.
+    pub const fn opposite(self) -> Self {
+        const OPPOSITE: [Face; 6] = [Face::D, Face::L, Face::B, Face::R, Face::U, Face::F];
+        OPPOSITE[self as usize]
+    }
+
+    /// The axis of the rotation of the whole cube in the same direction as the face.
+    pub const fn axis(self) -> Axis {
+        match self {
+            Face::U | Face::D => Axis::Y,
+            Face::R | Face::L => Axis::X,
+            Face::F | Face::B => Axis::Z,
+        }
+    }
+}
+
+/// The faces of each corner in the order of `Corners`, starting from U or D and going clockwise.
 #[rustfmt::skip]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub const CORNER_FACES: [[Face; 3]; 8] = {
+    use Face::*;
+    [[U, R, F], [U, F, L], [U, L, B], [U, B, R], [D, F, R], [D, L, F], [D, B, L], [D, R, B]]
+};
+
+/// The faces of each edge in the order of `Edges`, starting from the one it is flipped against.
+#[rustfmt::skip]
+pub const EDGE_FACES: [[Face; 2]; 12] = {
+    use Face::*;
+    [[U, R], [U, F], [U, L], [U, B], [D, R], [D, F], [D, L], [D, B], [F, R], [F, L], [B, L], [B, R]]
+};
+
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
 #[repr(u8)]
 pub enum Htm {
     U1, U2, U3, R1, R2, R3, F1, F2, F3, L1, L2, L3, D1, D2, D3, B1, B2, B3
@@ -25,6 +73,27 @@ impl Htm {
 
     pub const GENERATORS: [usize; Self::COUNT] = util::count::<{ Self::COUNT }>();
 
+    #[rustfmt::skip]
+    pub const ALL: [Self; Self::COUNT] = {
+        use Htm::*;
+        [U1, U2, U3, R1, R2, R3, F1, F2, F3, L1, L2, L3, D1, D2, D3, B1, B2, B3]
+    };
+
+    /// The turn of `face` by `amount` quarter turns clockwise, which must be 1, 2 or 3.
+    pub const fn new(face: Face, amount: u8) -> Self {
+        debug_assert!(amount >= 1 && amount <= 3, "Amount outside of turn range");
+        Self::ALL[face as usize * 3 + amount as usize - 1]
+    }
+
+    pub const fn face(self) -> Face {
+        Face::ALL[self as usize / 3]
+    }
+
+    /// The number of quarter turns clockwise, from 1 to 3.
+    pub const fn amount(self) -> u8 {
+        self as u8 % 3 + 1
+    }
+
     pub const fn to_corners(self) -> &'static Corners {
         &Self::CORNER_MOVES[self as usize]
     }
@@ -33,17 +102,119 @@ impl Htm {
         &Self::EDGE_MOVES[self as usize]
     }
 
+    /// Whether the two turns are of the same face, so that they could be merged into one.
+    pub const fn same_face(self, other: Self) -> bool {
+        self as u8 / 3 == other as u8 / 3
+    }
+
+    /// Whether the two turns are of faces on the same axis, in which case they commute.
+    pub const fn commutes(self, other: Self) -> bool {
+        // The axes of U, R, F, L, D and B: U-D, R-L and F-B
+        const AXES: [u8; 6] = [0, 1, 2, 1, 0, 2];
+        AXES[self as usize / 3] == AXES[other as usize / 3]
+    }
+
+    /// The turn which undoes this one.
+    pub const fn inverse(self) -> Self {
+        use Htm::*;
+
+        match self {
+            U1 => U3,
+            U2 => U2,
+            U3 => U1,
+            R1 => R3,
+            R2 => R2,
+            R3 => R1,
+            F1 => F3,
+            F2 => F2,
+            F3 => F1,
+            L1 => L3,
+            L2 => L2,
+            L3 => L1,
+            D1 => D3,
+            D2 => D2,
+            D3 => D1,
+            B1 => B3,
+            B2 => B2,
+            B3 => B1,
+        }
+    }
+
+    /// The reflection of this turn in `plane`, which is a turn of the mirrored face in the
+    /// opposite direction, e.g. `R` becomes `L'` when mirrored in M.
+    pub const fn mirror(self, plane: Slice) -> Self {
+        let face = match (plane, self.face()) {
+            (Slice::M, Face::R | Face::L) | (Slice::E, Face::U | Face::D) => self.face().opposite(),
+            (Slice::S, Face::F | Face::B) => self.face().opposite(),
+            (_, face) => face,
+        };
+
+        Self::new(face, 4 - self.amount())
+    }
+
+    /// This turn made after `rotation` of the whole cube, as a turn of the face which has been
+    /// rotated into its position.
+    pub const fn rotate(self, rotation: Rotation) -> Self {
+        Self::new(rotation.face(self.face()), self.amount())
+    }
+
+    /// The sequence which undoes `sequence`.
+    pub fn invert_seq(sequence: &[Self]) -> Vec<Self> {
+        sequence.iter().rev().map(|turn| turn.inverse()).collect()
+    }
+
+    /// The mirror image of `sequence` in `plane`, which reaches the mirror image of its state.
+    pub fn mirror_seq(sequence: &[Self], plane: Slice) -> Vec<Self> {
+        sequence.iter().map(|turn| turn.mirror(plane)).collect()
+    }
+
+    /// `sequence` made after `rotation` of the whole cube, in terms of the original faces.
+    pub fn rotate_seq(sequence: &[Self], rotation: Rotation) -> Vec<Self> {
+        sequence.iter().map(|turn| turn.rotate(rotation)).collect()
+    }
+
+    /// The shortest sequence equal to `sequence` which can be found by merging turns of the same
+    /// face and cancelling inverses, where turns of opposite faces may be moved past each other.
+    /// Such turns are put in the order of `Htm`, e.g. `D U` becomes `U D`, so that equal sequences
+    /// give the same result.
+    pub fn simplify(sequence: impl IntoIterator<Item = Self>) -> Vec<Self> {
+        let mut simplified: Vec<Self> = Vec::new();
+
+        for turn in sequence {
+            let run = simplified
+                .iter()
+                .rposition(|previous| !previous.commutes(turn))
+                .map_or(0, |ix| ix + 1);
+
+            match simplified[run..]
+                .iter()
+                .position(|previous| previous.same_face(turn))
+            {
+                Some(ix) => match (simplified[run + ix].amount() + turn.amount()) % 4 {
+                    0 => {
+                        simplified.remove(run + ix);
+                    }
+                    amount => simplified[run + ix] = Self::new(turn.face(), amount),
+                },
+                None => {
+                    simplified.push(turn);
+                    simplified[run..].sort_unstable();
+                }
+            }
+        }
+
+        simplified
+    }
+
     pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
         iter.map(|turn| format!("{}", turn))
             .intersperse(" ".to_string())
             .collect()
     }
 
-    pub fn parse(str: &str) -> Option<Vec<Self>> {
-        str.split_whitespace()
-            .map(str::parse::<Self>)
-            .map(Result::ok)
-            .collect::<Option<_>>()
+    /// Parse face turns separated by whitespace, e.g. `R U2 F'`.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        parse_tokens(str, Self::from_str)
     }
 }
 
@@ -74,8 +245,26 @@ impl std::fmt::Display for Htm {
     }
 }
 
+impl Metric for Htm {
+    const COUNT: usize = Self::COUNT;
+
+    const ALL: &'static [Self] = &Self::ALL;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn to_htm(self) -> Htm {
+        self
+    }
+
+    fn from_htm(htm: Htm) -> Option<Self> {
+        Some(htm)
+    }
+}
+
 impl FromStr for Htm {
-    type Err = ();
+    type Err = ParseError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
         use Htm::*;
@@ -99,7 +288,13 @@ impl FromStr for Htm {
             "B" => Ok(B1),
             "B2" => Ok(B2),
             "B'" => Ok(B3),
-            _ => Err(()),
+            _ => {
+                let reason = match value.chars().next() {
+                    Some('U' | 'R' | 'F' | 'L' | 'D' | 'B') => Reason::BadSuffix,
+                    _ => Reason::UnknownFace,
+                };
+                Err(ParseError::new(value, 0, value, reason))
+            }
         }
     }
 }
@@ -163,3 +358,116 @@ impl From<Htm> for &Edges {
         &Htm::EDGE_MOVES[usize::from(htm)]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Htm::{self, *};
+    use super::{Corners, Edges};
+    use crate::notation::{expand, parse, Rotation, Slice};
+    use crate::puzzle::Cube3x3;
+
+    const SEQUENCE: [Htm; 14] = [R1, L1, U2, D3, U2, R3, F1, B2, F3, D1, L3, R2, B1, B1];
+
+    /// The mirror image of `cube` in M, found by conjugating with the reflection, which swaps the
+    /// pieces on the left and right and reverses the direction of corner twists.
+    fn reflect(cube: &Cube3x3) -> Cube3x3 {
+        const CORNERS: [u8; 8] = [1, 0, 3, 2, 5, 4, 7, 6];
+        const EDGES: [u8; 12] = [2, 1, 0, 3, 6, 5, 4, 7, 9, 8, 11, 10];
+
+        let (corners, edges) = (cube.corners.elements(), cube.edges.elements());
+        let corners = CORNERS.map(|ix| {
+            let (corner, twist) = corners[ix as usize];
+            (CORNERS[corner as usize], (3 - twist) % 3)
+        });
+        let edges = EDGES.map(|ix| {
+            let (edge, flip) = edges[ix as usize];
+            (EDGES[edge as usize], flip)
+        });
+
+        Cube3x3::new(Corners::new(corners), Edges::new(edges))
+    }
+
+    /// `cube` seen after `rotation` of the whole cube, found by conjugating with the rotation.
+    fn rotate(cube: &Cube3x3, rotation: Rotation) -> Cube3x3 {
+        let rotation = Cube3x3::rotation(rotation);
+        rotation.permute(cube).permute(&rotation.inverse())
+    }
+
+    /// The state reached by `source`, which may rotate the cube as long as it is rotated back.
+    fn alg(source: &str) -> Cube3x3 {
+        let (turns, rotation) = expand(&parse(source).unwrap());
+        assert_eq!(rotation, Rotation::IDENTITY, "{}", source);
+        Cube3x3::from_slice(&turns)
+    }
+
+    #[test]
+    pub fn transformations() {
+        let cube = Cube3x3::from_slice(&SEQUENCE);
+        let source = Htm::format_seq(SEQUENCE.iter().copied());
+
+        let inverse = Htm::invert_seq(&SEQUENCE);
+        assert_eq!(Cube3x3::from_slice(&inverse), cube.inverse());
+        assert_eq!(cube.apply_seq(inverse), Cube3x3::default());
+
+        // Each mirror swaps the two faces parallel to its plane and turns every face the other way
+        let mirrors = [
+            (Slice::M, "L' R' U2 D U2 L F' B2 F D' R L2 B' B'"),
+            (Slice::E, "R' L' D2 U D2 R F' B2 F U' L R2 B' B'"),
+            (Slice::S, "R' L' U2 D U2 R B' F2 B D' L R2 F' F'"),
+        ];
+        for (plane, mirror) in mirrors.iter().copied() {
+            let mirrored = Htm::mirror_seq(&SEQUENCE, plane);
+            assert_eq!(Cube3x3::from_slice(&mirrored), alg(mirror), "{:?}", plane);
+            assert_eq!(Htm::mirror_seq(&mirrored, plane), SEQUENCE);
+        }
+
+        // The reflections in E and S are the one in M seen from the side and from above
+        let mirrored = |plane| Cube3x3::from_slice(&Htm::mirror_seq(&SEQUENCE, plane));
+        assert_eq!(mirrored(Slice::M), reflect(&cube));
+        let seen =
+            |rotation: Rotation| rotate(&reflect(&rotate(&cube, rotation)), rotation.inverse());
+        assert_eq!(mirrored(Slice::E), seen(Rotation::Z));
+        assert_eq!(mirrored(Slice::S), seen(Rotation::Y));
+
+        for (rotation, name) in [(Rotation::X, "x"), (Rotation::Y, "y"), (Rotation::Z, "z")] {
+            let rotated = Htm::rotate_seq(&SEQUENCE, rotation);
+            assert_eq!(
+                Cube3x3::from_slice(&rotated),
+                rotate(&cube, rotation),
+                "{}",
+                name
+            );
+
+            let source = format!("{} {} {}'", name, source, name);
+            assert_eq!(Cube3x3::from_slice(&rotated), alg(&source));
+            assert_eq!(rotated, expand(&parse(&source).unwrap()).0);
+        }
+        assert_eq!(Htm::rotate_seq(&SEQUENCE, Rotation::IDENTITY), SEQUENCE);
+    }
+
+    #[test]
+    pub fn simplify() {
+        let cases: [(&[Htm], &[Htm]); 8] = [
+            (&[R1, R1], &[R2]),
+            (&[R1, R3], &[]),
+            (&[U1, D1, U3], &[D1]),
+            (&[D1, U1], &[U1, D1]),
+            (&[R1, U1, D1, U3, R3], &[R1, D1, R3]),
+            (&[R1, L2, R1, L2], &[R2]),
+            (&[F1, R1, U1, U3, R3, B1], &[F1, B1]),
+            (&[U1, R2, F3, B1, F1, L1], &[U1, R2, B1, L1]),
+        ];
+
+        for (sequence, simplified) in cases {
+            assert_eq!(Htm::simplify(sequence.iter().copied()), simplified);
+        }
+
+        let sequence = [R1, L1, U2, D3, U2, R3, F1, B2, F3, D1, L3, R2, B1, B1];
+        let simplified = Htm::simplify(sequence.iter().copied());
+        assert_eq!(simplified, [R1, L1, D3, R3, B2, D1, R2, L3, B2]);
+        assert_eq!(
+            Cube3x3::from_slice(&sequence),
+            Cube3x3::from_slice(&simplified)
+        );
+    }
+}
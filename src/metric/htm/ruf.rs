@@ -1,96 +1,163 @@
-use super::Htm;
-use crate::core::definitions as def;
+use super::{Face, Htm};
+use crate::notation::{Alg, Axis, Move, Rotation, Turn};
 
+/// The turns of R, U and F only, as made by a robot which can only reach three faces. Turns of
+/// the other faces are made by first rotating the whole cube to bring them within reach.
 #[rustfmt::skip]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[repr(u8)]
 pub enum HtmRuf {
     U1, U2, U3, R1, R2, R3, F1, F2, F3
 }
 
-type Rotation = def::Array<4, 1>;
-
-const ROT_X: Rotation = def::Array::new([(1, 0), (4, 0), (2, 0), (3, 0), (5, 0), (0, 0)]);
-const ROT_Y: Rotation = def::Array::new([(0, 0), (2, 0), (5, 0), (1, 0), (4, 0), (3, 0)]);
-const ROT_Z: Rotation = ROT_X
-    .permute(&ROT_Y)
-    .permute(&ROT_X)
-    .permute(&ROT_X)
-    .permute(&ROT_X);
-
-const fn rotate(axis: usize, direction: usize) -> Rotation {
-    let mut rot = match axis % 3 {
-        0 => ROT_Y,
-        1 => ROT_X,
-        2 => ROT_Z,
-        _ => unreachable!(),
-    };
-
-    let mut x = 0;
-    while x < direction % 4 {
-        rot = rot.permute(&rot);
-        x += 1;
+/// The rotation bringing the face at each of L, D and B to U, F and U respectively.
+const fn rotate(position: Face) -> Rotation {
+    match position {
+        Face::L => Rotation::Z,
+        Face::D => Rotation::X,
+        Face::B => Rotation::X.inverse(),
+        _ => Rotation::IDENTITY,
     }
-
-    rot
 }
 
+/// The turns of a sequence in `Htm`, each with the rotation of the whole cube to be made before
+/// it, which is the identity if the face is already within reach.
 pub struct HtmRufIterator<Iter> {
     orientation: Rotation,
     iter: Iter,
 }
 
 impl<I: Iterator<Item = Htm>> Iterator for HtmRufIterator<I> {
-    type Item = HtmRuf;
+    type Item = (Rotation, HtmRuf);
 
     fn next(&mut self) -> Option<Self::Item> {
         let htm = self.iter.next()?;
-        let (ruf, orientation) = HtmRuf::reorient(htm, &self.orientation);
-        self.orientation = orientation;
-        Some(ruf)
+        let (rotation, ruf) = HtmRuf::reorient(htm, self.orientation);
+        self.orientation = self.orientation.then(rotation);
+        Some((rotation, ruf))
     }
 }
 
 impl HtmRuf {
-    pub fn from_htm(iter: impl Iterator<Item = Htm>) -> impl Iterator<Item = Self> {
+    pub const COUNT: usize = 9;
+
+    #[rustfmt::skip]
+    pub const ALL: [Self; Self::COUNT] = {
+        use HtmRuf::*;
+        [U1, U2, U3, R1, R2, R3, F1, F2, F3]
+    };
+
+    pub fn from_htm<I: Iterator<Item = Htm>>(iter: I) -> HtmRufIterator<I> {
         HtmRufIterator {
-            orientation: Rotation::default(),
+            orientation: Rotation::IDENTITY,
             iter,
         }
     }
 
-    fn reorient(htm: Htm, orientation: &Rotation) -> (Self, Rotation) {
-        #[rustfmt::ignore]
-        let rotation = match htm {
-            Htm::U1 | Htm::U2 | Htm::U3 |
-            Htm::R1 | Htm::R2 | Htm::R3 |
-            Htm::F1 | Htm::F2 | Htm::F3 => rotate(0, 0),
-            Htm::L1 => rotate(1, 1),
-            Htm::L2 => rotate(1, 2),
-            Htm::L3 => rotate(1, 3),
-            Htm::D1 => rotate(0, 1),
-            Htm::D2 => rotate(0, 2),
-            Htm::D3 => rotate(0, 3),
-            Htm::B1 => rotate(2, 1),
-            Htm::B2 => rotate(2, 2),
-            Htm::B3 => rotate(2, 3),
+    /// The face turned, relative to the current orientation of the cube.
+    pub const fn face(self) -> Face {
+        Face::ALL[self as usize / 3]
+    }
+
+    /// The number of quarter turns clockwise, from 1 to 3.
+    pub const fn amount(self) -> u8 {
+        self as u8 % 3 + 1
+    }
+
+    /// The same turn, when the cube has not been rotated.
+    pub const fn to_htm(self) -> Htm {
+        Htm::ALL[self as usize]
+    }
+
+    /// The rotation to make before the turn of `htm`, when the face at each position is given by
+    /// `orientation`, together with the turn made afterwards.
+    fn reorient(htm: Htm, orientation: Rotation) -> (Rotation, Self) {
+        let position = |orientation: Rotation| {
+            Face::ALL
+                .iter()
+                .copied()
+                .find(|&position| orientation.face(position) == htm.face())
+                .unwrap()
         };
 
-        let ruf = match htm {
-            Htm::U1 | Htm::D1 => HtmRuf::U1,
-            Htm::U2 | Htm::D2 => HtmRuf::U2,
-            Htm::U3 | Htm::D3 => HtmRuf::U3,
-            Htm::R1 | Htm::L1 => HtmRuf::R1,
-            Htm::R2 | Htm::L2 => HtmRuf::R2,
-            Htm::R3 | Htm::L3 => HtmRuf::R3,
-            Htm::F1 | Htm::B1 => HtmRuf::F1,
-            Htm::F2 | Htm::B2 => HtmRuf::F2,
-            Htm::F3 | Htm::B3 => HtmRuf::F3,
+        let rotation = rotate(position(orientation));
+        let face = position(orientation.then(rotation));
+
+        (
+            rotation,
+            Self::ALL[face as usize * 3 + htm.amount() as usize - 1],
+        )
+    }
+
+    /// The turns made by a sequence from `from_htm`, relative to the initial orientation, which
+    /// is the sequence it was made from.
+    pub fn to_htm_seq(sequence: impl IntoIterator<Item = (Rotation, Self)>) -> Vec<Htm> {
+        sequence
+            .into_iter()
+            .scan(Rotation::IDENTITY, |orientation, (rotation, turn)| {
+                *orientation = orientation.then(rotation);
+                Some(Htm::new(orientation.face(turn.face()), turn.amount()))
+            })
+            .collect()
+    }
+
+    /// A sequence from `from_htm` in the notation, with the rotations written out.
+    pub fn to_alg(sequence: impl IntoIterator<Item = (Rotation, Self)>) -> Alg {
+        let rotation = |rotation: Rotation| {
+            [Axis::X, Axis::Y, Axis::Z]
+                .iter()
+                .flat_map(|&axis| (1..4).map(move |amount| Move::new(Turn::Rotation(axis), amount)))
+                .find(|turn| turn.decompose().1 == rotation)
         };
 
-        let corners = def::Array::from(htm);
+        sequence
+            .into_iter()
+            .flat_map(|(turn_rotation, turn)| {
+                rotation(turn_rotation)
+                    .into_iter()
+                    .chain(std::iter::once(Move::from(turn.to_htm())))
+            })
+            .collect()
+    }
+}
+
+impl std::fmt::Display for HtmRuf {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_htm())
+    }
+}
+
+impl From<HtmRuf> for Htm {
+    fn from(ruf: HtmRuf) -> Self {
+        ruf.to_htm()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Htm::*;
+    use crate::puzzle::Cube3x3;
+
+    #[test]
+    pub fn robot() {
+        let sequence = [L1, D2, B3, R1, B1, U2, L3, F1, D1, B2];
+        let steps = HtmRuf::from_htm(sequence.iter().copied()).collect::<Vec<_>>();
+
+        assert_eq!(HtmRuf::to_htm_seq(steps.iter().copied()), sequence);
 
-        let orientation = orientation.permute(rotation);
+        let alg = HtmRuf::to_alg(steps.iter().copied());
+        assert!(alg.moves().iter().all(|turn| matches!(
+            turn.turn,
+            Turn::Face(Face::U | Face::R | Face::F) | Turn::Rotation(_)
+        )));
+        assert_eq!(alg.turns(), sequence);
 
-        todo!()
+        // Made on the cube, the turns leave the same state, seen from the final orientation
+        let cube = alg
+            .moves()
+            .iter()
+            .fold(Cube3x3::default(), |cube, &turn| cube.apply_move(turn));
+        assert_eq!(cube, Cube3x3::from_slice(&sequence).rotate(alg.rotation()));
     }
 }
@@ -0,0 +1,416 @@
+use crate::core::definitions as def;
+use crate::notation::{parse_tokens, ParseError, Reason};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// The corners: the five around U, the five beneath them on two faces around U, the five above
+/// those on two faces around D and the five around D, each starting nearest F and going
+/// anticlockwise seen from above. The twist of a corner is that of its facelet on U or D, or on
+/// the face of the ring it has only one facelet on, counted clockwise from the same facelet of
+/// its position.
+pub type Corners = def::Array<20, 3>;
+
+/// The edges: the five around U, the five between faces around U, the ten between faces of
+/// either ring, the five between faces around D and the five around D, in the same order as the
+/// corners. An edge is flipped when its first facelet is not on the first face of its position,
+/// where the first is the one on U or D, else the one around U, else the first going
+/// anticlockwise seen from above.
+pub type Edges = def::Array<30, 2>;
+
+/// The centres, in the order of `Face::ALL`.
+pub type Centres = def::Array<12, 1>;
+
+/// The faces of the Megaminx. F, R, BR, BL and L lie around U anticlockwise seen from above,
+/// and DR, DBR, B, DBL and DL around D, with DR beneath F and R.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Face {
+    U,
+    F,
+    R,
+    BR,
+    BL,
+    L,
+    DR,
+    DBR,
+    B,
+    DBL,
+    DL,
+    D,
+}
+
+const NAMES: [&str; 12] = [
+    "U", "F", "R", "BR", "BL", "L", "DR", "DBR", "B", "DBL", "DL", "D",
+];
+
+impl Face {
+    #[rustfmt::skip]
+    pub const ALL: [Self; 12] = {
+        use Face::*;
+        [U, F, R, BR, BL, L, DR, DBR, B, DBL, DL, D]
+    };
+
+    /// The face on the other side of the puzzle.
+    pub const fn opposite(self) -> Self {
+        use Face::*;
+        const OPPOSITE: [Face; 12] = [D, B, DBL, DL, DR, DBR, BL, L, F, R, BR, U];
+        OPPOSITE[self as usize]
+    }
+}
+
+impl std::fmt::Display for Face {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", NAMES[*self as usize])
+    }
+}
+
+/// The turns of the faces of the Megaminx, each by `amount` fifths of a turn clockwise.
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Megaminx {
+    U1, U2, U3, U4, F1, F2, F3, F4, R1, R2, R3, R4, BR1, BR2, BR3, BR4,
+    BL1, BL2, BL3, BL4, L1, L2, L3, L4, DR1, DR2, DR3, DR4, DBR1, DBR2, DBR3, DBR4,
+    B1, B2, B3, B4, DBL1, DBL2, DBL3, DBL4, DL1, DL2, DL3, DL4, D1, D2, D3, D4,
+}
+
+/// The clockwise fifth of a turn of each face.
+#[rustfmt::skip]
+const CORNER_TURNS: [Corners; 12] = [
+    Corners::new([(1, 0), (2, 0), (3, 0), (4, 0), (0, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0), (12, 0), (13, 0), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0)]), // U
+    Corners::new([(4, 1), (1, 0), (2, 0), (3, 0), (9, 2), (0, 2), (6, 0), (7, 0), (8, 0), (10, 2), (5, 2), (11, 0), (12, 0), (13, 0), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0)]), // F
+    Corners::new([(5, 2), (0, 1), (2, 0), (3, 0), (4, 0), (11, 2), (1, 2), (7, 0), (8, 0), (9, 0), (10, 0), (6, 2), (12, 0), (13, 0), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0)]), // R
+    Corners::new([(0, 0), (6, 2), (1, 1), (3, 0), (4, 0), (5, 0), (12, 2), (2, 2), (8, 0), (9, 0), (10, 0), (11, 0), (7, 2), (13, 0), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0)]), // BR
+    Corners::new([(0, 0), (1, 0), (7, 2), (2, 1), (4, 0), (5, 0), (6, 0), (13, 2), (3, 2), (9, 0), (10, 0), (11, 0), (12, 0), (8, 2), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0)]), // BL
+    Corners::new([(0, 0), (1, 0), (2, 0), (8, 2), (3, 1), (5, 0), (6, 0), (7, 0), (14, 2), (4, 2), (10, 0), (11, 0), (12, 0), (13, 0), (9, 2), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0)]), // L
+    Corners::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (10, 2), (6, 0), (7, 0), (8, 0), (9, 0), (15, 2), (5, 2), (12, 0), (13, 0), (14, 0), (16, 1), (11, 2), (17, 0), (18, 0), (19, 0)]), // DR
+    Corners::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (11, 2), (7, 0), (8, 0), (9, 0), (10, 0), (16, 2), (6, 2), (13, 0), (14, 0), (15, 0), (17, 1), (12, 2), (18, 0), (19, 0)]), // DBR
+    Corners::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (12, 2), (8, 0), (9, 0), (10, 0), (11, 0), (17, 2), (7, 2), (14, 0), (15, 0), (16, 0), (18, 1), (13, 2), (19, 0)]), // B
+    Corners::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (13, 2), (9, 0), (10, 0), (11, 0), (12, 0), (18, 2), (8, 2), (15, 0), (16, 0), (17, 0), (19, 1), (14, 2)]), // DBL
+    Corners::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0), (14, 2), (9, 2), (11, 0), (12, 0), (13, 0), (19, 2), (10, 2), (16, 0), (17, 0), (18, 0), (15, 1)]), // DL
+    Corners::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0), (12, 0), (13, 0), (14, 0), (19, 0), (15, 0), (16, 0), (17, 0), (18, 0)]), // D
+];
+
+#[rustfmt::skip]
+const EDGE_TURNS: [Edges; 12] = [
+    Edges::new([(1, 0), (2, 0), (3, 0), (4, 0), (0, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0), (12, 0), (13, 0), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0), (20, 0), (21, 0), (22, 0), (23, 0), (24, 0), (25, 0), (26, 0), (27, 0), (28, 0), (29, 0)]), // U
+    Edges::new([(9, 0), (1, 0), (2, 0), (3, 0), (4, 0), (0, 1), (6, 0), (7, 0), (8, 0), (19, 1), (5, 0), (11, 0), (12, 0), (13, 0), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (10, 0), (20, 0), (21, 0), (22, 0), (23, 0), (24, 0), (25, 0), (26, 0), (27, 0), (28, 0), (29, 0)]), // F
+    Edges::new([(0, 0), (5, 0), (2, 0), (3, 0), (4, 0), (11, 1), (1, 1), (7, 0), (8, 0), (9, 0), (10, 0), (12, 0), (6, 0), (13, 0), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0), (20, 0), (21, 0), (22, 0), (23, 0), (24, 0), (25, 0), (26, 0), (27, 0), (28, 0), (29, 0)]), // R
+    Edges::new([(0, 0), (1, 0), (6, 0), (3, 0), (4, 0), (5, 0), (13, 1), (2, 1), (8, 0), (9, 0), (10, 0), (11, 0), (12, 0), (14, 0), (7, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0), (20, 0), (21, 0), (22, 0), (23, 0), (24, 0), (25, 0), (26, 0), (27, 0), (28, 0), (29, 0)]), // BR
+    Edges::new([(0, 0), (1, 0), (2, 0), (7, 0), (4, 0), (5, 0), (6, 0), (15, 1), (3, 1), (9, 0), (10, 0), (11, 0), (12, 0), (13, 0), (14, 0), (16, 0), (8, 0), (17, 0), (18, 0), (19, 0), (20, 0), (21, 0), (22, 0), (23, 0), (24, 0), (25, 0), (26, 0), (27, 0), (28, 0), (29, 0)]), // BL
+    Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (8, 0), (5, 0), (6, 0), (7, 0), (17, 1), (4, 1), (10, 0), (11, 0), (12, 0), (13, 0), (14, 0), (15, 0), (16, 0), (18, 0), (9, 0), (19, 0), (20, 0), (21, 0), (22, 0), (23, 0), (24, 0), (25, 0), (26, 0), (27, 0), (28, 0), (29, 0)]), // L
+    Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (20, 0), (10, 0), (12, 0), (13, 0), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0), (25, 0), (11, 1), (22, 0), (23, 0), (24, 0), (21, 1), (26, 0), (27, 0), (28, 0), (29, 0)]), // DR
+    Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0), (21, 0), (12, 0), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0), (20, 0), (26, 0), (13, 1), (23, 0), (24, 0), (25, 0), (22, 1), (27, 0), (28, 0), (29, 0)]), // DBR
+    Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0), (12, 0), (13, 0), (22, 0), (14, 0), (16, 0), (17, 0), (18, 0), (19, 0), (20, 0), (21, 0), (27, 0), (15, 1), (24, 0), (25, 0), (26, 0), (23, 1), (28, 0), (29, 0)]), // B
+    Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0), (12, 0), (13, 0), (14, 0), (15, 0), (23, 0), (16, 0), (18, 0), (19, 0), (20, 0), (21, 0), (22, 0), (28, 0), (17, 1), (25, 0), (26, 0), (27, 0), (24, 1), (29, 0)]), // DBL
+    Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0), (12, 0), (13, 0), (14, 0), (15, 0), (16, 0), (17, 0), (24, 0), (18, 0), (19, 1), (21, 0), (22, 0), (23, 0), (29, 0), (25, 0), (26, 0), (27, 0), (28, 0), (20, 1)]), // DL
+    Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0), (12, 0), (13, 0), (14, 0), (15, 0), (16, 0), (17, 0), (18, 0), (19, 0), (20, 0), (21, 0), (22, 0), (23, 0), (24, 0), (29, 0), (25, 0), (26, 0), (27, 0), (28, 0)]), // D
+];
+
+impl Megaminx {
+    pub const COUNT: usize = 48;
+
+    #[rustfmt::skip]
+    pub const ALL: [Self; Self::COUNT] = {
+        use Megaminx::*;
+        [
+            U1, U2, U3, U4, F1, F2, F3, F4, R1, R2, R3, R4, BR1, BR2, BR3, BR4,
+            BL1, BL2, BL3, BL4, L1, L2, L3, L4, DR1, DR2, DR3, DR4, DBR1, DBR2, DBR3, DBR4,
+            B1, B2, B3, B4, DBL1, DBL2, DBL3, DBL4, DL1, DL2, DL3, DL4, D1, D2, D3, D4,
+        ]
+    };
+
+    pub const CORNER_MOVES: [Corners; Self::COUNT] = {
+        let mut moves = [Corners::IDENTITY; Self::COUNT];
+
+        let mut ix = 0;
+        while ix < Self::COUNT {
+            moves[ix] = match ix % 4 {
+                0 => CORNER_TURNS[ix / 4],
+                _ => moves[ix - 1].permute(&CORNER_TURNS[ix / 4]),
+            };
+            ix += 1;
+        }
+
+        moves
+    };
+
+    pub const EDGE_MOVES: [Edges; Self::COUNT] = {
+        let mut moves = [Edges::IDENTITY; Self::COUNT];
+
+        let mut ix = 0;
+        while ix < Self::COUNT {
+            moves[ix] = match ix % 4 {
+                0 => EDGE_TURNS[ix / 4],
+                _ => moves[ix - 1].permute(&EDGE_TURNS[ix / 4]),
+            };
+            ix += 1;
+        }
+
+        moves
+    };
+
+    /// The turn of `face` by `amount` fifths of a turn clockwise, which must be 1 to 4.
+    pub const fn new(face: Face, amount: u8) -> Self {
+        debug_assert!(amount >= 1 && amount <= 4, "Amount outside of turn range");
+        Self::ALL[face as usize * 4 + amount as usize - 1]
+    }
+
+    pub const fn face(self) -> Face {
+        Face::ALL[self as usize / 4]
+    }
+
+    /// The number of fifths of a turn clockwise, from 1 to 4.
+    pub const fn amount(self) -> u8 {
+        self as u8 % 4 + 1
+    }
+
+    pub const fn to_corners(self) -> &'static Corners {
+        &Self::CORNER_MOVES[self as usize]
+    }
+
+    pub const fn to_edges(self) -> &'static Edges {
+        &Self::EDGE_MOVES[self as usize]
+    }
+
+    pub const fn inverse(self) -> Self {
+        Self::new(self.face(), 5 - self.amount())
+    }
+
+    pub fn invert_seq(sequence: &[Self]) -> Vec<Self> {
+        sequence.iter().rev().map(|turn| turn.inverse()).collect()
+    }
+
+    /// Merge adjacent turns of the same face, dropping those which cancel out.
+    pub fn simplify(sequence: &[Self]) -> Vec<Self> {
+        let mut simplified: Vec<Self> = Vec::with_capacity(sequence.len());
+
+        for &turn in sequence {
+            match simplified.last() {
+                Some(&last) if last.face() == turn.face() => {
+                    simplified.pop();
+                    let amount = (last.amount() + turn.amount()) % 5;
+                    if amount != 0 {
+                        simplified.push(Self::new(turn.face(), amount));
+                    }
+                }
+                _ => simplified.push(turn),
+            }
+        }
+
+        simplified
+    }
+
+    /// Parse turns separated by whitespace, e.g. `R U2' DBR F'`.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        parse_tokens(str, Self::from_str)
+    }
+
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        iter.map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Megaminx {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let face = self.face();
+
+        match self.amount() {
+            1 => write!(f, "{}", face),
+            2 => write!(f, "{}2", face),
+            3 => write!(f, "{}2'", face),
+            _ => write!(f, "{}'", face),
+        }
+    }
+}
+
+impl FromStr for Megaminx {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseError::new(value, 0, value, reason);
+
+        let split = value
+            .find(|c: char| !c.is_ascii_uppercase())
+            .unwrap_or(value.len());
+        let (name, suffix) = value.split_at(split);
+        let face = NAMES
+            .iter()
+            .position(|&face| face == name)
+            .ok_or_else(|| error(Reason::UnknownFace))?;
+
+        let amount = match suffix {
+            "" => 1,
+            "2" => 2,
+            "2'" => 3,
+            "'" => 4,
+            _ => return Err(error(Reason::BadSuffix)),
+        };
+
+        Ok(Self::new(Face::ALL[face], amount))
+    }
+}
+
+impl TryFrom<usize> for Megaminx {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::ALL.get(value).copied().ok_or(())
+    }
+}
+
+impl From<Megaminx> for usize {
+    fn from(val: Megaminx) -> Self {
+        val as usize
+    }
+}
+
+/// The moves of the scrambles of the WCA, as introduced by Stefan Pochmann. `R++` and `R--` turn
+/// all but the face opposite R, DBL, by two fifths of a turn clockwise and anticlockwise seen
+/// from R, `D++` and `D--` turn all but U the same way seen from D, and `U` and `U'` are fifths
+/// of a turn of U.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Pochmann {
+    R2,
+    R3,
+    D2,
+    D3,
+    U1,
+    U4,
+}
+
+#[rustfmt::skip]
+const R_CORNERS: Corners = Corners::new([(11, 1), (5, 0), (10, 2), (15, 1), (16, 2), (6, 1), (0, 0), (9, 2), (8, 0), (17, 1), (12, 1), (1, 1), (4, 2), (13, 0), (14, 0), (7, 1), (2, 2), (3, 1), (18, 0), (19, 0)]);
+#[rustfmt::skip]
+const R_EDGES: Edges = Edges::new([(21, 0), (11, 1), (10, 1), (20, 1), (25, 1), (12, 1), (5, 1), (19, 0), (29, 1), (26, 0), (13, 1), (6, 0), (1, 1), (0, 1), (9, 1), (18, 1), (16, 0), (17, 0), (27, 0), (22, 0), (14, 1), (2, 1), (4, 0), (23, 0), (24, 0), (7, 1), (3, 1), (8, 0), (28, 0), (15, 0)]);
+#[rustfmt::skip]
+const R_CENTRES: Centres = Centres::new([(6, 0), (7, 0), (2, 0), (1, 0), (10, 0), (11, 0), (3, 0), (0, 0), (5, 0), (9, 0), (8, 0), (4, 0)]);
+#[rustfmt::skip]
+const D_CORNERS: Corners = Corners::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (8, 0), (9, 0), (5, 0), (6, 0), (7, 0), (13, 0), (14, 0), (10, 0), (11, 0), (12, 0), (18, 0), (19, 0), (15, 0), (16, 0), (17, 0)]);
+#[rustfmt::skip]
+const D_EDGES: Edges = Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (8, 0), (9, 0), (5, 0), (6, 0), (7, 0), (16, 0), (17, 0), (18, 0), (19, 0), (10, 0), (11, 0), (12, 0), (13, 0), (14, 0), (15, 0), (23, 0), (24, 0), (20, 0), (21, 0), (22, 0), (28, 0), (29, 0), (25, 0), (26, 0), (27, 0)]);
+#[rustfmt::skip]
+const D_CENTRES: Centres = Centres::new([(0, 0), (4, 0), (5, 0), (1, 0), (2, 0), (3, 0), (9, 0), (10, 0), (6, 0), (7, 0), (8, 0), (11, 0)]);
+
+impl Pochmann {
+    pub const COUNT: usize = 6;
+
+    pub const ALL: [Self; Self::COUNT] = {
+        use Pochmann::*;
+        [R2, R3, D2, D3, U1, U4]
+    };
+
+    pub const fn to_corners(self) -> Corners {
+        match self {
+            Pochmann::R2 => R_CORNERS,
+            Pochmann::R3 => R_CORNERS.inverse(),
+            Pochmann::D2 => D_CORNERS,
+            Pochmann::D3 => D_CORNERS.inverse(),
+            Pochmann::U1 => *Megaminx::U1.to_corners(),
+            Pochmann::U4 => *Megaminx::U4.to_corners(),
+        }
+    }
+
+    pub const fn to_edges(self) -> Edges {
+        match self {
+            Pochmann::R2 => R_EDGES,
+            Pochmann::R3 => R_EDGES.inverse(),
+            Pochmann::D2 => D_EDGES,
+            Pochmann::D3 => D_EDGES.inverse(),
+            Pochmann::U1 => *Megaminx::U1.to_edges(),
+            Pochmann::U4 => *Megaminx::U4.to_edges(),
+        }
+    }
+
+    /// The centres, which only `R` and `D` move.
+    pub const fn to_centres(self) -> Centres {
+        match self {
+            Pochmann::R2 => R_CENTRES,
+            Pochmann::R3 => R_CENTRES.inverse(),
+            Pochmann::D2 => D_CENTRES,
+            Pochmann::D3 => D_CENTRES.inverse(),
+            Pochmann::U1 | Pochmann::U4 => Centres::IDENTITY,
+        }
+    }
+
+    pub const fn inverse(self) -> Self {
+        Self::ALL[self as usize ^ 1]
+    }
+
+    /// Parse moves separated by whitespace, including line breaks, e.g. `R++ D-- U'`.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        parse_tokens(str, Self::from_str)
+    }
+
+    /// The moves separated by spaces, with a line break after each turn of U as in the
+    /// scrambles of the WCA.
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        let mut formatted = String::new();
+
+        for turn in iter {
+            if !formatted.is_empty() && !formatted.ends_with('\n') {
+                formatted.push(' ');
+            }
+            formatted.push_str(&turn.to_string());
+            if let Pochmann::U1 | Pochmann::U4 = turn {
+                formatted.push('\n');
+            }
+        }
+
+        formatted.trim_end().to_string()
+    }
+}
+
+impl std::fmt::Display for Pochmann {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Pochmann::R2 => "R++",
+            Pochmann::R3 => "R--",
+            Pochmann::D2 => "D++",
+            Pochmann::D3 => "D--",
+            Pochmann::U1 => "U",
+            Pochmann::U4 => "U'",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for Pochmann {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseError::new(value, 0, value, reason);
+
+        let mut chars = value.chars();
+        let face = chars.next().ok_or_else(|| error(Reason::UnknownFace))?;
+
+        match (face, chars.as_str()) {
+            ('R', "++") => Ok(Pochmann::R2),
+            ('R', "--") => Ok(Pochmann::R3),
+            ('D', "++") => Ok(Pochmann::D2),
+            ('D', "--") => Ok(Pochmann::D3),
+            ('U', "") => Ok(Pochmann::U1),
+            ('U', "'") => Ok(Pochmann::U4),
+            ('R' | 'D' | 'U', _) => Err(error(Reason::BadSuffix)),
+            _ => Err(error(Reason::UnknownFace)),
+        }
+    }
+}
+
+impl TryFrom<usize> for Pochmann {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::ALL.get(value).copied().ok_or(())
+    }
+}
+
+impl From<Pochmann> for usize {
+    fn from(val: Pochmann) -> Self {
+        val as usize
+    }
+}
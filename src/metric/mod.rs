@@ -1,5 +1,68 @@
+pub mod clock;
+pub mod cube4x4;
+pub mod cube_n;
+pub mod floppy;
 pub mod htm;
+pub mod megaminx;
+pub mod pyraminx;
+pub mod qtm;
+pub mod skewb;
+pub mod square1;
+pub mod stm;
 
-pub use htm::Htm;
-// pub use htm::ruf::HtmRuf;
+pub use clock::{Clock, Pins};
+pub use cube4x4::Cube4x4;
+pub use cube_n::CubeN;
+pub use floppy::Floppy;
 pub use htm::domino::Domino;
+pub use htm::ruf::HtmRuf;
+pub use htm::Htm;
+pub use megaminx::{Megaminx, Pochmann};
+pub use pyraminx::Pyraminx;
+pub use qtm::Qtm;
+pub use skewb::Skewb;
+pub use square1::Square1;
+pub use stm::Stm;
+
+use crate::notation::Axis;
+use htm::{Corners, Edges, Face};
+use std::fmt::{Debug, Display};
+
+/// A set of face turns of the cube, each of which counts as one move. The index of a move, as
+/// used by the move tables of the solvers, is its position in `ALL`.
+pub trait Metric: Copy + Eq + Debug + Display + 'static {
+    const COUNT: usize;
+
+    const ALL: &'static [Self];
+
+    fn index(self) -> usize;
+
+    /// The same move as a turn in `Htm`.
+    fn to_htm(self) -> Htm;
+
+    /// The move making the same turn as `htm`, if there is one in this metric.
+    fn from_htm(htm: Htm) -> Option<Self> {
+        Self::ALL.iter().copied().find(|turn| turn.to_htm() == htm)
+    }
+
+    /// The move which undoes this one. Every metric includes the inverse of each of its moves.
+    fn inverse(self) -> Self {
+        Self::from_htm(self.to_htm().inverse()).unwrap()
+    }
+
+    fn face(self) -> Face {
+        self.to_htm().face()
+    }
+
+    fn axis(self) -> Axis {
+        self.face().axis()
+    }
+
+    fn corners(self) -> &'static Corners {
+        self.to_htm().to_corners()
+    }
+
+    fn edges(self) -> &'static Edges {
+        self.to_htm().to_edges()
+    }
+}
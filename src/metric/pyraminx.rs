@@ -0,0 +1,163 @@
+use crate::core::definitions as def;
+use crate::notation::{parse_tokens, ParseError, Reason};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// The edges, in the order UL, UR, UB, LR, RB, BL. An edge is flipped when its sticker on the
+/// first of its two faces, taken in the order of the vertices they are opposite, is not the one
+/// of lower colour.
+pub type Edges = def::Array<6, 2>;
+
+/// Pieces which twist in place, one at each of the vertices U, L, R and B, such as the tips and
+/// the centres beneath them.
+pub type Twists = def::Array<4, 3>;
+
+/// The moves of the Pyraminx, each a third of a turn, clockwise for `1` and anticlockwise for `2`
+/// when seen from the vertex. Upper case turns the vertex together with the layer beneath it and
+/// lower case the tip alone.
+#[rustfmt::skip]
+#[allow(non_camel_case_types)]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Pyraminx {
+    U1, U2, L1, L2, R1, R2, B1, B2,
+    u1, u2, l1, l2, r1, r2, b1, b2,
+}
+
+const VERTICES: [char; 4] = ['U', 'L', 'R', 'B'];
+
+impl Pyraminx {
+    pub const COUNT: usize = 16;
+
+    #[rustfmt::skip]
+    pub const ALL: [Self; Self::COUNT] = {
+        use Pyraminx::*;
+        [U1, U2, L1, L2, R1, R2, B1, B2, u1, u2, l1, l2, r1, r2, b1, b2]
+    };
+
+    /// The turns of the layers, which leave nothing but the tips out of place once undone.
+    pub const LAYERS: [Self; 8] = {
+        use Pyraminx::*;
+        [U1, U2, L1, L2, R1, R2, B1, B2]
+    };
+
+    /// Turns of the tips leave the edges where they are.
+    pub const EDGE_MOVES: [Edges; Self::COUNT] = {
+        #[rustfmt::skip]
+        const LAYER_MOVES: [Edges; 8] = [
+            Edges::new([(1, 1), (2, 1), (0, 0), (3, 0), (4, 0), (5, 0)]), // U
+            Edges::new([(2, 0), (0, 1), (1, 1), (3, 0), (4, 0), (5, 0)]), // U'
+            Edges::new([(5, 0), (1, 0), (2, 0), (0, 1), (4, 0), (3, 1)]), // L
+            Edges::new([(3, 1), (1, 0), (2, 0), (5, 1), (4, 0), (0, 0)]), // L'
+            Edges::new([(0, 0), (3, 1), (2, 0), (4, 1), (1, 0), (5, 0)]), // R
+            Edges::new([(0, 0), (4, 0), (2, 0), (1, 1), (3, 1), (5, 0)]), // R'
+            Edges::new([(0, 0), (1, 0), (4, 0), (3, 0), (5, 1), (2, 1)]), // B
+            Edges::new([(0, 0), (1, 0), (5, 1), (3, 0), (2, 0), (4, 1)]), // B'
+        ];
+
+        let mut moves = [Edges::IDENTITY; Self::COUNT];
+
+        let mut ix = 0;
+        while ix < LAYER_MOVES.len() {
+            moves[ix] = LAYER_MOVES[ix];
+            ix += 1;
+        }
+
+        moves
+    };
+
+    /// The twist of the pieces at each vertex, which is that of the centres for turns of the
+    /// layers and of the tips for all turns.
+    pub const fn twists(self) -> Twists {
+        let mut twists = [(0, 0), (1, 0), (2, 0), (3, 0)];
+        twists[self.vertex() as usize].1 = self.amount();
+        Twists::new(twists)
+    }
+
+    /// The vertex turned, as an index into U, L, R and B.
+    pub const fn vertex(self) -> u8 {
+        self as u8 / 2 % 4
+    }
+
+    /// The number of thirds of a turn clockwise, 1 or 2.
+    pub const fn amount(self) -> u8 {
+        self as u8 % 2 + 1
+    }
+
+    pub const fn is_tip(self) -> bool {
+        self as usize >= Self::LAYERS.len()
+    }
+
+    /// The turn of the vertex `vertex` by `amount` thirds, which turns the tip alone if `tip`.
+    pub const fn new(vertex: u8, amount: u8, tip: bool) -> Self {
+        Self::ALL[tip as usize * 8 + vertex as usize * 2 + amount as usize - 1]
+    }
+
+    pub const fn inverse(self) -> Self {
+        Self::ALL[self as usize ^ 1]
+    }
+
+    pub fn invert_seq(sequence: &[Self]) -> Vec<Self> {
+        sequence.iter().rev().map(|turn| turn.inverse()).collect()
+    }
+
+    /// Parse turns separated by whitespace, e.g. `U L' r b'`.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        parse_tokens(str, Self::from_str)
+    }
+
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        iter.map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Pyraminx {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let vertex = VERTICES[self.vertex() as usize];
+        let vertex = if self.is_tip() {
+            vertex.to_ascii_lowercase()
+        } else {
+            vertex
+        };
+
+        match self.amount() {
+            1 => write!(f, "{}", vertex),
+            _ => write!(f, "{}'", vertex),
+        }
+    }
+}
+
+impl FromStr for Pyraminx {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut chars = value.chars();
+        let first = chars.next();
+        let vertex = first
+            .and_then(|c| VERTICES.iter().position(|&v| v == c.to_ascii_uppercase()))
+            .ok_or_else(|| ParseError::new(value, 0, value, Reason::UnknownFace))?;
+        let tip = value.starts_with(|c: char| c.is_ascii_lowercase());
+
+        match chars.as_str() {
+            "" => Ok(Self::new(vertex as u8, 1, tip)),
+            "'" => Ok(Self::new(vertex as u8, 2, tip)),
+            _ => Err(ParseError::new(value, 0, value, Reason::BadSuffix)),
+        }
+    }
+}
+
+impl TryFrom<usize> for Pyraminx {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::ALL.get(value).copied().ok_or(())
+    }
+}
+
+impl From<Pyraminx> for usize {
+    fn from(val: Pyraminx) -> Self {
+        val as usize
+    }
+}
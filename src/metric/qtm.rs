@@ -0,0 +1,171 @@
+use super::htm::{Corners, Edges, Face};
+use super::{Htm, Metric};
+use crate::util;
+use std::convert::TryFrom;
+
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Qtm {
+    U1, U3, R1, R3, F1, F3, L1, L3, D1, D3, B1, B3
+}
+
+impl Qtm {
+    pub const COUNT: usize = 12;
+
+    pub const GENERATORS: [usize; Self::COUNT] = util::count::<{ Self::COUNT }>();
+
+    #[rustfmt::skip]
+    pub const ALL: [Self; Self::COUNT] = {
+        use Qtm::*;
+        [U1, U3, R1, R3, F1, F3, L1, L3, D1, D3, B1, B3]
+    };
+
+    pub const CORNER_MOVES: [Corners; Self::COUNT] = {
+        let mut moves = [Corners::IDENTITY; Self::COUNT];
+
+        let mut ix = 0;
+        while ix < Self::COUNT {
+            moves[ix] = *Self::ALL[ix].to_htm().to_corners();
+            ix += 1;
+        }
+
+        moves
+    };
+
+    pub const EDGE_MOVES: [Edges; Self::COUNT] = {
+        let mut moves = [Edges::IDENTITY; Self::COUNT];
+
+        let mut ix = 0;
+        while ix < Self::COUNT {
+            moves[ix] = *Self::ALL[ix].to_htm().to_edges();
+            ix += 1;
+        }
+
+        moves
+    };
+
+    pub const fn face(self) -> Face {
+        Face::ALL[self as usize / 2]
+    }
+
+    pub const fn is_clockwise(self) -> bool {
+        self as u8 & 1 == 0
+    }
+
+    pub const fn to_htm(self) -> Htm {
+        Htm::new(self.face(), if self.is_clockwise() { 1 } else { 3 })
+    }
+
+    pub const fn inverse(self) -> Self {
+        Self::ALL[self as usize ^ 1]
+    }
+
+    /// The quarter turns making up `sequence`, where a half turn becomes two clockwise turns.
+    pub fn from_htm_seq(sequence: &[Htm]) -> Vec<Self> {
+        sequence
+            .iter()
+            .flat_map(|&turn| {
+                let quarter = Self::ALL[turn.face() as usize * 2 + (turn.amount() == 3) as usize];
+                let count = if turn.amount() == 2 { 2 } else { 1 };
+                vec![quarter; count]
+            })
+            .collect()
+    }
+
+    /// `sequence` in face turns, with consecutive turns of a face merged into one.
+    pub fn to_htm_seq(sequence: &[Self]) -> Vec<Htm> {
+        Htm::simplify(sequence.iter().map(|turn| turn.to_htm()))
+    }
+
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        iter.map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect()
+    }
+}
+
+impl Metric for Qtm {
+    const COUNT: usize = Self::COUNT;
+
+    const ALL: &'static [Self] = &Self::ALL;
+
+    fn index(self) -> usize {
+        self as usize
+    }
+
+    fn to_htm(self) -> Htm {
+        self.to_htm()
+    }
+}
+
+impl std::fmt::Display for Qtm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_htm())
+    }
+}
+
+impl From<Qtm> for Htm {
+    fn from(qtm: Qtm) -> Self {
+        qtm.to_htm()
+    }
+}
+
+impl TryFrom<Htm> for Qtm {
+    type Error = ();
+
+    fn try_from(htm: Htm) -> Result<Self, Self::Error> {
+        match htm.amount() {
+            1 => Ok(Self::ALL[htm.face() as usize * 2]),
+            3 => Ok(Self::ALL[htm.face() as usize * 2 + 1]),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<usize> for Qtm {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::ALL.get(value).copied().ok_or(())
+    }
+}
+
+impl From<Qtm> for usize {
+    fn from(val: Qtm) -> Self {
+        val as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::puzzle::Cube3x3;
+
+    #[test]
+    pub fn conversions() {
+        let sequence = [Htm::R2, Htm::U3, Htm::F1, Htm::D2];
+        let quarters = Qtm::from_htm_seq(&sequence);
+
+        use Qtm::*;
+        assert_eq!(quarters, [R1, R1, U3, F1, D1, D1]);
+        assert_eq!(Qtm::to_htm_seq(&quarters), sequence);
+        assert_eq!(
+            Cube3x3::from_slice(&sequence),
+            Cube3x3::default().apply_seq(quarters.iter().map(|&turn| Htm::from(turn)))
+        );
+
+        for turn in Qtm::ALL {
+            assert_eq!(Qtm::try_from(turn.to_htm()), Ok(turn));
+            assert_eq!(turn.inverse().to_htm(), turn.to_htm().inverse());
+            assert_eq!(
+                Cube3x3::new(
+                    Qtm::CORNER_MOVES[turn as usize],
+                    Qtm::EDGE_MOVES[turn as usize]
+                ),
+                Cube3x3::from(turn.to_htm())
+            );
+        }
+        assert_eq!(Qtm::try_from(Htm::B2), Err(()));
+    }
+}
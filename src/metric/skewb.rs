@@ -0,0 +1,133 @@
+use crate::core::definitions as def;
+use crate::notation::{parse_tokens, ParseError, Reason};
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+/// The corners, in the same order as for the cube.
+pub type Corners = def::Array<8, 3>;
+
+/// The centres, in the order U, R, F, D, L, B.
+pub type Centres = def::Array<6, 1>;
+
+/// The moves of the Skewb in the notation of the WCA, each a third of a turn of the half of the
+/// puzzle around one corner, clockwise for `1` and anticlockwise for `2` when seen from that
+/// corner. R turns around DRB, U around ULB, L around DLF and B around DBL, so that URF never
+/// moves.
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Skewb {
+    R1, R2, U1, U2, L1, L2, B1, B2,
+}
+
+const AXES: [char; 4] = ['R', 'U', 'L', 'B'];
+
+impl Skewb {
+    pub const COUNT: usize = 8;
+
+    #[rustfmt::skip]
+    pub const ALL: [Self; Self::COUNT] = {
+        use Skewb::*;
+        [R1, R2, U1, U2, L1, L2, B1, B2]
+    };
+
+    #[rustfmt::skip]
+    pub const CORNER_MOVES: [Corners; Self::COUNT] = [
+        Corners::new([(0, 0), (1, 0), (2, 0), (4, 2), (6, 2), (5, 0), (3, 2), (7, 1)]), // R
+        Corners::new([(0, 0), (1, 0), (2, 0), (6, 1), (3, 1), (5, 0), (4, 1), (7, 2)]), // R'
+        Corners::new([(0, 0), (3, 2), (2, 1), (6, 2), (4, 0), (5, 0), (1, 2), (7, 0)]), // U
+        Corners::new([(0, 0), (6, 1), (2, 2), (1, 1), (4, 0), (5, 0), (3, 1), (7, 0)]), // U'
+        Corners::new([(0, 0), (6, 2), (2, 0), (3, 0), (1, 2), (5, 1), (4, 2), (7, 0)]), // L
+        Corners::new([(0, 0), (4, 1), (2, 0), (3, 0), (6, 1), (5, 2), (1, 1), (7, 0)]), // L'
+        Corners::new([(0, 0), (1, 0), (7, 2), (3, 0), (4, 0), (2, 2), (6, 1), (5, 2)]), // B
+        Corners::new([(0, 0), (1, 0), (5, 1), (3, 0), (4, 0), (7, 1), (6, 2), (2, 1)]), // B'
+    ];
+
+    #[rustfmt::skip]
+    pub const CENTRE_MOVES: [Centres; Self::COUNT] = [
+        Centres::new([(0, 0), (3, 0), (2, 0), (5, 0), (4, 0), (1, 0)]), // R
+        Centres::new([(0, 0), (5, 0), (2, 0), (1, 0), (4, 0), (3, 0)]), // R'
+        Centres::new([(5, 0), (1, 0), (2, 0), (3, 0), (0, 0), (4, 0)]), // U
+        Centres::new([(4, 0), (1, 0), (2, 0), (3, 0), (5, 0), (0, 0)]), // U'
+        Centres::new([(0, 0), (1, 0), (4, 0), (2, 0), (3, 0), (5, 0)]), // L
+        Centres::new([(0, 0), (1, 0), (3, 0), (4, 0), (2, 0), (5, 0)]), // L'
+        Centres::new([(0, 0), (1, 0), (2, 0), (4, 0), (5, 0), (3, 0)]), // B
+        Centres::new([(0, 0), (1, 0), (2, 0), (5, 0), (3, 0), (4, 0)]), // B'
+    ];
+
+    /// The axis turned, as an index into R, U, L and B.
+    pub const fn axis(self) -> u8 {
+        self as u8 / 2
+    }
+
+    /// The number of thirds of a turn clockwise, 1 or 2.
+    pub const fn amount(self) -> u8 {
+        self as u8 % 2 + 1
+    }
+
+    pub const fn new(axis: u8, amount: u8) -> Self {
+        Self::ALL[axis as usize * 2 + amount as usize - 1]
+    }
+
+    pub const fn inverse(self) -> Self {
+        Self::ALL[self as usize ^ 1]
+    }
+
+    pub fn invert_seq(sequence: &[Self]) -> Vec<Self> {
+        sequence.iter().rev().map(|turn| turn.inverse()).collect()
+    }
+
+    /// Parse turns separated by whitespace, e.g. `R U' L B'`.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        parse_tokens(str, Self::from_str)
+    }
+
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        iter.map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Skewb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let axis = AXES[self.axis() as usize];
+
+        match self.amount() {
+            1 => write!(f, "{}", axis),
+            _ => write!(f, "{}'", axis),
+        }
+    }
+}
+
+impl FromStr for Skewb {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let mut chars = value.chars();
+        let axis = chars
+            .next()
+            .and_then(|c| AXES.iter().position(|&axis| axis == c))
+            .ok_or_else(|| ParseError::new(value, 0, value, Reason::UnknownFace))?;
+
+        match chars.as_str() {
+            "" => Ok(Self::new(axis as u8, 1)),
+            "'" => Ok(Self::new(axis as u8, 2)),
+            _ => Err(ParseError::new(value, 0, value, Reason::BadSuffix)),
+        }
+    }
+}
+
+impl TryFrom<usize> for Skewb {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::ALL.get(value).copied().ok_or(())
+    }
+}
+
+impl From<Skewb> for usize {
+    fn from(val: Skewb) -> Self {
+        val as usize
+    }
+}
@@ -0,0 +1,153 @@
+use crate::notation::{ParseError, Reason};
+use std::str::FromStr;
+
+/// The moves of the Square-1 in the notation of the WCA. `(x, y)` turns the top layer by `x`
+/// and the bottom layer by `y` twelfths of a turn, clockwise as seen from each and from -5 to 6,
+/// and `/` turns the right half of the puzzle by a half turn.
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Square1 {
+    Turn(i8, i8),
+    Twist,
+}
+
+impl Square1 {
+    /// The turn of the layers by `top` and `bottom` twelfths clockwise, which may be any number
+    /// and are reduced to the range from -5 to 6.
+    pub const fn turn(top: i8, bottom: i8) -> Self {
+        Square1::Turn(reduce(top), reduce(bottom))
+    }
+
+    pub const fn inverse(self) -> Self {
+        match self {
+            Square1::Turn(top, bottom) => Self::turn(-top, -bottom),
+            Square1::Twist => Square1::Twist,
+        }
+    }
+
+    pub fn invert_seq(sequence: &[Self]) -> Vec<Self> {
+        sequence.iter().rev().map(|turn| turn.inverse()).collect()
+    }
+
+    /// Merge adjacent turns of the layers, dropping those which cancel out, as well as pairs of
+    /// twists which do.
+    pub fn simplify(sequence: &[Self]) -> Vec<Self> {
+        let mut simplified: Vec<Self> = Vec::with_capacity(sequence.len());
+
+        for &turn in sequence {
+            match (simplified.last(), turn) {
+                (Some(&Square1::Turn(a, b)), Square1::Turn(c, d)) => {
+                    simplified.pop();
+                    simplified.push(Self::turn(a + c, b + d));
+                }
+                (Some(Square1::Twist), Square1::Twist) => {
+                    simplified.pop();
+                }
+                _ => simplified.push(turn),
+            }
+
+            if let Some(Square1::Turn(0, 0)) = simplified.last() {
+                simplified.pop();
+            }
+        }
+
+        simplified
+    }
+
+    /// Parse moves with or without whitespace between them, e.g. `(1, 0)/ (-3, 3) /`.
+    pub fn parse(str: &str) -> Result<Vec<Self>, ParseError> {
+        let mut moves = vec![];
+
+        let mut offset = 0;
+        while offset < str.len() {
+            let rest = &str[offset..];
+            let token = match rest.chars().next() {
+                Some(c) if c.is_whitespace() => {
+                    offset += c.len_utf8();
+                    continue;
+                }
+                Some('(') => match rest.find(')') {
+                    Some(ix) => &rest[..=ix],
+                    None => {
+                        return Err(ParseError::new(
+                            str,
+                            offset,
+                            rest,
+                            Reason::UnbalancedBracket,
+                        ))
+                    }
+                },
+                Some(c) => &rest[..c.len_utf8()],
+                None => break,
+            };
+
+            let turn = token
+                .parse::<Self>()
+                .map_err(|error| error.within(str, offset))?;
+            moves.push(turn);
+            offset += token.len();
+        }
+
+        Ok(moves)
+    }
+
+    /// The moves in the notation of the WCA, e.g. `(1, 0) / (-3, 3) /`.
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        iter.map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect()
+    }
+}
+
+/// The same number of twelfths of a turn, from -5 to 6.
+const fn reduce(amount: i8) -> i8 {
+    let amount = amount.rem_euclid(12);
+    if amount > 6 {
+        amount - 12
+    } else {
+        amount
+    }
+}
+
+impl std::fmt::Display for Square1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Square1::Turn(top, bottom) => write!(f, "({}, {})", top, bottom),
+            Square1::Twist => write!(f, "/"),
+        }
+    }
+}
+
+impl FromStr for Square1 {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let error = |reason| ParseError::new(value, 0, value, reason);
+
+        if value == "/" {
+            return Ok(Square1::Twist);
+        }
+
+        let inner = value
+            .strip_prefix('(')
+            .ok_or_else(|| error(Reason::UnknownFace))?
+            .strip_suffix(')')
+            .ok_or_else(|| error(Reason::UnbalancedBracket))?;
+        let comma = inner
+            .find(',')
+            .ok_or_else(|| error(Reason::MissingSeparator))?;
+
+        let amount = |digits: &str| {
+            digits
+                .trim()
+                .parse::<i8>()
+                .ok()
+                .filter(|amount| (-5..=6).contains(amount))
+                .ok_or_else(|| error(Reason::BadAmount))
+        };
+
+        Ok(Square1::Turn(
+            amount(&inner[..comma])?,
+            amount(&inner[comma + 1..])?,
+        ))
+    }
+}
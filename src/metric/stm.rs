@@ -0,0 +1,237 @@
+use super::htm::{Corners, Edges, Face};
+use super::Htm;
+use crate::notation::{Move, Rotation, Slice, Turn};
+use crate::util;
+use std::convert::TryFrom;
+
+/// The slice turn metric, where a turn of any face or of any of the middle slices counts as one.
+/// Slice turns move the centres, which are tracked by `Cube3x3` as the orientation returned by
+/// `Stm::rotation`.
+#[rustfmt::skip]
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[repr(u8)]
+pub enum Stm {
+    U1, U2, U3, R1, R2, R3, F1, F2, F3, L1, L2, L3, D1, D2, D3, B1, B2, B3,
+    M1, M2, M3, E1, E2, E3, S1, S2, S3
+}
+
+impl Stm {
+    pub const COUNT: usize = 27;
+
+    pub const GENERATORS: [usize; Self::COUNT] = util::count::<{ Self::COUNT }>();
+
+    #[rustfmt::skip]
+    pub const ALL: [Self; Self::COUNT] = {
+        use Stm::*;
+        [
+            U1, U2, U3, R1, R2, R3, F1, F2, F3, L1, L2, L3, D1, D2, D3, B1, B2, B3,
+            M1, M2, M3, E1, E2, E3, S1, S2, S3,
+        ]
+    };
+
+    /// The slices in the order of their turns.
+    pub const SLICES: [Slice; 3] = [Slice::M, Slice::E, Slice::S];
+
+    /// Slice turns leave the corners where they are.
+    pub const CORNER_MOVES: [Corners; Self::COUNT] = {
+        let mut moves = [Corners::IDENTITY; Self::COUNT];
+
+        let mut ix = 0;
+        while ix < Htm::COUNT {
+            moves[ix] = Htm::CORNER_MOVES[ix];
+            ix += 1;
+        }
+
+        moves
+    };
+
+    pub const EDGE_MOVES: [Edges; Self::COUNT] = {
+        #[rustfmt::skip]
+        const SLICE_MOVES: [Edges; 9] = [
+            Edges::new([(0, 0), (3, 1), (2, 0), (7, 1), (4, 0), (1, 1), (6, 0), (5, 1), (8, 0), (9, 0), (10, 0), (11, 0)]), // M
+            Edges::new([(0, 0), (7, 0), (2, 0), (5, 0), (4, 0), (3, 0), (6, 0), (1, 0), (8, 0), (9, 0), (10, 0), (11, 0)]), // M2
+            Edges::new([(0, 0), (5, 1), (2, 0), (1, 1), (4, 0), (7, 1), (6, 0), (3, 1), (8, 0), (9, 0), (10, 0), (11, 0)]), // M'
+            Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (9, 1), (10, 1), (11, 1), (8, 1)]), // E
+            Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (10, 0), (11, 0), (8, 0), (9, 0)]), // E2
+            Edges::new([(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0), (6, 0), (7, 0), (11, 1), (8, 1), (9, 1), (10, 1)]), // E'
+            Edges::new([(2, 1), (1, 0), (6, 1), (3, 0), (0, 1), (5, 0), (4, 1), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0)]), // S
+            Edges::new([(6, 0), (1, 0), (4, 0), (3, 0), (2, 0), (5, 0), (0, 0), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0)]), // S2
+            Edges::new([(4, 1), (1, 0), (0, 1), (3, 0), (6, 1), (5, 0), (2, 1), (7, 0), (8, 0), (9, 0), (10, 0), (11, 0)]), // S'
+        ];
+
+        let mut moves = [Edges::IDENTITY; Self::COUNT];
+
+        let mut ix = 0;
+        while ix < Self::COUNT {
+            moves[ix] = if ix < Htm::COUNT {
+                Htm::EDGE_MOVES[ix]
+            } else {
+                SLICE_MOVES[ix - Htm::COUNT]
+            };
+            ix += 1;
+        }
+
+        moves
+    };
+
+    /// The number of quarter turns clockwise, from 1 to 3. Slices turn clockwise in the
+    /// direction of L, D and F respectively.
+    pub const fn amount(self) -> u8 {
+        self as u8 % 3 + 1
+    }
+
+    /// The turn of a face, or `None` for a slice turn.
+    pub const fn to_htm(self) -> Option<Htm> {
+        if (self as usize) < Htm::COUNT {
+            Some(Htm::ALL[self as usize])
+        } else {
+            None
+        }
+    }
+
+    pub const fn from_htm(htm: Htm) -> Self {
+        Self::ALL[htm as usize]
+    }
+
+    pub const fn from_slice(slice: Slice, amount: u8) -> Self {
+        let slice = match slice {
+            Slice::M => 0,
+            Slice::E => 1,
+            Slice::S => 2,
+        };
+
+        Self::ALL[Htm::COUNT + slice * 3 + amount as usize - 1]
+    }
+
+    pub const fn to_move(self) -> Move {
+        let turn = match self.to_htm() {
+            Some(htm) => Turn::Face(htm.face()),
+            None => Turn::Slice(Self::SLICES[(self as usize - Htm::COUNT) / 3]),
+        };
+
+        Move {
+            turn,
+            amount: self.amount(),
+        }
+    }
+
+    pub const fn inverse(self) -> Self {
+        let ix = self as usize;
+        Self::ALL[ix - ix % 3 + 2 - ix % 3]
+    }
+
+    /// Whether the two turns move pieces on the same axis, in which case they commute.
+    pub const fn commutes(self, other: Self) -> bool {
+        // The axes of U, R, F, L, D, B, M, E and S
+        const AXES: [u8; 9] = [0, 1, 2, 1, 0, 2, 1, 0, 2];
+        AXES[self as usize / 3] == AXES[other as usize / 3]
+    }
+
+    /// The movement of the centres, which is only non-trivial for slice turns.
+    pub const fn rotation(self) -> Rotation {
+        match self.to_move().turn {
+            Turn::Slice(Slice::M) => Rotation::of(Face::L).pow(self.amount()),
+            Turn::Slice(Slice::E) => Rotation::of(Face::D).pow(self.amount()),
+            Turn::Slice(Slice::S) => Rotation::of(Face::F).pow(self.amount()),
+            _ => Rotation::IDENTITY,
+        }
+    }
+
+    pub fn format_seq(iter: impl Iterator<Item = Self>) -> String {
+        iter.map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect()
+    }
+}
+
+impl std::fmt::Display for Stm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_move())
+    }
+}
+
+impl From<Htm> for Stm {
+    fn from(htm: Htm) -> Self {
+        Self::from_htm(htm)
+    }
+}
+
+impl From<Stm> for Move {
+    fn from(stm: Stm) -> Self {
+        stm.to_move()
+    }
+}
+
+impl TryFrom<Stm> for Htm {
+    type Error = ();
+
+    fn try_from(stm: Stm) -> Result<Self, Self::Error> {
+        stm.to_htm().ok_or(())
+    }
+}
+
+impl TryFrom<Move> for Stm {
+    type Error = ();
+
+    fn try_from(Move { turn, amount }: Move) -> Result<Self, Self::Error> {
+        match turn {
+            Turn::Face(face) => Ok(Self::from_htm(Htm::new(face, amount))),
+            Turn::Slice(slice) => Ok(Self::from_slice(slice, amount)),
+            _ => Err(()),
+        }
+    }
+}
+
+impl TryFrom<usize> for Stm {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Self::ALL.get(value).copied().ok_or(())
+    }
+}
+
+impl From<Stm> for usize {
+    fn from(val: Stm) -> Self {
+        val as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Alg;
+    use crate::puzzle::Cube3x3;
+
+    fn cube(source: &str) -> Cube3x3 {
+        source
+            .parse::<Alg>()
+            .unwrap()
+            .moves()
+            .iter()
+            .fold(Cube3x3::default(), |cube, &turn| cube.apply_move(turn))
+    }
+
+    #[test]
+    pub fn slices() {
+        for turn in Stm::ALL {
+            assert_eq!(Stm::try_from(turn.to_move()), Ok(turn));
+            assert_eq!(turn.inverse().to_move(), turn.to_move().inverse());
+            assert_eq!(
+                Cube3x3::default().apply_stm(turn),
+                cube(&format!("{}", turn))
+            );
+        }
+
+        // A slice turn is the same as turning the two faces and rotating back
+        assert_eq!(cube("M'"), cube("R' L x"));
+        assert_eq!(cube("E S2 M"), cube("U D' y' F2 B2 z2 R L' x'"));
+        assert_eq!(cube("Rw R'"), cube("M'"));
+
+        // Without a rotation at the end, the state is the same as from the face turns alone
+        let h_perm: Alg = "M2 U M2 U2 M2 U M2".parse().unwrap();
+        assert_eq!(cube("M2 U M2 U2 M2 U M2"), h_perm.state());
+        assert!(cube("(M2 U M2 U2 M2 U M2)2").is_solved());
+        assert!(cube("x y M2").apply_stm(Stm::M2).is_solved());
+        assert!(!cube("M2").is_solved());
+    }
+}
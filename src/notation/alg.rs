@@ -0,0 +1,165 @@
+use super::{expand_moves, flatten, invert, parse, Move, ParseError, Rotation, Turn};
+use crate::metric::Htm;
+use std::iter::FromIterator;
+use std::ops::Add;
+use std::str::FromStr;
+
+/// A sequence of moves, such as an algorithm, scramble or solution, with any groups, commutators
+/// and conjugates expanded. Slice moves and rotations are kept as they are, so that the length
+/// can be measured in the metrics which count them.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Alg(Vec<Move>);
+
+impl Alg {
+    pub fn new(moves: Vec<Move>) -> Self {
+        Self(moves)
+    }
+
+    pub fn moves(&self) -> &[Move] {
+        &self.0
+    }
+
+    pub fn inverse(&self) -> Self {
+        Self(invert(&self.0))
+    }
+
+    /// The face turns made, relative to the initial orientation of the cube.
+    pub fn turns(&self) -> Vec<Htm> {
+        expand_moves(&self.0).0
+    }
+
+    /// The orientation of the cube at the end, relative to the initial one.
+    pub fn rotation(&self) -> Rotation {
+        expand_moves(&self.0).1
+    }
+
+    /// The state reached from the solved state, for any cube which can be built from face turns,
+    /// e.g. `Cube3x3`, `Cube2x2` or the Kociemba coordinate cubes.
+    pub fn state<T: FromIterator<Htm>>(&self) -> T {
+        self.turns().into_iter().collect()
+    }
+
+    /// The length in the half turn metric, where each face turn counts one, slice moves two
+    /// and rotations nothing.
+    pub fn htm(&self) -> usize {
+        self.turns().len()
+    }
+
+    /// The length in the quarter turn metric, where half turns count twice as much as in HTM.
+    pub fn qtm(&self) -> usize {
+        self.turns()
+            .iter()
+            .map(|turn| if turn.amount() == 2 { 2 } else { 1 })
+            .sum()
+    }
+
+    /// The length in the slice turn metric, where each face, wide or slice move counts one and
+    /// rotations nothing.
+    pub fn stm(&self) -> usize {
+        self.0
+            .iter()
+            .filter(|turn| !matches!(turn.turn, Turn::Rotation(_)))
+            .count()
+    }
+
+    /// The length in the execution turn metric, where every move counts one, including
+    /// rotations.
+    pub fn etm(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl std::fmt::Display for Alg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let moves = self
+            .0
+            .iter()
+            .map(|turn| format!("{}", turn))
+            .intersperse(" ".to_string())
+            .collect::<String>();
+
+        write!(f, "{}", moves)
+    }
+}
+
+impl FromStr for Alg {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(Self(flatten(&parse(value)?)))
+    }
+}
+
+impl Add for Alg {
+    type Output = Self;
+
+    fn add(mut self, Self(other): Self) -> Self::Output {
+        self.0.extend(other);
+        self
+    }
+}
+
+impl From<Vec<Htm>> for Alg {
+    fn from(turns: Vec<Htm>) -> Self {
+        turns.into_iter().collect()
+    }
+}
+
+impl FromIterator<Htm> for Alg {
+    fn from_iter<T: IntoIterator<Item = Htm>>(iter: T) -> Self {
+        Self(iter.into_iter().map(Move::from).collect())
+    }
+}
+
+impl FromIterator<Move> for Alg {
+    fn from_iter<T: IntoIterator<Item = Move>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorithm::kociemba::Phase1;
+    use crate::metric::Htm::*;
+    use crate::puzzle::{Cube2x2, Cube3x3};
+
+    #[test]
+    pub fn metrics() {
+        let alg: Alg = "[M': U2] x (Rw U R')2 y".parse().unwrap();
+        assert_eq!(format!("{}", alg), "M' U2 M x Rw U R' Rw U R' y");
+        assert_eq!(alg.htm(), 11);
+        assert_eq!(alg.qtm(), 12);
+        assert_eq!(alg.stm(), 9);
+        assert_eq!(alg.etm(), 11);
+        assert_eq!(alg.rotation(), Rotation::X.pow(3).then(Rotation::Y));
+    }
+
+    #[test]
+    pub fn states() {
+        let sune: Alg = "R U R' U R U2 R'".parse().unwrap();
+        let twice = sune.clone() + sune.clone();
+
+        assert_eq!(twice.etm(), 14);
+        assert_eq!(
+            twice.state::<Cube3x3>(),
+            Cube3x3::from_slice(&[R1, U1, R3, U1, R1, U2, R3, R1, U1, R3, U1, R1, U2, R3])
+        );
+        assert_eq!(
+            (sune.clone() + sune.inverse()).state::<Cube3x3>(),
+            Cube3x3::default()
+        );
+        assert_eq!(
+            sune.state::<Cube2x2>(),
+            [R1, U1, R3, U1, R1, U2, R3]
+                .iter()
+                .copied()
+                .collect::<Cube2x2>()
+        );
+        assert_eq!(
+            sune.state::<Phase1>(),
+            Phase1::from(&sune.state::<Cube3x3>())
+        );
+        assert_eq!(Alg::from(vec![R1, U2, F3]), "R U2 F'".parse().unwrap());
+    }
+}
@@ -0,0 +1,91 @@
+/// The reason some input could not be parsed as a move sequence.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Reason {
+    /// A letter which names no face, slice or rotation.
+    UnknownFace,
+    /// A prime or `w` which cannot follow the move before it, e.g. `Mw`.
+    BadSuffix,
+    /// A bracket without its partner, or closed by the wrong kind of bracket.
+    UnbalancedBracket,
+    /// A commutator or conjugate without the `,` or `:` between its two parts.
+    MissingSeparator,
+    /// A `,` or `:` outside of a commutator or conjugate, or a second one within it.
+    UnexpectedSeparator,
+    /// A `^` which does not start a group on the inverse, as in `^(R U)`.
+    MissingGroup,
+    /// A count which turns a move other than one to three times, or repeats a group no times,
+    /// more than 99 times or into too many moves, e.g. `R5`, `(R U)0` or `(R U)100`.
+    BadCount,
+    /// A layer or range of layers which names none, e.g. `0R` or `3-2R`.
+    BadLayers,
+    /// An amount which is not a number or out of range, e.g. `(7, 0)` on the Square-1.
+    BadAmount,
+}
+
+impl std::fmt::Display for Reason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let description = match self {
+            Reason::UnknownFace => "unknown face",
+            Reason::BadSuffix => "bad suffix",
+            Reason::UnbalancedBracket => "unbalanced bracket",
+            Reason::MissingSeparator => "missing `,` or `:`",
+            Reason::UnexpectedSeparator => "unexpected separator",
+            Reason::MissingGroup => "expected `(` after `^`",
+            Reason::BadCount => "bad count",
+            Reason::BadLayers => "bad layers",
+            Reason::BadAmount => "bad amount",
+        };
+
+        write!(f, "{}", description)
+    }
+}
+
+/// An error locating the token which could not be parsed in the input.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct ParseError {
+    /// The byte offset of the token in the input.
+    pub offset: usize,
+    /// The offending token, which is empty at the end of the input.
+    pub token: String,
+    pub reason: Reason,
+    input: String,
+}
+
+impl ParseError {
+    pub fn new(input: &str, offset: usize, token: &str, reason: Reason) -> Self {
+        Self {
+            offset,
+            token: token.to_string(),
+            reason,
+            input: input.to_string(),
+        }
+    }
+
+    /// The same error within a larger input, in which the original one starts at `offset`.
+    pub fn within(self, input: &str, offset: usize) -> Self {
+        Self::new(input, self.offset + offset, &self.token, self.reason)
+    }
+}
+
+impl std::fmt::Display for ParseError {
+    /// The reason followed by the line of the input containing the token, which is marked with
+    /// carets beneath it.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.token.as_str() {
+            "" => writeln!(f, "{} at end of input", self.reason)?,
+            token => writeln!(f, "{} `{}` at offset {}", self.reason, token, self.offset)?,
+        }
+
+        let start = self.input[..self.offset].rfind('\n').map_or(0, |ix| ix + 1);
+        let end = self.input[self.offset..]
+            .find('\n')
+            .map_or(self.input.len(), |ix| ix + self.offset);
+        let column = self.input[start..self.offset].chars().count();
+        let width = self.token.chars().count().max(1);
+
+        writeln!(f, "{}", &self.input[start..end])?;
+        write!(f, "{}{}", " ".repeat(column), "^".repeat(width))
+    }
+}
+
+impl std::error::Error for ParseError {}
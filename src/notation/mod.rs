@@ -0,0 +1,369 @@
+mod alg;
+mod error;
+mod parser;
+mod rotation;
+
+pub use alg::Alg;
+pub use error::{ParseError, Reason};
+pub use parser::parse;
+pub use rotation::Rotation;
+
+use crate::metric::htm::Face;
+use crate::metric::Htm;
+
+/// The slices between opposite faces, each turning in the direction of one of the faces.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Slice {
+    /// Between R and L, turning as L.
+    M,
+    /// Between U and D, turning as D.
+    E,
+    /// Between F and B, turning as F.
+    S,
+}
+
+/// The axes of rotations of the whole cube, turning as R, U and F respectively.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Turn {
+    Face(Face),
+    /// A face together with the adjacent slice, e.g. `Rw` or `r`.
+    Wide(Face),
+    Slice(Slice),
+    Rotation(Axis),
+}
+
+/// A single turn in the notation, by `amount` quarter turns clockwise, from 1 to 3.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Move {
+    pub turn: Turn,
+    pub amount: u8,
+}
+
+/// The number of repetitions of a group, which is inverted first if `inverse` is set.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Power {
+    pub count: u32,
+    pub inverse: bool,
+}
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Node {
+    Move(Move),
+    /// `(A)n`
+    Group(Vec<Node>, Power),
+    /// `[A, B]`, which is `A B A' B'`.
+    Commutator(Vec<Node>, Vec<Node>, Power),
+    /// `[A: B]`, which is `A B A'`.
+    Conjugate(Vec<Node>, Vec<Node>, Power),
+    /// `^(A)`, moves done on the inverse scramble, as in NISS.
+    Niss(Vec<Node>),
+}
+
+impl Move {
+    pub const fn new(turn: Turn, amount: u8) -> Self {
+        Self { turn, amount }
+    }
+
+    pub const fn inverse(self) -> Self {
+        Self::new(self.turn, 4 - self.amount)
+    }
+
+    /// The face turns this move consists of, relative to the current orientation, followed by the
+    /// rotation of the whole cube it makes.
+    pub(crate) fn decompose(self) -> (Vec<(Face, u8)>, Rotation) {
+        let Self { turn, amount } = self;
+        let counter = 4 - amount;
+
+        match turn {
+            Turn::Face(face) => (vec![(face, amount)], Rotation::IDENTITY),
+            Turn::Wide(face) => (
+                vec![(face.opposite(), amount)],
+                Rotation::of(face).pow(amount),
+            ),
+            Turn::Slice(Slice::M) => (
+                vec![(Face::R, amount), (Face::L, counter)],
+                Rotation::of(Face::L).pow(amount),
+            ),
+            Turn::Slice(Slice::E) => (
+                vec![(Face::U, amount), (Face::D, counter)],
+                Rotation::of(Face::D).pow(amount),
+            ),
+            Turn::Slice(Slice::S) => (
+                vec![(Face::F, counter), (Face::B, amount)],
+                Rotation::of(Face::F).pow(amount),
+            ),
+            Turn::Rotation(Axis::X) => (Vec::new(), Rotation::X.pow(amount)),
+            Turn::Rotation(Axis::Y) => (Vec::new(), Rotation::Y.pow(amount)),
+            Turn::Rotation(Axis::Z) => (Vec::new(), Rotation::Z.pow(amount)),
+        }
+    }
+}
+
+impl From<Htm> for Move {
+    fn from(htm: Htm) -> Self {
+        Self::new(Turn::Face(htm.face()), htm.amount())
+    }
+}
+
+impl Power {
+    pub const ONE: Self = Self {
+        count: 1,
+        inverse: false,
+    };
+
+    /// The net number of quarter turns clockwise when applied to a single move.
+    const fn amount(self) -> u8 {
+        let amount = (self.count % 4) as u8;
+        if self.inverse {
+            (4 - amount) % 4
+        } else {
+            amount
+        }
+    }
+
+    fn apply(self, moves: Vec<Move>) -> Vec<Move> {
+        let moves = if self.inverse { invert(&moves) } else { moves };
+        moves.repeat(self.count as usize)
+    }
+}
+
+/// Parse the words of `source`, which are separated by whitespace, each with `token`. An error is
+/// located within the whole of `source`. This is the notation of the puzzles whose moves are
+/// single words, e.g. `R U2 F'`.
+pub fn parse_tokens<T>(
+    source: &str,
+    token: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    source
+        .split_whitespace()
+        .map(|word| {
+            let offset = word.as_ptr() as usize - source.as_ptr() as usize;
+            token(word).map_err(|error| error.within(source, offset))
+        })
+        .collect()
+}
+
+/// The moves which undo `moves`.
+pub fn invert(moves: &[Move]) -> Vec<Move> {
+    moves.iter().rev().map(|turn| turn.inverse()).collect()
+}
+
+/// Expand groups, commutators and conjugates into the moves they stand for. Moves on the inverse
+/// are inverted and placed after all the moves of the same sequence on the normal scramble.
+pub fn flatten(sequence: &[Node]) -> Vec<Move> {
+    let mut normal = Vec::new();
+    let mut inverse = Vec::new();
+
+    for node in sequence {
+        match node {
+            Node::Move(turn) => normal.push(*turn),
+            Node::Group(inner, power) => normal.extend(power.apply(flatten(inner))),
+            Node::Commutator(a, b, power) => {
+                let (a, b) = (flatten(a), flatten(b));
+                normal.extend(power.apply([&a[..], &b, &invert(&a), &invert(&b)].concat()));
+            }
+            Node::Conjugate(a, b, power) => {
+                let (a, b) = (flatten(a), flatten(b));
+                normal.extend(power.apply([&a[..], &b, &invert(&a)].concat()));
+            }
+            Node::Niss(inner) => inverse.extend(flatten(inner)),
+        }
+    }
+
+    normal.extend(invert(&inverse));
+    normal
+}
+
+/// The face turns of `sequence` relative to the initial orientation of the cube, which is how
+/// `Cube3x3` sees them, together with the orientation of the cube at the end.
+pub fn expand(sequence: &[Node]) -> (Vec<Htm>, Rotation) {
+    expand_moves(&flatten(sequence))
+}
+
+/// As `expand`, for moves which have already been flattened.
+pub fn expand_moves(moves: &[Move]) -> (Vec<Htm>, Rotation) {
+    let mut orientation = Rotation::IDENTITY;
+    let mut turns = Vec::new();
+
+    for &turn in moves {
+        let (faces, rotation) = turn.decompose();
+        for (face, amount) in faces {
+            turns.push(Htm::new(orientation.face(face), amount));
+        }
+        orientation = orientation.then(rotation);
+    }
+
+    (turns, orientation)
+}
+
+pub fn format_seq(sequence: &[Node]) -> String {
+    sequence
+        .iter()
+        .map(|node| format!("{}", node))
+        .intersperse(" ".to_string())
+        .collect()
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.turn {
+            Turn::Face(face) => write!(f, "{:?}", face)?,
+            Turn::Wide(face) => write!(f, "{:?}w", face)?,
+            Turn::Slice(slice) => write!(f, "{:?}", slice)?,
+            Turn::Rotation(axis) => write!(f, "{}", format!("{:?}", axis).to_lowercase())?,
+        }
+
+        match self.amount {
+            1 => Ok(()),
+            3 => write!(f, "'"),
+            amount => write!(f, "{}", amount),
+        }
+    }
+}
+
+impl std::fmt::Display for Power {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.count != 1 {
+            write!(f, "{}", self.count)?;
+        }
+        if self.inverse {
+            write!(f, "'")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Node::Move(turn) => write!(f, "{}", turn),
+            Node::Group(inner, power) => write!(f, "({}){}", format_seq(inner), power),
+            Node::Commutator(a, b, power) => {
+                write!(f, "[{}, {}]{}", format_seq(a), format_seq(b), power)
+            }
+            Node::Conjugate(a, b, power) => {
+                write!(f, "[{}: {}]{}", format_seq(a), format_seq(b), power)
+            }
+            Node::Niss(inner) => write!(f, "^({})", format_seq(inner)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Htm::*;
+    use crate::puzzle::Cube3x3;
+
+    fn turns(source: &str) -> (Vec<Htm>, Rotation) {
+        expand(&parse(source).unwrap())
+    }
+
+    fn cube(source: &str) -> Cube3x3 {
+        let (turns, rotation) = turns(source);
+        assert_eq!(rotation, Rotation::IDENTITY);
+        Cube3x3::from_slice(&turns)
+    }
+
+    #[test]
+    pub fn structure() {
+        assert_eq!(
+            turns("R U R' U'"),
+            (vec![R1, U1, R3, U3], Rotation::IDENTITY)
+        );
+        assert_eq!(turns("RUR'U'").0, vec![R1, U1, R3, U3]);
+        assert_eq!(turns("(R U)3").0, vec![R1, U1, R1, U1, R1, U1]);
+        assert_eq!(turns("(R U2)'").0, vec![U2, R3]);
+        assert_eq!(turns("[R, U]").0, vec![R1, U1, R3, U3]);
+        assert_eq!(turns("[F: [R, U]]").0, vec![F1, R1, U1, R3, U3, F3]);
+        assert_eq!(turns("[R: U]2").0, vec![R1, U1, R3, R1, U1, R3]);
+        assert_eq!(turns("R ^(U F) D").0, vec![R1, D1, F3, U3]);
+        assert_eq!(turns("R U // comment\n R'").0, vec![R1, U1, R3]);
+        assert_eq!(cube("(R U R' U')6"), Cube3x3::default());
+    }
+
+    #[test]
+    pub fn rotations() {
+        assert_eq!(turns("x"), (Vec::new(), Rotation::X));
+        assert_eq!(turns("x U x'").0, vec![F1]);
+        assert_eq!(turns("y R y'").0, vec![B1]);
+        assert_eq!(turns("z U z'").0, vec![L1]);
+        assert_eq!(turns("x y y'"), (Vec::new(), Rotation::X));
+        assert_eq!(turns("x x x x").1, Rotation::IDENTITY);
+        assert_eq!(turns("y' x").1, Rotation::Y.inverse().then(Rotation::X));
+        assert_eq!(turns("x2 y2 z2").1, Rotation::IDENTITY);
+        assert_eq!(turns("r U r'").0, vec![L1, F1, L3]);
+        assert_eq!(turns("Rw"), (vec![L1], Rotation::X));
+
+        assert_eq!(cube("Rw R' x'"), cube("M' x'"));
+        assert_eq!(cube("Uw U' y'"), cube("E' y'"));
+        assert_eq!(cube("Fw F' z'"), cube("S z'"));
+        assert_eq!(cube("(M2 U M2 U2 M2 U M2)2"), Cube3x3::default());
+    }
+
+    #[test]
+    pub fn round_trip() {
+        let source = "R U' (Rw2 M')3' [x: [F, D2]] ^(B y2)";
+        let sequence = parse(source).unwrap();
+        assert_eq!(format_seq(&sequence), source);
+    }
+
+    #[test]
+    pub fn errors() {
+        let cases = [
+            ("R U Q", 4, "Q", Reason::UnknownFace),
+            ("R Q2' U", 2, "Q2'", Reason::UnknownFace),
+            ("(R Qw)2", 3, "Qw", Reason::UnknownFace),
+            ("R4 U", 0, "R4", Reason::BadCount),
+            ("U R5", 2, "R5", Reason::BadCount),
+            ("R0", 0, "R0", Reason::BadCount),
+            ("R U (R U)0", 9, "0", Reason::BadCount),
+            ("[R, U]0'", 6, "0'", Reason::BadCount),
+            ("(R U)4000000000", 5, "4000000000", Reason::BadCount),
+            ("(R U)100", 5, "100", Reason::BadCount),
+            ("(((R U)99)99)99", 13, "99", Reason::BadCount),
+            ("[[R, U]99, [F, D]99]99", 20, "99", Reason::BadCount),
+            ("R Mw", 2, "Mw", Reason::BadSuffix),
+            ("R (U F", 2, "(", Reason::UnbalancedBracket),
+            ("R U)", 3, ")", Reason::UnbalancedBracket),
+            ("[R, U)", 5, ")", Reason::UnbalancedBracket),
+            ("[R U]", 4, "]", Reason::MissingSeparator),
+            ("R, U", 1, ",", Reason::UnexpectedSeparator),
+            ("[R, U, F]", 5, ",", Reason::UnexpectedSeparator),
+            ("^R", 0, "^R", Reason::MissingGroup),
+        ];
+
+        for (source, offset, token, reason) in cases {
+            assert_eq!(
+                parse(source),
+                Err(ParseError::new(source, offset, token, reason)),
+                "{}",
+                source
+            );
+        }
+
+        assert_eq!(Htm::parse(" R  U2 F'"), Ok(vec![R1, U2, F3]));
+        assert_eq!(
+            Htm::parse("R U2 Q' F"),
+            Err(ParseError::new("R U2 Q' F", 5, "Q'", Reason::UnknownFace))
+        );
+        assert_eq!(
+            Htm::parse("R U Q"),
+            Err(ParseError::new("R U Q", 4, "Q", Reason::UnknownFace))
+        );
+        assert_eq!(
+            Htm::parse("R U5"),
+            Err(ParseError::new("R U5", 2, "U5", Reason::BadSuffix))
+        );
+
+        let message = format!("{}", parse("R U\nF Q2 D").unwrap_err());
+        assert_eq!(message, "unknown face `Q2` at offset 6\nF Q2 D\n  ^^");
+    }
+}
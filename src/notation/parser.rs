@@ -0,0 +1,279 @@
+use super::{Axis, Move, Node, ParseError, Power, Reason, Slice, Turn};
+use crate::metric::htm::Face;
+
+/// The largest number of times a group, commutator or conjugate may be repeated.
+const MAX_COUNT: u32 = 99;
+
+/// The most moves a sequence may stand for, which bounds repetitions nested within each other.
+const MAX_LENGTH: usize = 100_000;
+
+/// Parse an algorithm in WCA notation, extended with wide and slice moves, rotations, groups
+/// with repetition `(A)n`, commutators `[A, B]`, conjugates `[A: B]`, moves on the inverse
+/// `^(A)` and comments from `//` to the end of the line. Moves need not be separated by spaces.
+/// A group may be repeated at most 99 times, and the whole may stand for at most 100,000 moves.
+pub fn parse(source: &str) -> Result<Vec<Node>, ParseError> {
+    let mut parser = Parser {
+        source,
+        position: 0,
+    };
+
+    let sequence = parser.sequence()?;
+    match parser.peek() {
+        None => Ok(sequence),
+        Some(_) => Err(parser.unexpected(None)),
+    }
+}
+
+struct Parser<'a> {
+    source: &'a str,
+    position: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<char> {
+        self.source[self.position..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let next = self.peek()?;
+        self.position += next.len_utf8();
+        Some(next)
+    }
+
+    fn skip_blank(&mut self) {
+        loop {
+            match self.peek() {
+                Some(next) if next.is_whitespace() => {
+                    self.bump();
+                }
+                Some('/') if self.source[self.position..].starts_with("//") => {
+                    while !matches!(self.bump(), None | Some('\n')) {}
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// Consume the rest of a word, up to a blank, bracket or separator, so that an error names it
+    /// whole as `Htm::parse` does.
+    fn skip_word(&mut self) {
+        loop {
+            match self.peek() {
+                None | Some('(' | ')' | '[' | ']' | ',' | ':' | '^' | '/') => return,
+                Some(next) if next.is_whitespace() => return,
+                Some(_) => {
+                    self.bump();
+                }
+            }
+        }
+    }
+
+    fn error(&self, start: usize, reason: Reason) -> ParseError {
+        ParseError::new(
+            self.source,
+            start,
+            &self.source[start..self.position],
+            reason,
+        )
+    }
+
+    /// The error for the next character, which does not continue the input as expected. Running
+    /// out of input is blamed on `opening`, the offset of the innermost unclosed bracket.
+    fn unexpected(&self, opening: Option<usize>) -> ParseError {
+        let (start, reason) = match (self.peek(), opening) {
+            (None, Some(opening)) => (opening, Reason::UnbalancedBracket),
+            (Some(',' | ':'), _) => (self.position, Reason::UnexpectedSeparator),
+            _ => (self.position, Reason::UnbalancedBracket),
+        };
+
+        self.error_at(start, reason)
+    }
+
+    /// An error for the single character at `start`, or for the end of the input.
+    fn error_at(&self, start: usize, reason: Reason) -> ParseError {
+        let length = self.source[start..]
+            .chars()
+            .next()
+            .map_or(0, char::len_utf8);
+        ParseError::new(
+            self.source,
+            start,
+            &self.source[start..start + length],
+            reason,
+        )
+    }
+
+    /// Consume the bracket closing the one at `opening`.
+    fn close(&mut self, closing: char, opening: usize) -> Result<(), ParseError> {
+        self.skip_blank();
+        match self.peek() {
+            Some(next) if next == closing => {
+                self.bump();
+                Ok(())
+            }
+            _ => Err(self.unexpected(Some(opening))),
+        }
+    }
+
+    /// Nodes up to the end of the enclosing group, or of the input.
+    fn sequence(&mut self) -> Result<Vec<Node>, ParseError> {
+        let mut nodes = Vec::new();
+
+        loop {
+            self.skip_blank();
+            match self.peek() {
+                None | Some(')' | ']' | ',' | ':') => return Ok(nodes),
+                Some(_) => nodes.push(self.node()?),
+            }
+        }
+    }
+
+    fn node(&mut self) -> Result<Node, ParseError> {
+        let start = self.position;
+
+        match self.bump() {
+            Some('(') => {
+                let inner = self.sequence()?;
+                self.close(')', start)?;
+                let power = self.repetition(length(&inner))?;
+                Ok(Node::Group(inner, power))
+            }
+            Some('[') => {
+                let a = self.sequence()?;
+                let conjugate = match self.peek() {
+                    Some(',') => false,
+                    Some(':') => true,
+                    None => return Err(self.unexpected(Some(start))),
+                    Some(_) => return Err(self.error_at(self.position, Reason::MissingSeparator)),
+                };
+                self.bump();
+
+                let b = self.sequence()?;
+                self.close(']', start)?;
+
+                let once = if conjugate {
+                    2 * length(&a) + length(&b)
+                } else {
+                    2 * (length(&a) + length(&b))
+                };
+                let power = self.repetition(once)?;
+                Ok(if conjugate {
+                    Node::Conjugate(a, b, power)
+                } else {
+                    Node::Commutator(a, b, power)
+                })
+            }
+            Some('^') => {
+                let opening = self.position;
+                if self.bump() != Some('(') {
+                    return Err(self.error(start, Reason::MissingGroup));
+                }
+                let inner = self.sequence()?;
+                self.close(')', opening)?;
+                Ok(Node::Niss(inner))
+            }
+            Some(symbol) => {
+                let mut turn = match turn(symbol) {
+                    Some(turn) => turn,
+                    None => {
+                        self.skip_word();
+                        return Err(self.error(start, Reason::UnknownFace));
+                    }
+                };
+                if self.peek() == Some('w') {
+                    self.bump();
+                    match turn {
+                        Turn::Face(face) => turn = Turn::Wide(face),
+                        _ => return Err(self.error(start, Reason::BadSuffix)),
+                    }
+                }
+
+                let power = self.power()?;
+                match power.count {
+                    1..=3 => Ok(Node::Move(Move::new(turn, power.amount()))),
+                    _ => Err(self.error(start, Reason::BadCount)),
+                }
+            }
+            None => Err(self.unexpected(None)),
+        }
+    }
+
+    /// The optional count and prime directly following a move or group.
+    fn power(&mut self) -> Result<Power, ParseError> {
+        let start = self.position;
+        while matches!(self.peek(), Some('0'..='9')) {
+            self.bump();
+        }
+
+        let count = match &self.source[start..self.position] {
+            "" => 1,
+            digits => digits
+                .parse()
+                .map_err(|_| self.error(start, Reason::BadCount))?,
+        };
+
+        let inverse = matches!(self.peek(), Some('\'' | '’'));
+        if inverse {
+            self.bump();
+        }
+
+        Ok(Power { count, inverse })
+    }
+
+    /// The power of a group, commutator or conjugate of `length` moves, which must repeat it at
+    /// least once and at most `MAX_COUNT` times, into no more than `MAX_LENGTH` moves.
+    fn repetition(&mut self, length: usize) -> Result<Power, ParseError> {
+        let start = self.position;
+        let power = self.power()?;
+        let count = power.count as usize;
+        if count == 0 || power.count > MAX_COUNT || length.saturating_mul(count) > MAX_LENGTH {
+            return Err(self.error(start, Reason::BadCount));
+        }
+
+        Ok(power)
+    }
+}
+
+/// The number of moves `sequence` stands for once expanded, which is at most `MAX_LENGTH` for
+/// any sequence the parser accepts.
+fn length(sequence: &[Node]) -> usize {
+    sequence
+        .iter()
+        .map(|node| match node {
+            Node::Move(_) => 1,
+            Node::Group(inner, power) => length(inner) * power.count as usize,
+            Node::Commutator(a, b, power) => 2 * (length(a) + length(b)) * power.count as usize,
+            Node::Conjugate(a, b, power) => (2 * length(a) + length(b)) * power.count as usize,
+            Node::Niss(inner) => length(inner),
+        })
+        .sum()
+}
+
+fn turn(symbol: char) -> Option<Turn> {
+    let face = |symbol| match symbol {
+        'U' => Some(Face::U),
+        'R' => Some(Face::R),
+        'F' => Some(Face::F),
+        'L' => Some(Face::L),
+        'D' => Some(Face::D),
+        'B' => Some(Face::B),
+        _ => None,
+    };
+
+    if let Some(face) = face(symbol) {
+        return Some(Turn::Face(face));
+    }
+    if let Some(face) = face(symbol.to_ascii_uppercase()) {
+        return Some(Turn::Wide(face));
+    }
+
+    match symbol {
+        'M' => Some(Turn::Slice(Slice::M)),
+        'E' => Some(Turn::Slice(Slice::E)),
+        'S' => Some(Turn::Slice(Slice::S)),
+        'x' => Some(Turn::Rotation(Axis::X)),
+        'y' => Some(Turn::Rotation(Axis::Y)),
+        'z' => Some(Turn::Rotation(Axis::Z)),
+        _ => None,
+    }
+}
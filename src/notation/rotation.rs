@@ -0,0 +1,77 @@
+use crate::metric::htm::Face::{self, *};
+
+/// An orientation of the whole cube, given by the face found at each position, in the order
+/// U, R, F, L, D, B.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Rotation([Face; 6]);
+
+impl Rotation {
+    pub const IDENTITY: Self = Self([U, R, F, L, D, B]);
+    /// The rotation of the whole cube in the direction of R.
+    pub const X: Self = Self([F, R, D, L, B, U]);
+    /// The rotation of the whole cube in the direction of U.
+    pub const Y: Self = Self([U, B, R, F, D, L]);
+    /// The rotation of the whole cube in the direction of F.
+    pub const Z: Self = Self([L, U, F, D, R, B]);
+
+    /// The rotation of the whole cube in the direction of a turn of `face`.
+    pub const fn of(face: Face) -> Self {
+        match face {
+            U => Self::Y,
+            R => Self::X,
+            F => Self::Z,
+            L => Self::X.inverse(),
+            D => Self::Y.inverse(),
+            B => Self::Z.inverse(),
+        }
+    }
+
+    /// The face found at `position` after this rotation.
+    pub const fn face(self, position: Face) -> Face {
+        self.0[position as usize]
+    }
+
+    /// This rotation followed by `other`.
+    pub const fn then(self, other: Self) -> Self {
+        let mut faces = Self::IDENTITY.0;
+
+        let mut ix = 0;
+        while ix < 6 {
+            faces[ix] = self.face(other.0[ix]);
+            ix += 1;
+        }
+
+        Self(faces)
+    }
+
+    pub const fn inverse(self) -> Self {
+        let mut faces = Self::IDENTITY.0;
+
+        let mut ix = 0;
+        while ix < 6 {
+            faces[self.0[ix] as usize] = Face::ALL[ix];
+            ix += 1;
+        }
+
+        Self(faces)
+    }
+
+    /// This rotation repeated `amount` times.
+    pub const fn pow(self, amount: u8) -> Self {
+        let mut rotation = Self::IDENTITY;
+
+        let mut ix = 0;
+        while ix < amount % 4 {
+            rotation = rotation.then(self);
+            ix += 1;
+        }
+
+        rotation
+    }
+}
+
+impl Default for Rotation {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
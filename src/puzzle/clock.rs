@@ -0,0 +1,466 @@
+//! The Clock, with nine dials on each side. The dials at the corners turn together with those
+//! behind them, one way on the front and the other on the back, so that fourteen dials are
+//! independent: the nine of the front and the five of the back between its corners.
+//!
+//! Every turn adds its hours to the dials it connects, so the turns commute and a position is
+//! just the hours of the dials modulo twelve. A solution gives a number of hours to each of the
+//! fifteen settings of the pins on either side, and an optimal one is the combination with the
+//! fewest turns.
+//!
+//! A dial on one side is turned by every turn of that side but those with all their pins away
+//! from it, so the dials fix the total hours of the turns whose pins lie within each set of
+//! pins away from a dial: all four, three for a corner and two along a side for an edge. With
+//! the totals for the single pins and the diagonals, which are free, the turns follow by
+//! inclusion and exclusion. Every one of the free totals is tried on each side, and the counts
+//! of turns are kept for every number of hours of the corners, which the two sides share.
+
+use crate::metric::clock::Pins;
+use crate::metric::Clock as ClockMetric;
+use rand::Rng;
+use std::cmp::min;
+use std::iter::FromIterator;
+
+/// The number of independent dials.
+const DIALS: usize = 14;
+
+/// The pins around each dial of a side, row by row, any of which connects the dial to the wheels
+/// when it is up.
+const CONNECTIONS: [u8; 9] = [
+    0b1000, 0b1001, 0b0001, 0b1100, 0b1111, 0b0011, 0b0100, 0b0110, 0b0010,
+];
+
+/// The positions on a side of the dials of the back between its corners.
+const MIDDLES: [usize; 5] = [1, 3, 4, 5, 7];
+
+/// The positions on a side of the dials at its corners, each with that of the corner behind it
+/// on the other side.
+const CORNERS: [(usize, usize); 4] = [(0, 2), (2, 0), (6, 8), (8, 6)];
+
+/// The number of settings of the hours of the four corners.
+const CORNER_STATES: usize = 12 * 12 * 12 * 12;
+
+/// The masks of the pins whose totals are not fixed by the dials: the single pins and the
+/// diagonals.
+const FREE: [usize; 6] = [0b0001, 0b0010, 0b0100, 0b1000, 0b0101, 0b1010];
+
+/// The masks of one pin or two, whose turns do not depend on the corners.
+const SMALL: [usize; 10] = [
+    0b0001, 0b0010, 0b0100, 0b1000, 0b0011, 0b0110, 0b1100, 0b1001, 0b0101, 0b1010,
+];
+
+/// The value of each digit of the free totals, written as a number in base twelve.
+const FREE_POWERS: [usize; 6] = [1, 12, 144, 1728, 20736, 248832];
+
+/// The number of settings of the free totals.
+const FREE_STATES: usize = 12 * FREE_POWERS[5];
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Clock {
+    /// The hours clockwise from twelve of the dials of the front row by row, followed by those
+    /// of the back between its corners, top, left, centre, right and bottom as seen from behind.
+    pub dials: [u8; DIALS],
+}
+
+impl Clock {
+    pub fn apply(&self, turn: ClockMetric) -> Self {
+        match turn {
+            ClockMetric::Turn(pins, amount) => {
+                let mut dials = self.dials;
+                for (dial, &connections) in dials.iter_mut().zip(CONNECTIONS.iter()) {
+                    if connections & pins.mask() != 0 {
+                        *dial = (*dial as i8 + amount).rem_euclid(12) as u8;
+                    }
+                }
+                Self { dials }
+            }
+            ClockMetric::Flip => {
+                let [front, back] = self.faces();
+                let mut dials = [0; DIALS];
+                dials[..9].copy_from_slice(&back);
+                for (dial, &ix) in dials[9..].iter_mut().zip(MIDDLES.iter()) {
+                    *dial = front[ix];
+                }
+                Self { dials }
+            }
+        }
+    }
+
+    pub fn apply_seq(&self, sequence: impl IntoIterator<Item = ClockMetric>) -> Self {
+        sequence
+            .into_iter()
+            .fold(*self, |puzzle, turn| puzzle.apply(turn))
+    }
+
+    /// The hours of the nine dials of the front and of the back, each row by row as seen from
+    /// its own side. Each corner of the back is behind the opposite corner of the same row on
+    /// the front, and turns the other way.
+    pub fn faces(&self) -> [[u8; 9]; 2] {
+        let mut front = [0; 9];
+        front.copy_from_slice(&self.dials[..9]);
+
+        let mut back = [0; 9];
+        for (&ix, &hours) in MIDDLES.iter().zip(self.dials[9..].iter()) {
+            back[ix] = hours;
+        }
+        for &(ix, behind) in CORNERS.iter() {
+            back[ix] = (12 - front[behind]) % 12;
+        }
+
+        [front, back]
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// A uniformly random position.
+    pub fn random_state() -> Self {
+        let mut rng = rand::thread_rng();
+        let mut dials = [0; DIALS];
+        for dial in dials.iter_mut() {
+            *dial = rng.gen_range(0..12);
+        }
+
+        Self { dials }
+    }
+
+    /// An optimal solution, with the turns on the front followed by those on the back. Any
+    /// setting of the pins may be used, not only those named by the WCA.
+    pub fn solve(&self) -> Vec<ClockMetric> {
+        let negate = |face: [u8; 9]| face.map(|hours| (12 - hours) % 12);
+        let [front, back] = self.faces().map(negate);
+        let tables = [table(&front), table(&back)];
+
+        // The hours added to the corners by the front, which leave the rest to the back
+        let increments = |index: usize| {
+            let (mut front_increment, mut back_increment) = (front, back);
+            for (k, &(ix, behind)) in CORNERS.iter().enumerate() {
+                front_increment[ix] = (index / 12usize.pow(k as u32) % 12) as u8;
+                back_increment[behind] = (front_increment[ix] + 12 - front[ix]) % 12;
+            }
+            [front_increment, back_increment]
+        };
+        let count = |increments: &[[u8; 9]; 2]| {
+            fewest(&tables[0], &increments[0]).0 + fewest(&tables[1], &increments[1]).0
+        };
+        let [front, back] = (0..CORNER_STATES)
+            .map(increments)
+            .min_by_key(count)
+            .unwrap();
+
+        let side = |table: &[(u8, usize)], increment: &[u8; 9]| {
+            let turns = turns(increment, fewest(table, increment).1);
+            Pins::ALL
+                .iter()
+                .map(|&pins| (pins, turns[pins.mask() as usize]))
+                .filter(|&(_, hours)| hours != 0)
+                .map(|(pins, hours)| ClockMetric::turn(pins, hours as i8))
+                .collect::<Vec<_>>()
+        };
+        let mut solution = side(&tables[0], &front);
+        let back = side(&tables[1], &back);
+        if !back.is_empty() {
+            solution.push(ClockMetric::Flip);
+            solution.extend(back);
+        }
+
+        solution
+    }
+
+    /// A scramble in the form of the WCA, turning each pin configuration of the front and then
+    /// those of two or four pins on the back by a random number of hours. As these turns are a
+    /// basis, the position reached is uniformly random.
+    pub fn random_scramble() -> Vec<ClockMetric> {
+        let mut rng = rand::thread_rng();
+        let mut turn = |pins| ClockMetric::Turn(pins, rng.gen_range(-5..=6));
+
+        let mut scramble = Pins::WCA.iter().map(|&pins| turn(pins)).collect::<Vec<_>>();
+        scramble.push(ClockMetric::Flip);
+        for &pins in [Pins::U, Pins::R, Pins::D, Pins::L, Pins::All].iter() {
+            scramble.push(turn(pins));
+        }
+
+        scramble
+    }
+}
+
+impl FromIterator<ClockMetric> for Clock {
+    fn from_iter<T: IntoIterator<Item = ClockMetric>>(iter: T) -> Self {
+        Self::default().apply_seq(iter)
+    }
+}
+
+/// For each mask of pins, the total hours of the turns of one side whose pins are all among
+/// those of the mask, when the side adds `increment` to its dials. The totals for the masks of
+/// `FREE` are the digits of `free` in base twelve.
+fn sums(increment: &[u8; 9], free: usize) -> [u8; 16] {
+    // A dial is turned by every turn but those whose pins are all away from it
+    let centre = increment[4];
+    let mut sums = [0; 16];
+    sums[15] = centre;
+    for (&connections, &hours) in CONNECTIONS.iter().zip(increment.iter()) {
+        sums[15 ^ connections as usize] = (centre + 12 - hours) % 12;
+    }
+    for (ix, &mask) in FREE.iter().enumerate() {
+        sums[mask] = (free / FREE_POWERS[ix] % 12) as u8;
+    }
+
+    sums
+}
+
+/// The hours of the turn of each mask of pins on one side which adds `increment` to its dials,
+/// with the free totals given by `free`, by inclusion and exclusion over the totals.
+fn turns(increment: &[u8; 9], free: usize) -> [u8; 16] {
+    let mut turns = sums(increment, free);
+    for bit in [1, 2, 4, 8] {
+        for mask in (0..16).filter(|mask| mask & bit != 0) {
+            turns[mask] = (turns[mask] + 12 - turns[mask ^ bit]) % 12;
+        }
+    }
+
+    turns
+}
+
+/// The hours of the corners of `increment` as the digits of a number in base twelve, in the
+/// order of `CORNERS`, and their total.
+fn corners(increment: &[u8; 9]) -> (usize, usize) {
+    CORNERS
+        .iter()
+        .rev()
+        .fold((0, 0), |(index, total), &(ix, _)| {
+            let hours = increment[ix] as usize;
+            (12 * index + hours, (total + hours) % 12)
+        })
+}
+
+/// The fewest turns of one side which add `increment` to its edges and centre, with the free
+/// totals which give them. They are indexed by the hours added to the corners, as in `corners`,
+/// and by the total of those hours for which the turn of all four pins vanishes, which `fewest`
+/// makes up for when the total is another.
+fn table(increment: &[u8; 9]) -> Vec<(u8, usize)> {
+    let centre = increment[4];
+    let mut centred = *increment;
+    for &(ix, _) in CORNERS.iter() {
+        centred[ix] = centre;
+    }
+
+    // The turns are linear in the free totals, so that stepping from each to the next in base
+    // twelve adds the turns of a single hour of the digits which change
+    let columns = FREE_POWERS.map(|power| turns(&[0; 9], power));
+    let mut turns = self::turns(&centred, 0);
+    let triples = CORNERS.map(|(ix, _)| 15 ^ CONNECTIONS[ix] as usize);
+
+    let mut table = vec![(u8::MAX, 0); CORNER_STATES * 12];
+    for free in 0..FREE_STATES {
+        // With the corners at the hours of the centre the totals for three pins are zero, so that
+        // the turn of three pins vanishes for the hours of its corner below, and that of all four
+        // for the total below
+        let mut count = 0;
+        for &mask in SMALL.iter() {
+            if turns[mask] != 0 {
+                count += 1;
+            }
+        }
+
+        let mut index = 0;
+        for &mask in triples.iter().rev() {
+            index = 12 * index + ((centre + turns[mask]) % 12) as usize;
+        }
+        let total = ((4 * centre + 12 - turns[15]) % 12) as usize;
+
+        let entry = &mut table[index * 12 + total];
+        if count < entry.0 {
+            *entry = (count, free);
+        }
+
+        let mut k = 0;
+        loop {
+            for mask in 1..16 {
+                turns[mask] = (turns[mask] + columns[k][mask]) % 12;
+            }
+            if k + 1 == FREE_POWERS.len() || free / FREE_POWERS[k] % 12 != 11 {
+                break;
+            }
+            k += 1;
+        }
+    }
+
+    // Other hours of a corner need at most the turn of the three pins around it
+    for k in 0..CORNERS.len() {
+        let stride = 12 * 12usize.pow(k as u32);
+        let starts = (0..table.len())
+            .step_by(12 * stride)
+            .flat_map(|high| high..high + stride);
+        for start in starts {
+            let (count, free) = (0..12).map(|x| table[start + x * stride]).min().unwrap();
+            for x in 0..12 {
+                let entry = &mut table[start + x * stride];
+                if count.saturating_add(1) < entry.0 {
+                    *entry = (count + 1, free);
+                }
+            }
+        }
+    }
+
+    table
+}
+
+/// The fewest turns of one side adding `increment` to its dials, looked up in its `table`, with
+/// the free totals which give them.
+fn fewest(table: &[(u8, usize)], increment: &[u8; 9]) -> (u8, usize) {
+    let (index, total) = corners(increment);
+    let row = &table[index * 12..][..12];
+
+    // Any other total of the corners needs the turn of all four pins
+    let (count, free) = *row.iter().min().unwrap();
+    min(row[total], (count + 1, free))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Reason;
+    use rand::seq::SliceRandom;
+
+    #[test]
+    pub fn pins() {
+        // A single pin turns the four dials around it, and the corner behind the other way
+        let puzzle = Clock::from_iter(ClockMetric::parse("UR1+").unwrap());
+        let [front, back] = puzzle.faces();
+        assert_eq!(front, [0, 1, 1, 0, 1, 1, 0, 0, 0]);
+        assert_eq!(back, [11, 0, 0, 0, 0, 0, 0, 0, 0]);
+
+        let puzzle = Clock::from_iter(ClockMetric::parse("ALL1+").unwrap());
+        let [front, back] = puzzle.faces();
+        assert_eq!(front, [1; 9]);
+        assert_eq!(back, [11, 0, 11, 0, 0, 0, 11, 0, 11]);
+
+        // The same pin on the back turns the dials between its corners there
+        let puzzle = Clock::from_iter(ClockMetric::parse("y2 UR1+ y2").unwrap());
+        assert_eq!(puzzle.dials, [11, 0, 0, 0, 0, 0, 0, 0, 0, 1, 0, 1, 1, 0]);
+        let flipped = puzzle.apply(ClockMetric::Flip);
+        assert_eq!(flipped.faces(), [puzzle.faces()[1], puzzle.faces()[0]]);
+        assert_eq!(flipped.apply(ClockMetric::Flip), puzzle);
+    }
+
+    #[test]
+    pub fn notation() {
+        let scramble = ClockMetric::parse("UR3+ DL2-  y2 ALL6- U0+").unwrap();
+        assert_eq!(
+            ClockMetric::format_seq(scramble.iter().copied()),
+            "UR3+ DL2- y2 ALL6+ U0+"
+        );
+        assert_eq!(
+            ClockMetric::turn(Pins::U, 13),
+            ClockMetric::Turn(Pins::U, 1)
+        );
+        assert_eq!(
+            ClockMetric::turn(Pins::U, 7),
+            ClockMetric::Turn(Pins::U, -5)
+        );
+
+        let error = |str: &str| ClockMetric::parse(str).unwrap_err().reason;
+        assert_eq!(error("UR7+"), Reason::BadAmount);
+        assert_eq!(error("UR3"), Reason::BadAmount);
+        assert_eq!(error("UR+"), Reason::BadAmount);
+        assert_eq!(error("UR-3+"), Reason::BadAmount);
+        assert_eq!(error("UF3+"), Reason::UnknownFace);
+        assert_eq!(error("uR3+"), Reason::UnknownFace);
+        assert_eq!(error("//3+"), Reason::UnknownFace);
+
+        // The settings not named by the WCA
+        let turns = ClockMetric::parse("/1+ \\2- ur3+ dr4- dl5+ ul6+").unwrap();
+        let pins = [
+            Pins::Slash,
+            Pins::Backslash,
+            Pins::NotUR,
+            Pins::NotDR,
+            Pins::NotDL,
+            Pins::NotUL,
+        ];
+        for (&turn, &pins) in turns.iter().zip(pins.iter()) {
+            assert!(matches!(turn, ClockMetric::Turn(p, _) if p == pins));
+            assert_eq!(
+                pins.mask().count_ones(),
+                if pins < Pins::NotUR { 2 } else { 3 }
+            );
+        }
+        assert_eq!(
+            ClockMetric::format_seq(turns.iter().copied()),
+            "/1+ \\2- ur3+ dr4- dl5+ ul6+"
+        );
+    }
+
+    #[test]
+    pub fn solutions() {
+        assert!(Clock::default().solve().is_empty());
+
+        let puzzle = Clock::from_iter(ClockMetric::parse("UL1+").unwrap());
+        assert_eq!(puzzle.solve(), vec![ClockMetric::Turn(Pins::UL, -1)]);
+        let puzzle = Clock::from_iter(ClockMetric::parse("y2 UR1+ y2").unwrap());
+        assert_eq!(
+            puzzle.solve(),
+            vec![ClockMetric::Flip, ClockMetric::Turn(Pins::UR, -1)]
+        );
+
+        // Every dial but one turned, which three pins up solve in a single turn, and two
+        // diagonals, which are each one turn of the pins at opposite corners
+        let puzzle = Clock {
+            dials: [0, 1, 1, 1, 1, 1, 1, 1, 1, 0, 0, 0, 0, 0],
+        };
+        assert_eq!(puzzle.solve(), vec![ClockMetric::Turn(Pins::NotUL, -1)]);
+        let puzzle = Clock::from_iter(ClockMetric::parse("/2+ y2 \\3-").unwrap());
+        let solution = puzzle.solve();
+        assert_eq!(solution.len(), 3);
+        assert!(puzzle.apply_seq(solution).is_solved());
+
+        let mut rng = rand::thread_rng();
+        let moves = Pins::ALL
+            .iter()
+            .map(|&pins| ClockMetric::Turn(pins, 1))
+            .chain(std::iter::once(ClockMetric::Flip))
+            .collect::<Vec<_>>();
+        for _ in 0..5 {
+            let turns = (0..3).map(|_| *moves.choose(&mut rng).unwrap());
+            let puzzle = Clock::from_iter(turns);
+            let solution = puzzle.solve();
+            let count = solution
+                .iter()
+                .filter(|&&turn| turn != ClockMetric::Flip)
+                .count();
+            assert!(count <= 3);
+            assert!(puzzle.apply_seq(solution).is_solved());
+        }
+
+        for _ in 0..3 {
+            let puzzle = Clock::random_state();
+            assert!(puzzle.apply_seq(puzzle.solve()).is_solved());
+        }
+    }
+
+    #[test]
+    pub fn scrambles() {
+        let scramble = Clock::random_scramble();
+        assert_eq!(scramble.len(), 15);
+        assert_eq!(scramble[9], ClockMetric::Flip);
+
+        let pins = scramble
+            .iter()
+            .filter_map(|&turn| match turn {
+                ClockMetric::Turn(pins, amount) => {
+                    assert!((-5..=6).contains(&amount));
+                    Some(pins)
+                }
+                ClockMetric::Flip => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(&pins[..9], &Pins::WCA);
+        assert_eq!(&pins[9..], &[Pins::U, Pins::R, Pins::D, Pins::L, Pins::All]);
+
+        let text = ClockMetric::format_seq(scramble.iter().copied());
+        assert_eq!(ClockMetric::parse(&text).unwrap(), scramble);
+
+        let puzzle = Clock::from_iter(scramble);
+        assert!(puzzle.apply_seq(puzzle.solve()).is_solved());
+    }
+}
@@ -1,9 +1,30 @@
 use crate::core::definitions as def;
+use crate::core::pruning;
+use crate::core::search::{walk, Depth, Search};
+use crate::core::transition as trans;
+use crate::metric::{Htm, HtmRuf};
+use crate::notation::Rotation;
+use crate::puzzle::Cube3x3;
+use std::convert::{TryFrom, TryInto};
+use std::iter::FromIterator;
 
 type Permutation = def::PermutationCoord<8>;
 type Orientation = def::OrientationCoord<8, 3>;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+type Corners = def::Array<8, 3>;
+type ReducedCorners = def::Array<7, 3>;
+type ReducedPermutation = def::PermutationCoord<7>;
+type ReducedOrientation = def::OrientationCoord<7, 3>;
+type Coordinate = def::Coordinate<7, 3>;
+
+/// The corner which R, U and F leave in place, DBL.
+const FIXED: u8 = 6;
+const MOVE_COUNT: usize = HtmRuf::COUNT;
+const STATES: usize = ReducedPermutation::BOUND * ReducedOrientation::BOUND;
+
+pub type Tables = (Table, PruningTable);
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Cube2x2 {
     pub permutation: Permutation,
     pub orientation: Orientation,
@@ -17,8 +38,248 @@ impl Cube2x2 {
         }
     }
 
+    pub fn apply(&self, htm: Htm) -> Self {
+        let corners = htm.to_corners();
+        Self::new(
+            self.permutation.permute(corners),
+            self.orientation.permute(corners),
+        )
+    }
+
+    pub fn apply_seq(&self, sequence: impl IntoIterator<Item = Htm>) -> Self {
+        sequence
+            .into_iter()
+            .fold(*self, |cube, turn| cube.apply(turn))
+    }
+
     pub fn random_state() -> Self {
-        let array = def::Array::random();
-        Self::new(array.p_coordinate(), array.o_coordinate())
+        Self::from(&def::Array::random())
+    }
+
+    pub fn corners(&self) -> Corners {
+        def::Coordinate(self.orientation, self.permutation).array()
+    }
+
+    /// Whether the cube is solved, in any orientation.
+    pub fn is_solved(&self) -> bool {
+        let corners = self.corners();
+        Cube3x3::rotations().any(|cube| cube.corners == corners)
+    }
+
+    /// The same position seen after a rotation of the whole cube which brings DBL into place,
+    /// together with that rotation. Any sequence solving the position with R, U and F solves
+    /// this cube once each turn is rotated by `Htm::rotate`.
+    pub fn reduce(&self) -> (Reduced, Rotation) {
+        let corners = self.corners();
+
+        Cube3x3::rotations()
+            .find_map(|rotation| {
+                let reduced = reduce(&corners.permute(&rotation.corners))?;
+                Some((Reduced::from(&reduced), rotation.centres))
+            })
+            .unwrap()
+    }
+
+    /// An optimal solution in the half turn metric, found by walking down the pruning table.
+    pub fn solve(&self, (table, pruning_table): &Tables) -> Vec<Htm> {
+        let (position, rotation) = self.reduce();
+
+        walk(position, pruning_table, table)
+            .into_iter()
+            .map(|turn| HtmRuf::ALL[turn].to_htm().rotate(rotation))
+            .collect()
+    }
+
+    /// A scramble for a random state, which is the inverse of its optimal solution. As for the
+    /// WCA event, states which can be solved in fewer than four turns are skipped.
+    pub fn random_scramble(tables: &Tables) -> Vec<Htm> {
+        loop {
+            let solution = Self::random_state().solve(tables);
+            if solution.len() >= 4 {
+                return Htm::invert_seq(&solution);
+            }
+        }
+    }
+}
+
+impl From<&Corners> for Cube2x2 {
+    fn from(corners: &Corners) -> Self {
+        Self::new(corners.p_coordinate(), corners.o_coordinate())
+    }
+}
+
+impl FromIterator<Htm> for Cube2x2 {
+    fn from_iter<T: IntoIterator<Item = Htm>>(iter: T) -> Self {
+        Self::default().apply_seq(iter)
+    }
+}
+
+/// The corners other than DBL, if it is solved.
+fn reduce(corners: &Corners) -> Option<ReducedCorners> {
+    let corners = corners.elements();
+    if corners[FIXED as usize] != (FIXED, 0) {
+        return None;
+    }
+
+    let relabel = |corner: u8| if corner > FIXED { corner - 1 } else { corner };
+    let mut reduced = [(0, 0); 7];
+    for (ix, &(corner, twist)) in corners.iter().filter(|&&(c, _)| c != FIXED).enumerate() {
+        reduced[ix] = (relabel(corner), twist);
+    }
+
+    ReducedCorners::try_from(reduced).ok()
+}
+
+/// The cube with DBL in place, which can be solved with R, U and F alone. Its 3,674,160
+/// positions are small enough for a pruning table of the exact distance of each.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Reduced(Coordinate);
+
+impl From<&ReducedCorners> for Reduced {
+    fn from(corners: &ReducedCorners) -> Self {
+        Self(def::Coordinate(
+            corners.o_coordinate(),
+            corners.p_coordinate(),
+        ))
+    }
+}
+
+impl From<Reduced> for usize {
+    fn from(Reduced(coordinate): Reduced) -> Self {
+        coordinate.into()
+    }
+}
+
+impl TryFrom<usize> for Reduced {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self(value.try_into()?))
+    }
+}
+
+impl Search for Reduced {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.0.lookup(self)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        (0..MOVE_COUNT)
+            .map(|ix| (table.lookup(self, ix), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+pub struct Table(
+    trans::Table<ReducedPermutation, { ReducedPermutation::BOUND }, MOVE_COUNT>,
+    trans::Table<ReducedOrientation, { ReducedOrientation::BOUND }, MOVE_COUNT>,
+);
+
+impl Table {
+    pub fn new() -> Self {
+        let moves = HtmRuf::ALL.map(|turn| reduce(turn.to_htm().to_corners()).unwrap());
+
+        Self(
+            trans::Table::new(
+                &moves,
+                ReducedPermutation::all(),
+                ReducedPermutation::permute,
+            ),
+            trans::Table::new(
+                &moves,
+                ReducedOrientation::all(),
+                ReducedOrientation::permute,
+            ),
+        )
+    }
+
+    pub fn lookup(&self, Reduced(def::Coordinate(o, p)): Reduced, index: usize) -> Reduced {
+        let Self(p_table, o_table) = self;
+        Reduced(def::Coordinate(
+            o_table.lookup(o, index),
+            p_table.lookup(p, index),
+        ))
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct PruningTable(pruning::Table<Reduced, STATES>);
+
+impl PruningTable {
+    pub fn new(table: &Table) -> Self {
+        Self(pruning::Table::new(
+            &HtmRuf::ALL,
+            |position: Reduced, &turn: &HtmRuf| table.lookup(position, turn as usize),
+        ))
+    }
+}
+
+pub fn generate_tables() -> Tables {
+    let table = Table::new();
+    let pruning_table = PruningTable::new(&table);
+
+    (table, pruning_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Alg;
+
+    #[test]
+    pub fn distances() {
+        let (_, pruning_table) = generate_tables();
+
+        // The number of positions at each distance, from God's number for the 2x2x2
+        let mut counts = [0; 12];
+        for ix in 0..STATES {
+            let position = Reduced::try_from(ix).unwrap();
+            counts[position.heuristic(&pruning_table) as usize] += 1;
+        }
+        assert_eq!(
+            counts,
+            [1, 9, 54, 321, 1847, 9992, 50136, 227536, 870072, 1887748, 623800, 2644]
+        );
+    }
+
+    #[test]
+    pub fn orientations() {
+        let tables = generate_tables();
+
+        // Every orientation of the solved cube is solved, such as after turning both R and L
+        for cube in Cube3x3::rotations() {
+            let cube = Cube2x2::from(&cube.corners);
+            assert!(cube.is_solved());
+            assert!(cube.solve(&tables).is_empty());
+        }
+        let turned: Alg = "R L'".parse().unwrap();
+        assert!(turned.state::<Cube2x2>().is_solved());
+
+        // Turns of L, D and B are solved by turns of R, U and F after a rotation
+        let scramble: Alg = "L2 D B'".parse().unwrap();
+        let cube = scramble.state::<Cube2x2>();
+        let solution = cube.solve(&tables);
+        assert_eq!(solution.len(), 3);
+        assert!(cube.apply_seq(solution).is_solved());
+        assert_eq!(Cube2x2::default().apply(Htm::L1).solve(&tables).len(), 1);
+
+        // Scrambles are for states at least four turns from solved
+        for _ in 0..20 {
+            let scramble = Cube2x2::random_scramble(&tables);
+            let cube = Cube2x2::from_iter(scramble.iter().copied());
+            assert!(scramble.len() >= 4);
+            assert_eq!(cube.solve(&tables).len(), scramble.len());
+        }
     }
 }
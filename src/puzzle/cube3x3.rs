@@ -1,26 +1,136 @@
 use crate::core::definitions as def;
+use crate::core::search::{Depth, Search};
 use crate::metric::htm::{Corners, Edges};
-use crate::metric::Htm;
+use crate::metric::{Htm, Stm};
+use crate::notation::{Move, Rotation};
+use std::convert::TryFrom;
 use std::iter::FromIterator;
 
-#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Cube3x3 {
     pub corners: Corners,
     pub edges: Edges,
+    /// The orientation of the centres, which is only changed by slice moves and rotations.
+    pub centres: Rotation,
 }
 
 impl Cube3x3 {
+    /// The rotation of the whole cube in the direction of R.
+    #[rustfmt::skip]
+    pub const X: Self = Self {
+        corners: Corners::new([(4, 2), (5, 1), (1, 2), (0, 1), (7, 1), (6, 2), (2, 1), (3, 2)]),
+        edges: Edges::new([(8, 0), (5, 1), (9, 0), (1, 1), (11, 0), (7, 1), (10, 0), (3, 1), (4, 0), (6, 0), (2, 0), (0, 0)]),
+        centres: Rotation::X,
+    };
+
+    /// The rotation of the whole cube in the direction of U.
+    #[rustfmt::skip]
+    pub const Y: Self = Self {
+        corners: Corners::new([(3, 0), (0, 0), (1, 0), (2, 0), (7, 0), (4, 0), (5, 0), (6, 0)]),
+        edges: Edges::new([(3, 0), (0, 0), (1, 0), (2, 0), (7, 0), (4, 0), (5, 0), (6, 0), (11, 1), (8, 1), (9, 1), (10, 1)]),
+        centres: Rotation::Y,
+    };
+
+    /// The rotation of the whole cube in the direction of F.
+    #[rustfmt::skip]
+    pub const Z: Self = Self {
+        corners: Corners::new([(1, 1), (5, 2), (6, 1), (2, 2), (0, 2), (4, 1), (7, 2), (3, 1)]),
+        edges: Edges::new([(2, 1), (9, 1), (6, 1), (10, 1), (0, 1), (8, 1), (4, 1), (11, 1), (1, 1), (5, 1), (7, 1), (3, 1)]),
+        centres: Rotation::Z,
+    };
+
+    /// The state with the given corners and edges, and the centres in place.
     pub const fn new(corners: Corners, edges: Edges) -> Self {
-        Self { corners, edges }
+        Self {
+            corners,
+            edges,
+            centres: Rotation::IDENTITY,
+        }
+    }
+
+    /// This state followed by `other`.
+    pub const fn permute(&self, other: &Self) -> Self {
+        Self {
+            corners: self.corners.permute(&other.corners),
+            edges: self.edges.permute(&other.edges),
+            centres: self.centres.then(other.centres),
+        }
     }
 
     pub const fn apply(&self, htm: Htm) -> Self {
-        let Self { corners, edges } = self;
+        let Self {
+            corners,
+            edges,
+            centres,
+        } = self;
 
         let corners = corners.permute(htm.to_corners());
         let edges = edges.permute(htm.to_edges());
 
-        Self { corners, edges }
+        Self {
+            corners,
+            edges,
+            centres: *centres,
+        }
+    }
+
+    pub const fn apply_stm(&self, stm: Stm) -> Self {
+        self.permute(&Self {
+            corners: Stm::CORNER_MOVES[stm as usize],
+            edges: Stm::EDGE_MOVES[stm as usize],
+            centres: stm.rotation(),
+        })
+    }
+
+    /// Make a move in any notation, turning the centres along with wide and slice moves and
+    /// rotations.
+    pub fn apply_move(&self, turn: Move) -> Self {
+        let (faces, rotation) = turn.decompose();
+
+        faces
+            .into_iter()
+            .fold(*self, |cube, (face, amount)| {
+                cube.apply(Htm::new(face, amount))
+            })
+            .rotate(rotation)
+    }
+
+    /// This state with the whole cube rotated afterwards.
+    pub fn rotate(&self, rotation: Rotation) -> Self {
+        self.permute(&Self::rotation(rotation))
+    }
+
+    /// The solved cube in each of its 24 orientations.
+    pub fn rotations() -> impl Iterator<Item = Self> {
+        // Each orientation brings one of the six faces to U, followed by a number of y rotations
+        let tilts = [
+            Self::default(),
+            Self::X,
+            Self::X.permute(&Self::X),
+            Self::X.inverse(),
+            Self::Z,
+            Self::Z.inverse(),
+        ];
+
+        IntoIterator::into_iter(tilts).flat_map(|tilt| {
+            (0..4).scan(tilt, |cube, _| {
+                let current = *cube;
+                *cube = cube.permute(&Self::Y);
+                Some(current)
+            })
+        })
+    }
+
+    /// The solved cube in the given orientation.
+    pub fn rotation(rotation: Rotation) -> Self {
+        Self::rotations()
+            .find(|cube| cube.centres == rotation)
+            .unwrap()
+    }
+
+    /// Whether every piece is in place relative to the centres, in any orientation of the cube.
+    pub fn is_solved(&self) -> bool {
+        *self == Self::rotation(self.centres)
     }
 
     pub const fn apply_slice(self, slice: &[Htm]) -> Self {
@@ -38,7 +148,7 @@ impl Cube3x3 {
     pub fn apply_seq(&self, sequence: impl IntoIterator<Item = Htm>) -> Self {
         sequence
             .into_iter()
-            .fold(self.clone(), |cube, turn| cube.apply(turn))
+            .fold(*self, |cube, turn| cube.apply(turn))
     }
 
     pub const fn from_slice(slice: &[Htm]) -> Self {
@@ -46,6 +156,15 @@ impl Cube3x3 {
         start.apply_slice(slice)
     }
 
+    /// The state which undoes this one, reached by the inverse of any sequence reaching this.
+    pub const fn inverse(&self) -> Self {
+        Self {
+            corners: self.corners.inverse(),
+            edges: self.edges.inverse(),
+            centres: self.centres.inverse(),
+        }
+    }
+
     pub fn random_state() -> Self {
         Self::new(def::Array::random(), def::Array::random())
     }
@@ -56,7 +175,13 @@ impl From<Htm> for Cube3x3 {
         let corners: &Corners = htm.into();
         let edges: &Edges = htm.into();
 
-        Self::new(corners.clone(), edges.clone())
+        Self::new(*corners, *edges)
+    }
+}
+
+impl From<Stm> for Cube3x3 {
+    fn from(stm: Stm) -> Self {
+        Self::default().apply_stm(stm)
     }
 }
 
@@ -72,3 +197,25 @@ impl FromIterator<Htm> for Cube3x3 {
         Self::new(corners, edges)
     }
 }
+
+/// Searching the full cube state directly is only practical for short distances, e.g. with
+/// `core::search::bidirectional`, so there is no heuristic.
+impl Search for Cube3x3 {
+    type Edge = Htm;
+    type HeuristicData = ();
+    type TransitionData = ();
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+
+    fn heuristic(self, _: &Self::HeuristicData) -> Depth {
+        0
+    }
+
+    fn transition(self, _: &Self::TransitionData) -> Self::Iter {
+        Htm::GENERATORS
+            .iter()
+            .map(|&ix| Htm::try_from(ix).unwrap())
+            .map(|htm| (self.apply(htm), htm))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
@@ -0,0 +1,697 @@
+//! The 4x4x4, solved by reduction to the 3x3x3. The centres are solved in three steps: first the
+//! U and D centres are brought to U and D, then the L and R centres to L and R, and then each
+//! centre to its own face with turns which keep opposite faces together. The edges are then paired
+//! by slicing, replacing one of the edges in the slice with another and slicing back. What remains
+//! is a 3x3x3, once any parity has been fixed, which is solved with Kociemba's algorithm.
+
+use crate::algorithm::kociemba;
+use crate::core::definitions as def;
+use crate::core::pruning;
+use crate::core::search::{ida_iter, walk, Depth, Search};
+use crate::core::transition as trans;
+use crate::metric::cube4x4::{Centres, Layers, Wings};
+use crate::metric::htm::{Corners, Edges, Face};
+use crate::metric::{Cube4x4 as Cube4x4Metric, Htm};
+use crate::puzzle::Cube3x3;
+use crate::util;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::convert::{TryFrom, TryInto};
+use std::iter::FromIterator;
+
+type Colour = def::CombinationCoord<24, 4>;
+type Sides = def::CombinationCoord<16, 8>;
+type Pair = def::CombinationCoord<8, 4>;
+
+/// The positions of the centres on each pair of opposite faces, as indices into `Centres`.
+const UD: [u8; 8] = [0, 1, 2, 3, 16, 17, 18, 19];
+const LR: [u8; 8] = [4, 5, 6, 7, 12, 13, 14, 15];
+const FB: [u8; 8] = [8, 9, 10, 11, 20, 21, 22, 23];
+/// The positions of the centres on R, F, L and B.
+const SIDES: [u8; 16] = [4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 20, 21, 22, 23];
+
+/// The turns keeping the U and D centres on U and D: any turn of the outer layers or of the inner
+/// layers next to U and D, and half turns of the other inner layers.
+const SIDE_TURNS: [usize; SIDE_TURN_COUNT] = {
+    let mut turns = [0; SIDE_TURN_COUNT];
+
+    let mut ix = 0;
+    let mut turn = 0;
+    while turn < Cube4x4Metric::COUNT {
+        let face = Face::ALL[turn % Htm::COUNT / 3];
+        if turn < Htm::COUNT || matches!(face, Face::U | Face::D) || turn % 3 == 1 {
+            turns[ix] = turn;
+            ix += 1;
+        }
+        turn += 1;
+    }
+
+    turns
+};
+const SIDE_TURN_COUNT: usize = 28;
+
+/// The turns keeping each pair of opposite faces together: any turn of the outer layers, and half
+/// turns of the inner ones.
+const FACE_TURNS: [usize; FACE_TURN_COUNT] = {
+    let mut turns = [0; FACE_TURN_COUNT];
+
+    let mut ix = 0;
+    while ix < FACE_TURN_COUNT {
+        turns[ix] = if ix < Htm::COUNT {
+            ix
+        } else {
+            Htm::COUNT + (ix - Htm::COUNT) * 3 + 1
+        };
+        ix += 1;
+    }
+
+    turns
+};
+const FACE_TURN_COUNT: usize = 24;
+
+/// The sequences placed between slicing and slicing back to pair the edges, each of which moves an
+/// edge of the slice out to U and one from U in, and leaves the centres where they were so that
+/// slicing back restores them.
+const FLIPS: [&str; 4] = [
+    "R U R' F R' F' R",
+    "L' U' L F' L F L'",
+    "R F' U R' F",
+    "L' F U' L F'",
+];
+
+const OLL_PARITY: &str = "2R2 B2 U2 2L U2 2R' U2 2R U2 F2 2R F2 2L' B2 2R2";
+const PLL_PARITY: &str = "2R2 U2 2R2 Uw2 2R2 Uw2";
+
+/// The number of turns in a sequence of random moves, as the WCA used.
+const SCRAMBLE_LENGTH: usize = 40;
+
+pub type Tables = (Table, PruningTable, kociemba::Tables);
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Cube4x4 {
+    pub corners: Corners,
+    pub wings: Wings,
+    pub centres: Centres,
+}
+
+impl Cube4x4 {
+    pub fn apply(&self, turn: Cube4x4Metric) -> Self {
+        turn.generators()
+            .fold(*self, |cube, generator| cube.turn(generator))
+    }
+
+    pub fn apply_seq(&self, sequence: impl IntoIterator<Item = Cube4x4Metric>) -> Self {
+        sequence
+            .into_iter()
+            .fold(*self, |cube, turn| cube.apply(turn))
+    }
+
+    /// Turn the single layer with the index `generator` in `Cube4x4Metric::ALL`.
+    fn turn(&self, generator: usize) -> Self {
+        Self {
+            corners: self
+                .corners
+                .permute(Cube4x4Metric::ALL[generator].corners()),
+            wings: self.wings.permute(&Cube4x4Metric::WING_MOVES[generator]),
+            centres: self
+                .centres
+                .permute(&Cube4x4Metric::CENTRE_MOVES[generator]),
+        }
+    }
+
+    fn turn_seq(&self, generators: &[usize]) -> Self {
+        generators
+            .iter()
+            .fold(*self, |cube, &generator| cube.turn(generator))
+    }
+
+    /// A uniformly random position. Unlike the 3x3x3, the corners and wings may be in any
+    /// permutation, since the inner layers swap the wings, and so may the centres, which can be
+    /// swapped with others of the same colour.
+    pub fn random_state() -> Self {
+        Self {
+            corners: Corners::random(),
+            wings: Wings::random(),
+            centres: Centres::random(),
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.corners == Corners::IDENTITY && self.wings == Wings::IDENTITY && self.centres_solved()
+    }
+
+    /// Whether each centre is on its own face.
+    pub fn centres_solved(&self) -> bool {
+        self.centres
+            .elements()
+            .iter()
+            .enumerate()
+            .all(|(ix, &(centre, _))| centre / 4 == ix as u8 / 4)
+    }
+
+    /// The number of edges whose two wings are next to each other.
+    pub fn paired(&self) -> usize {
+        let wings = self.wings.elements();
+        (0..12)
+            .filter(|ix| wings[2 * ix].0 / 2 == wings[2 * ix + 1].0 / 2)
+            .count()
+    }
+
+    /// The 3x3x3 made of the corners and the paired edges, if the centres are solved and every
+    /// edge is paired. This may be a position which cannot be reached on the 3x3x3, with a single
+    /// edge flipped or two edges swapped, which is fixed by `reduce`.
+    pub fn to_3x3(&self) -> Option<(Corners, [(u8, u8); 12])> {
+        if !self.centres_solved() || self.paired() < 12 {
+            return None;
+        }
+
+        let wings = self.wings.elements();
+        let mut edges = [(0, 0); 12];
+        for (ix, edge) in edges.iter_mut().enumerate() {
+            let wing = wings[2 * ix].0;
+            *edge = (wing / 2, wing % 2);
+        }
+
+        Some((self.corners, edges))
+    }
+
+    /// A sequence solving the centres and pairing the edges, leaving a position which can be
+    /// solved as a 3x3x3, or `None` if the edges could not be paired.
+    pub fn reduce(&self, (table, pruning_table, _): &Tables) -> Option<Vec<Cube4x4Metric>> {
+        let mut cube = *self;
+        let mut generators = solve_up_down(&cube, table, pruning_table);
+        cube = cube.turn_seq(&generators);
+
+        let sides: Vec<usize> = walk(Sides::from(&cube), pruning_table, table)
+            .into_iter()
+            .map(|ix| SIDE_TURNS[ix])
+            .collect();
+        cube = cube.turn_seq(&sides);
+        generators.extend(sides);
+
+        let faces: Vec<usize> = walk(Faces::from(&cube), pruning_table, table)
+            .into_iter()
+            .map(|ix| FACE_TURNS[ix])
+            .collect();
+        cube = cube.turn_seq(&faces);
+        generators.extend(faces);
+
+        let mut reduction: Vec<_> = generators
+            .into_iter()
+            .map(|ix| Cube4x4Metric::ALL[ix])
+            .collect();
+
+        let pairing = cube.pair_edges()?;
+        cube = cube.apply_seq(pairing.iter().copied());
+        reduction.extend(pairing);
+
+        // A single edge flipped on its own, and an odd permutation of the edges against the
+        // corners, which the 3x3x3 cannot have and which are fixed independently of each other.
+        let (corners, edges) = cube.to_3x3()?;
+        let flipped = edges.iter().map(|&(_, flip)| flip).sum::<u8>() % 2 == 1;
+        let swapped = corners.is_even()
+            != def::Array::<12, 1>::new(edges.map(|(edge, _)| (edge, 0))).is_even();
+        if flipped {
+            reduction.extend(Cube4x4Metric::parse(OLL_PARITY).unwrap());
+        }
+        if swapped {
+            reduction.extend(Cube4x4Metric::parse(PLL_PARITY).unwrap());
+        }
+
+        Some(reduction)
+    }
+
+    /// Pair the edges one or two at a time, each time choosing the setup of up to three outer
+    /// turns, the slice and the sequence from `FLIPS` which pair the most, or `None` if none of
+    /// them pairs another edge.
+    fn pair_edges(&self) -> Option<Vec<Cube4x4Metric>> {
+        let flips = FLIPS.map(|flip| Cube4x4Metric::parse(flip).unwrap());
+        let slices = ["2U", "2U'", "2D", "2D'"].map(|slice| slice.parse().unwrap());
+
+        let mut cube = *self;
+        let mut solution = Vec::new();
+
+        while cube.paired() < 12 {
+            let mut setups = vec![Vec::new()];
+            let mut best: Option<(Self, Vec<Cube4x4Metric>)> = None;
+
+            for _ in 0..4 {
+                for setup in &setups {
+                    for &slice in &slices {
+                        for flip in &flips {
+                            let mut step = setup.clone();
+                            step.push(slice);
+                            step.extend(flip);
+                            step.push(Cube4x4Metric::inverse(slice));
+
+                            let next = cube.apply_seq(step.iter().copied());
+                            let paired = best
+                                .as_ref()
+                                .map_or(cube.paired(), |(best, _)| best.paired());
+                            if next.centres_solved() && next.paired() > paired {
+                                best = Some((next, step));
+                            }
+                        }
+                    }
+                }
+
+                if best.is_some() {
+                    break;
+                }
+                setups = extend(&setups);
+            }
+
+            let (next, step) = best?;
+            cube = next;
+            solution.extend(step);
+        }
+
+        Some(solution)
+    }
+
+    /// A solution, which is far from optimal: the reduction followed by a solution of the
+    /// 3x3x3 with Kociemba's algorithm, or `None` if the reduction failed.
+    pub fn solve(&self, tables: &Tables) -> Option<Vec<Cube4x4Metric>> {
+        let mut solution = self.reduce(tables)?;
+        let cube = self.apply_seq(solution.iter().copied());
+
+        let (corners, edges) = cube.to_3x3()?;
+        let cube = Cube3x3::new(corners, Edges::new(edges));
+        solution.extend(
+            cube.kociemba(&tables.2, None)
+                .into_iter()
+                .map(Cube4x4Metric::from_htm),
+        );
+
+        Some(solution)
+    }
+
+    /// A scramble for a random state, which is the inverse of its solution, or `None` if the
+    /// reduction failed.
+    pub fn random_scramble(tables: &Tables) -> Option<Vec<Cube4x4Metric>> {
+        let solution = Self::random_state().solve(tables)?;
+        Some(Cube4x4Metric::invert_seq(&solution))
+    }
+
+    /// A sequence of random turns of the outer layers and of both layers at a face, as the WCA
+    /// used to scramble before it scrambled to random states. No two turns in a row are of the
+    /// same face.
+    pub fn random_moves() -> Vec<Cube4x4Metric> {
+        let mut rng = rand::thread_rng();
+        let mut scramble: Vec<Cube4x4Metric> = Vec::with_capacity(SCRAMBLE_LENGTH);
+
+        while scramble.len() < SCRAMBLE_LENGTH {
+            let face = *Face::ALL.choose(&mut rng).unwrap();
+            if scramble.last().map(|last| last.face) == Some(face) {
+                continue;
+            }
+
+            let layers = *[Layers::Outer, Layers::Wide].choose(&mut rng).unwrap();
+            scramble.push(Cube4x4Metric::new(face, layers, rng.gen_range(1..4)));
+        }
+
+        scramble
+    }
+}
+
+impl FromIterator<Cube4x4Metric> for Cube4x4 {
+    fn from_iter<T: IntoIterator<Item = Cube4x4Metric>>(iter: T) -> Self {
+        Self::default().apply_seq(iter)
+    }
+}
+
+/// The setups one outer turn longer than `setups`, not turning the same face twice in a row.
+fn extend(setups: &[Vec<Cube4x4Metric>]) -> Vec<Vec<Cube4x4Metric>> {
+    setups
+        .iter()
+        .flat_map(|setup| {
+            Htm::ALL
+                .iter()
+                .filter(move |turn| setup.last().map(|last| last.face) != Some(turn.face()))
+                .map(move |&turn| {
+                    let mut setup = setup.clone();
+                    setup.push(Cube4x4Metric::from_htm(turn));
+                    setup
+                })
+        })
+        .collect()
+}
+
+/// The combination of the centres at `positions` which belong to any of `faces`.
+fn mark<const N: usize, const K: usize>(
+    centres: &Centres,
+    positions: &[u8; N],
+    faces: &[Face],
+) -> def::CombinationCoord<N, K> {
+    let centres = centres.elements();
+
+    let (mut marked, mut unmarked) = (N - K, 0);
+    let mut labels = [(0, 0); N];
+    for (label, &position) in labels.iter_mut().zip(positions) {
+        let face = Face::ALL[centres[position as usize].0 as usize / 4];
+        let counter = if faces.contains(&face) {
+            &mut marked
+        } else {
+            &mut unmarked
+        };
+        *label = (*counter as u8, 0);
+        *counter += 1;
+    }
+
+    def::Array::<N, 1>::new(labels).c_coordinate()
+}
+
+/// The movement of the centres at `positions` by a turn which keeps them among themselves.
+fn restrict<const N: usize>(centres: &Centres, positions: &[u8; N]) -> def::Array<N, 1> {
+    let centres = centres.elements();
+
+    let mut restricted = [(0, 0); N];
+    for (ix, &position) in positions.iter().enumerate() {
+        let (centre, _) = centres[position as usize];
+        let centre = positions.iter().position(|&p| p == centre).unwrap();
+        restricted[ix] = (centre as u8, 0);
+    }
+
+    def::Array::new(restricted)
+}
+
+/// The positions of the U and of the D centres, which are to be brought to U and D in either
+/// order.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+struct UpDown {
+    up: Colour,
+    down: Colour,
+}
+
+impl Search for UpDown {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.0.lookup(self.up).max(table.0.lookup(self.down))
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        (0..Cube4x4Metric::COUNT)
+            .map(|ix| {
+                let position = Self {
+                    up: table.0.lookup(self.up, ix),
+                    down: table.0.lookup(self.down, ix),
+                };
+                (position, ix)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+fn solve_up_down(cube: &Cube4x4, table: &Table, pruning_table: &PruningTable) -> Vec<usize> {
+    let positions = util::count::<24>().map(|ix| ix as u8);
+    let position = UpDown {
+        up: mark(&cube.centres, &positions, &[Face::U]),
+        down: mark(&cube.centres, &positions, &[Face::D]),
+    };
+    let goal = |position: &UpDown| position.heuristic(pruning_table) == 0;
+
+    ida_iter(position, &goal, pruning_table, table, None)
+        .next()
+        .unwrap()
+        .into_iter()
+        .map(|(_, ix)| ix)
+        .collect()
+}
+
+impl From<&Cube4x4> for Sides {
+    fn from(cube: &Cube4x4) -> Self {
+        mark(&cube.centres, &SIDES, &[Face::L, Face::R])
+    }
+}
+
+/// The position of the centres on each pair of opposite faces, by which of them belong to the
+/// second face of the pair.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Faces {
+    ud: Pair,
+    lr: Pair,
+    fb: Pair,
+}
+
+impl Faces {
+    pub const BOUND: usize = Pair::BOUND * Pair::BOUND * Pair::BOUND;
+}
+
+impl From<&Cube4x4> for Faces {
+    fn from(cube: &Cube4x4) -> Self {
+        Self {
+            ud: mark(&cube.centres, &UD, &[Face::D]),
+            lr: mark(&cube.centres, &LR, &[Face::L]),
+            fb: mark(&cube.centres, &FB, &[Face::B]),
+        }
+    }
+}
+
+impl From<Faces> for usize {
+    fn from(Faces { ud, lr, fb }: Faces) -> Self {
+        (usize::from(ud) * Pair::BOUND + usize::from(lr)) * Pair::BOUND + usize::from(fb)
+    }
+}
+
+impl TryFrom<usize> for Faces {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            ud: (value / Pair::BOUND / Pair::BOUND).try_into()?,
+            lr: (value / Pair::BOUND % Pair::BOUND).try_into()?,
+            fb: (value % Pair::BOUND).try_into()?,
+        })
+    }
+}
+
+impl Search for Faces {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.2.lookup(self)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        (0..FACE_TURNS.len())
+            .map(|ix| (table.faces(self, ix), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl Search for Sides {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.1.lookup(self)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        (0..SIDE_TURNS.len())
+            .map(|ix| (table.1.lookup(self, ix), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+pub struct Table(
+    trans::Table<Colour, { Colour::BOUND }, { Cube4x4Metric::COUNT }>,
+    trans::Table<Sides, { Sides::BOUND }, SIDE_TURN_COUNT>,
+    [trans::Table<Pair, { Pair::BOUND }, FACE_TURN_COUNT>; 3],
+);
+
+impl Table {
+    pub fn new() -> Self {
+        let moves = Cube4x4Metric::CENTRE_MOVES;
+        let pair = |positions: &[u8; 8]| {
+            trans::Table::new(
+                &FACE_TURNS.map(|ix| restrict(&moves[ix], positions)),
+                Pair::all(),
+                Pair::permute,
+            )
+        };
+
+        Self(
+            trans::Table::new(&moves, Colour::all(), Colour::permute),
+            trans::Table::new(
+                &SIDE_TURNS.map(|ix| restrict(&moves[ix], &SIDES)),
+                Sides::all(),
+                Sides::permute,
+            ),
+            [pair(&UD), pair(&LR), pair(&FB)],
+        )
+    }
+
+    fn faces(&self, Faces { ud, lr, fb }: Faces, ix: usize) -> Faces {
+        let [ud_table, lr_table, fb_table] = &self.2;
+        Faces {
+            ud: ud_table.lookup(ud, ix),
+            lr: lr_table.lookup(lr, ix),
+            fb: fb_table.lookup(fb, ix),
+        }
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct PruningTable(
+    pruning::Table<Colour, { Colour::BOUND }>,
+    pruning::Table<Sides, { Sides::BOUND }>,
+    pruning::Table<Faces, { Faces::BOUND }>,
+);
+
+impl PruningTable {
+    pub fn new(table: &Table) -> Self {
+        // The centres of one colour are on U or D when all of the marked positions are.
+        let up_down = Colour::all().filter(|colour| {
+            let marked = colour.array::<1>().elements();
+            UD.iter()
+                .filter(|&&position| marked[position as usize].0 >= 20)
+                .count()
+                == 4
+        });
+        let sides = Sides::from(&Cube4x4::default());
+
+        Self(
+            pruning::Table::from_goals(
+                up_down,
+                &util::count::<{ Cube4x4Metric::COUNT }>(),
+                |colour: Colour, &ix: &usize| table.0.lookup(colour, ix),
+            ),
+            pruning::Table::from_goals(
+                std::iter::once(sides),
+                &util::count::<SIDE_TURN_COUNT>(),
+                |sides: Sides, &ix: &usize| table.1.lookup(sides, ix),
+            ),
+            pruning::Table::new(
+                &util::count::<FACE_TURN_COUNT>(),
+                |faces: Faces, &ix: &usize| table.faces(faces, ix),
+            ),
+        )
+    }
+}
+
+pub fn generate_tables() -> Tables {
+    let table = Table::new();
+    let pruning_table = PruningTable::new(&table);
+
+    (table, pruning_table, kociemba::generate_tables())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Reason;
+
+    #[test]
+    pub fn parity() {
+        let tables = generate_tables();
+
+        // A lone flipped edge, fixed by the OLL parity alone
+        let oll = Cube4x4::from_iter(Cube4x4Metric::parse(OLL_PARITY).unwrap());
+        let (corners, edges) = oll.to_3x3().unwrap();
+        assert_eq!(edges.iter().filter(|&&(_, flip)| flip == 1).count(), 1);
+        assert_eq!(corners.is_even(), Edges::new(edges).is_even());
+        assert_eq!(
+            oll.reduce(&tables),
+            Some(Cube4x4Metric::parse(OLL_PARITY).unwrap())
+        );
+
+        // Two edges swapped against the corners, fixed by the PLL parity alone
+        let pll = Cube4x4::from_iter(Cube4x4Metric::parse(PLL_PARITY).unwrap());
+        let (corners, edges) = pll.to_3x3().unwrap();
+        assert!(corners.is_even());
+        assert!(!Edges::new(edges).is_even());
+        assert_eq!(
+            pll.reduce(&tables),
+            Some(Cube4x4Metric::parse(PLL_PARITY).unwrap())
+        );
+
+        for cube in [oll, pll] {
+            assert!(cube.apply_seq(cube.solve(&tables).unwrap()).is_solved());
+        }
+    }
+
+    #[test]
+    pub fn reduction() {
+        let tables = generate_tables();
+
+        // Opposite centres can always be brought together by the last stage
+        let PruningTable(_, _, faces) = &tables.1;
+        let reachable = (0..Faces::BOUND)
+            .filter(|&ix| faces.lookup(Faces::try_from(ix).unwrap()) > 0)
+            .count();
+        assert_eq!(reachable + 1, Faces::BOUND);
+
+        assert_eq!(Cube4x4::default().solve(&tables), Some(vec![]));
+
+        // Unpaired edges with the centres and corners solved
+        let cube = Cube4x4::from_iter(Cube4x4Metric::parse("2R U R' U' 2R'").unwrap());
+        assert!(cube.centres_solved());
+        assert!(cube.paired() < 12);
+        assert_eq!(cube.to_3x3(), None);
+        assert!(cube.apply_seq(cube.solve(&tables).unwrap()).is_solved());
+
+        for _ in 0..3 {
+            let cube = Cube4x4::random_state();
+            let reduced = cube.apply_seq(cube.reduce(&tables).unwrap());
+            let (corners, edges) = reduced.to_3x3().unwrap();
+            assert_eq!(corners.is_even(), Edges::new(edges).is_even());
+            assert_eq!(edges.iter().map(|&(_, flip)| flip).sum::<u8>() % 2, 0);
+        }
+    }
+
+    #[test]
+    pub fn scrambles() {
+        let tables = generate_tables();
+
+        let turns = Cube4x4Metric::parse("Rw U2 2F' r R 2D Fw' L2").unwrap();
+        assert_eq!(
+            Cube4x4Metric::format_seq(turns.iter().copied()),
+            "Rw U2 2F' Rw R 2D Fw' L2"
+        );
+        for (scramble, reason) in [
+            ("2Rw", Reason::BadSuffix),
+            ("R3", Reason::BadSuffix),
+            ("3R", Reason::UnknownFace),
+            ("M", Reason::UnknownFace),
+        ] {
+            assert_eq!(Cube4x4Metric::parse(scramble).unwrap_err().reason, reason);
+        }
+
+        let scramble = Cube4x4::random_moves();
+        assert_eq!(scramble.len(), SCRAMBLE_LENGTH);
+        assert!(scramble
+            .windows(2)
+            .all(|turns| turns[0].face != turns[1].face));
+        assert!(scramble.iter().all(|turn| turn.layers != Layers::Inner));
+        let formatted = Cube4x4Metric::format_seq(scramble.iter().copied());
+        assert_eq!(Cube4x4Metric::parse(&formatted).unwrap(), scramble);
+
+        let cube = Cube4x4::from_iter(scramble);
+        assert!(cube.apply_seq(cube.solve(&tables).unwrap()).is_solved());
+
+        // A scramble for a random state is solved by the inverse of the scramble
+        let scramble = Cube4x4::random_scramble(&tables).unwrap();
+        let cube = Cube4x4::from_iter(scramble.iter().copied());
+        assert!(cube
+            .apply_seq(Cube4x4Metric::invert_seq(&scramble))
+            .is_solved());
+    }
+}
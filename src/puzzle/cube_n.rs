@@ -0,0 +1,613 @@
+//! Cubes of any size N of at least 2. The corners move as those of the 3x3x3, and so do the middle
+//! edges and the fixed centres of odd sizes, which together are kept as a `Cube3x3`. The other
+//! pieces fall into orbits of 24 which only move among themselves: the wings at each distance
+//! from the corners, and the centres at each position within a face up to its rotation.
+//!
+//! The turns of the wings and centres are found from the positions of the pieces, with the cube
+//! centred on the origin, U towards +z, R towards +x and F towards -y.
+
+use crate::core::definitions as def;
+use crate::metric::cube4x4::{Centres, Wings};
+use crate::metric::htm::{Corners, Edges, Face, CORNER_FACES, EDGE_FACES};
+use crate::metric::{CubeN as CubeNMetric, Htm, Stm};
+use crate::notation::Slice;
+use crate::puzzle::Cube3x3;
+use std::iter::FromIterator;
+
+type Vector = [i16; 3];
+
+/// The outward normal of each face, in the order of `Face::ALL`.
+const NORMALS: [Vector; 6] = [
+    [0, 0, 1],
+    [1, 0, 0],
+    [0, -1, 0],
+    [-1, 0, 0],
+    [0, 0, -1],
+    [0, 1, 0],
+];
+
+/// The order of the faces in the facelets.
+const FACELETS: [Face; 6] = [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+const LETTERS: [char; 6] = ['U', 'R', 'F', 'L', 'D', 'B'];
+
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct CubeN<const N: usize> {
+    /// The corners, and for odd sizes the middle edges and the fixed centres. Even sizes have
+    /// neither, and leave the edges and centres of the frame solved.
+    pub frame: Cube3x3,
+    /// The orbits of wings, from the one next to the corners inwards, each numbered as for the
+    /// 4x4x4.
+    pub wings: Vec<Wings>,
+    /// The orbits of centres, numbered four to a face as for the 4x4x4, in the order of
+    /// `CubeN::centre_orbits`.
+    pub centres: Vec<Centres>,
+}
+
+impl<const N: usize> CubeN<N> {
+    /// The coordinate of the outer layers.
+    const EXTENT: i16 = N as i16 - 1;
+
+    /// The number of orbits of wings.
+    pub const fn wing_orbits() -> usize {
+        N.saturating_sub(2) / 2
+    }
+
+    /// The orbits of centres, each given by the position of one of its centres on a face, in
+    /// units of half a piece along the axes of the plane of the face given by `frame`. The
+    /// position is the one with the first coordinate positive and the second not negative.
+    pub fn centre_orbits() -> Vec<(i16, i16)> {
+        let coordinates = (0..N.saturating_sub(2) as i16).map(|ix| 2 * ix - (Self::EXTENT - 2));
+
+        coordinates
+            .clone()
+            .filter(|&a| a > 0)
+            .flat_map(|a| coordinates.clone().filter(|&b| b >= 0).map(move |b| (a, b)))
+            .collect()
+    }
+
+    /// The positions of the wings of the orbit `orbit`, in the order of `Wings`.
+    fn wing_positions(orbit: usize) -> [Vector; 24] {
+        let offset = Self::EXTENT - 2 * (orbit as i16 + 1);
+
+        let mut positions = [[0; 3]; 24];
+        for (slot, [first, second]) in EDGE_FACES.iter().enumerate() {
+            let (first, second) = (NORMALS[*first as usize], NORMALS[*second as usize]);
+            let along = cross(first, second);
+            for side in 0..2 {
+                let sign = 2 * side as i16 - 1;
+                positions[2 * slot + side] = add(
+                    scale(Self::EXTENT, add(first, second)),
+                    scale(sign * offset, along),
+                );
+            }
+        }
+
+        positions
+    }
+
+    /// The positions of the centres of the orbit with the centre `(a, b)`, in the order of
+    /// `Centres`. The centres of each face are numbered by their coordinates in increasing order.
+    fn centre_positions((a, b): (i16, i16)) -> [Vector; 24] {
+        let mut positions = [[0; 3]; 24];
+        for (face, &normal) in NORMALS.iter().enumerate() {
+            let (first, second) = frame(normal);
+            let mut rotations = [(a, b), (-b, a), (-a, -b), (b, -a)].map(|(a, b)| {
+                add(
+                    scale(Self::EXTENT, normal),
+                    add(scale(a, first), scale(b, second)),
+                )
+            });
+            rotations.sort_unstable();
+            positions[4 * face..4 * face + 4].copy_from_slice(&rotations);
+        }
+
+        positions
+    }
+
+    /// The movement of the pieces at `positions` by `turn`.
+    fn turn_orbit(positions: &[Vector; 24], turn: CubeNMetric) -> def::Array<24, 1> {
+        let normal = NORMALS[turn.face as usize];
+
+        let mut elements = [(0, 0); 24];
+        for (element, &position) in elements.iter_mut().zip(positions) {
+            let layer = (Self::EXTENT - dot(position, normal)) / 2 + 1;
+            let source = if turn.turns(layer as u8) {
+                (0..4 - turn.amount).fold(position, |position, _| rotate(normal, position))
+            } else {
+                position
+            };
+            element.0 = positions.iter().position(|&p| p == source).unwrap() as u8;
+        }
+
+        def::Array::new(elements)
+    }
+
+    /// Turn the outer layer at `face` of the frame.
+    fn turn_frame(frame: Cube3x3, htm: Htm) -> Cube3x3 {
+        if N % 2 == 1 {
+            frame.apply(htm)
+        } else {
+            Cube3x3 {
+                corners: frame.corners.permute(htm.to_corners()),
+                ..frame
+            }
+        }
+    }
+
+    /// Turn the layers of `turn`, where layers beyond the far side of the cube are ignored.
+    pub fn apply(&self, turn: CubeNMetric) -> Self {
+        let CubeNMetric { face, amount, .. } = turn;
+
+        let mut frame = self.frame;
+        if turn.turns(1) {
+            frame = Self::turn_frame(frame, Htm::new(face, amount));
+        }
+        if turn.turns(N as u8) {
+            frame = Self::turn_frame(frame, Htm::new(face.opposite(), 4 - amount));
+        }
+        if N % 2 == 1 && turn.turns(N as u8 / 2 + 1) {
+            // Slices turn in the direction of L, D and F
+            let (slice, amount) = match face {
+                Face::L => (Slice::M, amount),
+                Face::R => (Slice::M, 4 - amount),
+                Face::D => (Slice::E, amount),
+                Face::U => (Slice::E, 4 - amount),
+                Face::F => (Slice::S, amount),
+                Face::B => (Slice::S, 4 - amount),
+            };
+            frame = frame.apply_stm(Stm::from_slice(slice, amount));
+        }
+
+        let wings = self
+            .wings
+            .iter()
+            .enumerate()
+            .map(|(orbit, wings)| {
+                wings.permute(&Self::turn_orbit(&Self::wing_positions(orbit), turn))
+            })
+            .collect();
+        let centres = self
+            .centres
+            .iter()
+            .zip(Self::centre_orbits())
+            .map(|(centres, orbit)| {
+                centres.permute(&Self::turn_orbit(&Self::centre_positions(orbit), turn))
+            })
+            .collect();
+
+        Self {
+            frame,
+            wings,
+            centres,
+        }
+    }
+
+    pub fn apply_seq(&self, sequence: impl IntoIterator<Item = CubeNMetric>) -> Self {
+        sequence
+            .into_iter()
+            .fold(self.clone(), |cube, turn| cube.apply(turn))
+    }
+
+    /// A random position, with each orbit of wings and centres shuffled and the frame in a random
+    /// state of the 3x3x3, of which even sizes only have the corners.
+    pub fn random_state() -> Self {
+        let frame = if N % 2 == 1 {
+            Cube3x3::random_state()
+        } else {
+            Cube3x3::new(Corners::random(), Edges::IDENTITY)
+        };
+
+        Self {
+            frame,
+            wings: (0..Self::wing_orbits()).map(|_| Wings::random()).collect(),
+            centres: Self::centre_orbits()
+                .iter()
+                .map(|_| Centres::random())
+                .collect(),
+        }
+    }
+
+    /// Whether each face shows a single colour, in any orientation of the cube.
+    pub fn is_solved(&self) -> bool {
+        self.facelets()
+            .chunks(N * N)
+            .all(|face| face.iter().all(|&colour| colour == face[0]))
+    }
+
+    /// The cube of this size in the same state as the 3x3x3 `cube`, with each wing next to the
+    /// middle edge of its edge and the centres of each face together.
+    pub fn from_3x3(cube: &Cube3x3) -> Self {
+        let edges = cube.edges.elements();
+        let mut wings = [(0, 0); 24];
+        for (ix, wing) in wings.iter_mut().enumerate() {
+            let (edge, flip) = edges[ix / 2];
+            *wing = (2 * edge + (flip ^ (ix as u8 % 2)), 0);
+        }
+
+        let mut centres = [(0, 0); 24];
+        for (ix, centre) in centres.iter_mut().enumerate() {
+            let face = cube.centres.face(Face::ALL[ix / 4]);
+            *centre = (4 * face as u8 + ix as u8 % 4, 0);
+        }
+
+        let frame = if N % 2 == 1 {
+            *cube
+        } else {
+            Cube3x3::new(cube.corners, Edges::IDENTITY)
+        };
+
+        Self {
+            frame,
+            wings: vec![Wings::new(wings); Self::wing_orbits()],
+            centres: vec![Centres::new(centres); Self::centre_orbits().len()],
+        }
+    }
+
+    /// The 3x3x3 made of the corners and the edges and centres once reduced, if the wings of each
+    /// edge are together, next to the middle edge for odd sizes, and the centres of each face are
+    /// together. For even sizes the state may not be reachable on the 3x3x3, and if a single edge
+    /// is flipped there is none.
+    pub fn to_3x3(&self) -> Option<Cube3x3> {
+        let cube = if N % 2 == 1 {
+            self.frame
+        } else {
+            let colour = |face: Face| match self.centres.first() {
+                Some(centres) => Face::ALL[centres.elements()[4 * face as usize].0 as usize / 4],
+                None => face,
+            };
+            let rotation = Cube3x3::rotations()
+                .map(|cube| cube.centres)
+                .find(|rotation| {
+                    Face::ALL
+                        .iter()
+                        .all(|&face| rotation.face(face) == colour(face))
+                })?;
+
+            let edges = match self.wings.first() {
+                Some(wings) => {
+                    let wings = wings.elements();
+                    let mut edges = [(0, 0); 12];
+                    for (ix, edge) in edges.iter_mut().enumerate() {
+                        let wing = wings[2 * ix].0;
+                        *edge = (wing / 2, wing % 2);
+                    }
+                    Edges::create(edges).ok()?
+                }
+                None => Edges::IDENTITY,
+            };
+
+            Cube3x3 {
+                centres: rotation,
+                ..Cube3x3::new(self.frame.corners, edges)
+            }
+        };
+
+        let reduced = Self::from_3x3(&cube);
+        if reduced.wings == self.wings && colours(&reduced.centres) == colours(&self.centres) {
+            Some(cube)
+        } else {
+            None
+        }
+    }
+
+    /// The colour of each facelet, face by face in the order U, R, F, D, L, B. Each face is read
+    /// row by row from the top left, as seen from outside the cube with U above the sides, B
+    /// above U and F above D.
+    pub fn facelets(&self) -> Vec<Face> {
+        FACELETS
+            .iter()
+            .flat_map(|&face| {
+                (0..N).flat_map(move |row| (0..N).map(move |column| (face, row, column)))
+            })
+            .map(|(face, row, column)| self.colour(face, facelet(face, row, column, Self::EXTENT)))
+            .collect()
+    }
+
+    /// The colour of the facelet on `face` of the piece at `position`.
+    fn colour(&self, face: Face, position: Vector) -> Face {
+        let outer: Vec<Face> = Face::ALL
+            .iter()
+            .copied()
+            .filter(|&face| dot(position, NORMALS[face as usize]) == Self::EXTENT)
+            .collect();
+
+        match outer.len() {
+            3 => {
+                let slot = CORNER_FACES
+                    .iter()
+                    .position(|faces| faces.iter().all(|face| outer.contains(face)))
+                    .unwrap();
+                let facelet = CORNER_FACES[slot].iter().position(|&f| f == face).unwrap();
+                let (corner, twist) = self.frame.corners.elements()[slot];
+                CORNER_FACES[corner as usize][(facelet + 3 - twist as usize) % 3]
+            }
+            2 => {
+                let slot = EDGE_FACES
+                    .iter()
+                    .position(|faces| faces.iter().all(|face| outer.contains(face)))
+                    .unwrap();
+                let facelet = EDGE_FACES[slot].iter().position(|&f| f == face).unwrap();
+                let [first, second] = EDGE_FACES[slot];
+                let along = dot(
+                    position,
+                    cross(NORMALS[first as usize], NORMALS[second as usize]),
+                );
+
+                let (edge, flip) = if along == 0 {
+                    self.frame.edges.elements()[slot]
+                } else {
+                    let side = (along > 0) as usize;
+                    let orbit = ((Self::EXTENT - along.abs()) / 2 - 1) as usize;
+                    let wing = self.wings[orbit].elements()[2 * slot + side].0;
+                    (wing / 2, (wing % 2) ^ side as u8)
+                };
+                EDGE_FACES[edge as usize][(facelet + flip as usize) % 2]
+            }
+            _ => {
+                let (first, second) = frame(NORMALS[face as usize]);
+                let (mut a, mut b) = (dot(position, first), dot(position, second));
+                if (a, b) == (0, 0) {
+                    return self.frame.centres.face(face);
+                }
+                while !(a > 0 && b >= 0) {
+                    let (x, y) = (b, -a);
+                    a = x;
+                    b = y;
+                }
+
+                let orbit = Self::centre_orbits()
+                    .iter()
+                    .position(|&orbit| orbit == (a, b))
+                    .unwrap();
+                let ix = Self::centre_positions((a, b))
+                    .iter()
+                    .position(|&p| p == position)
+                    .unwrap();
+                Face::ALL[self.centres[orbit].elements()[ix].0 as usize / 4]
+            }
+        }
+    }
+}
+
+impl<const N: usize> Default for CubeN<N> {
+    fn default() -> Self {
+        Self {
+            frame: Cube3x3::default(),
+            wings: vec![Wings::IDENTITY; Self::wing_orbits()],
+            centres: vec![Centres::IDENTITY; Self::centre_orbits().len()],
+        }
+    }
+}
+
+impl<const N: usize> FromIterator<CubeNMetric> for CubeN<N> {
+    fn from_iter<T: IntoIterator<Item = CubeNMetric>>(iter: T) -> Self {
+        Self::default().apply_seq(iter)
+    }
+}
+
+impl<const N: usize> std::fmt::Display for CubeN<N> {
+    /// The facelets unfolded into a cross, with L, F, R and B side by side.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let facelets = self.facelets();
+        let row = |face: Face, row: usize| -> String {
+            let start = FACELETS.iter().position(|&f| f == face).unwrap() * N * N + row * N;
+            facelets[start..start + N]
+                .iter()
+                .map(|&colour| LETTERS[colour as usize])
+                .collect()
+        };
+        let indent = " ".repeat(N + 1);
+
+        for ix in 0..N {
+            writeln!(f, "{}{}", indent, row(Face::U, ix))?;
+        }
+        for ix in 0..N {
+            let sides: Vec<String> = [Face::L, Face::F, Face::R, Face::B]
+                .iter()
+                .map(|&face| row(face, ix))
+                .collect();
+            writeln!(f, "{}", sides.join(" "))?;
+        }
+        for ix in 0..N {
+            writeln!(f, "{}{}", indent, row(Face::D, ix))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The face to which each centre belongs, since those of a face are interchangeable.
+fn colours(centres: &[Centres]) -> Vec<[u8; 24]> {
+    centres
+        .iter()
+        .map(|centres| centres.elements().map(|(centre, _)| centre / 4))
+        .collect()
+}
+
+/// The position of the piece under the facelet at `row` and `column` of `face`.
+fn facelet(face: Face, row: usize, column: usize, extent: i16) -> Vector {
+    let (row, column) = (extent - 2 * row as i16, 2 * column as i16 - extent);
+
+    match face {
+        Face::U => [column, row, extent],
+        Face::R => [extent, column, row],
+        Face::F => [column, -extent, row],
+        Face::D => [column, -row, -extent],
+        Face::L => [-extent, -column, row],
+        Face::B => [-column, extent, row],
+    }
+}
+
+/// Two axes in the plane with the normal `normal`, such that the turn from the first to the
+/// second is anticlockwise as seen from outside.
+fn frame(normal: Vector) -> (Vector, Vector) {
+    let mut axes = (0..3).filter(|&axis| normal[axis] == 0).map(|axis| {
+        let mut vector = [0; 3];
+        vector[axis] = 1;
+        vector
+    });
+    let (first, second) = (axes.next().unwrap(), axes.next().unwrap());
+
+    if cross(first, second) == normal {
+        (first, second)
+    } else {
+        (second, first)
+    }
+}
+
+/// The quarter turn of `vector` clockwise, as seen from outside the face with the normal `normal`.
+fn rotate(normal: Vector, vector: Vector) -> Vector {
+    add(
+        scale(-1, cross(normal, vector)),
+        scale(dot(normal, vector), normal),
+    )
+}
+
+fn add([x, y, z]: Vector, [a, b, c]: Vector) -> Vector {
+    [x + a, y + b, z + c]
+}
+
+fn scale(k: i16, [x, y, z]: Vector) -> Vector {
+    [k * x, k * y, k * z]
+}
+
+fn dot([x, y, z]: Vector, [a, b, c]: Vector) -> i16 {
+    x * a + y * b + z * c
+}
+
+fn cross([x, y, z]: Vector, [a, b, c]: Vector) -> Vector {
+    [y * c - z * b, z * a - x * c, x * b - y * a]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Cube4x4 as Cube4x4Metric;
+    use crate::notation::Reason;
+    use crate::puzzle::Cube4x4;
+    use rand::Rng;
+
+    /// The facelets after `turns`, found by turning each sticker on its own.
+    fn stickers<const N: usize>(turns: &[CubeNMetric]) -> Vec<Face> {
+        let extent = N as i16 - 1;
+        let mut stickers: Vec<(Vector, Vector, Face)> = FACELETS
+            .iter()
+            .flat_map(|&face| {
+                (0..N).flat_map(move |row| (0..N).map(move |column| (face, row, column)))
+            })
+            .map(|(face, row, column)| {
+                (
+                    facelet(face, row, column, extent),
+                    NORMALS[face as usize],
+                    face,
+                )
+            })
+            .collect();
+
+        for turn in turns {
+            let normal = NORMALS[turn.face as usize];
+            for (position, facing, _) in stickers.iter_mut() {
+                let layer = (extent - dot(*position, normal)) / 2 + 1;
+                if turn.turns(layer as u8) {
+                    for _ in 0..turn.amount {
+                        *position = rotate(normal, *position);
+                        *facing = rotate(normal, *facing);
+                    }
+                }
+            }
+        }
+
+        FACELETS
+            .iter()
+            .flat_map(|&face| {
+                (0..N).flat_map(move |row| (0..N).map(move |column| (face, row, column)))
+            })
+            .map(|(face, row, column)| {
+                let position = facelet(face, row, column, extent);
+                let normal = NORMALS[face as usize];
+                stickers
+                    .iter()
+                    .find(|&&(p, n, _)| p == position && n == normal)
+                    .unwrap()
+                    .2
+            })
+            .collect()
+    }
+
+    fn random_turns(size: u8, count: usize) -> Vec<CubeNMetric> {
+        let mut rng = rand::thread_rng();
+        (0..count)
+            .map(|_| {
+                let first = rng.gen_range(1..=size);
+                let last = rng.gen_range(first..=size);
+                CubeNMetric::new(
+                    Face::ALL[rng.gen_range(0..6)],
+                    first,
+                    last,
+                    rng.gen_range(1..4),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn cube_n() {
+        let turns = CubeNMetric::parse("R 3R 3r 2-3R' r Lw2 U").unwrap();
+        assert_eq!(
+            CubeNMetric::format_seq(turns.into_iter()),
+            "R 3R 3Rw 2-3R' Rw Lw2 U"
+        );
+        for (input, reason) in [
+            ("0R", Reason::BadLayers),
+            ("3-2R", Reason::BadLayers),
+            ("rw", Reason::BadSuffix),
+        ] {
+            assert_eq!(input.parse::<CubeNMetric>().unwrap_err().reason, reason);
+        }
+
+        let turns = random_turns(4, 30);
+        assert_eq!(
+            CubeN::<4>::from_iter(turns.iter().copied()).facelets(),
+            stickers::<4>(&turns)
+        );
+        let turns = random_turns(5, 30);
+        assert_eq!(
+            CubeN::<5>::from_iter(turns.iter().copied()).facelets(),
+            stickers::<5>(&turns)
+        );
+        let turns = random_turns(7, 20);
+        let cube = CubeN::<7>::from_iter(turns.iter().copied());
+        assert_eq!(cube.facelets(), stickers::<7>(&turns));
+        assert!(cube.apply_seq(CubeNMetric::invert_seq(&turns)).is_solved());
+
+        // The outer layers and the inner ones of the 4x4x4 agree with its own model
+        let turns = Cube4x4Metric::parse("R 2U' Fw2 2L B' Dw").unwrap();
+        let cube = Cube4x4::from_iter(turns.iter().copied());
+        let other = CubeN::<4>::from_iter(CubeNMetric::parse("R 2U' Fw2 2L B' Dw").unwrap());
+        assert_eq!(
+            (other.frame.corners, other.wings[0], other.centres[0]),
+            (cube.corners, cube.wings, cube.centres)
+        );
+
+        // Face turns and slices of odd sizes turn the frame as they turn the 3x3x3
+        let scramble = "R U2 F' L D B2";
+        let cube = Cube3x3::from_iter(Htm::parse(scramble).unwrap());
+        let other = CubeN::<5>::from_iter(CubeNMetric::parse(scramble).unwrap());
+        assert_eq!(other.to_3x3(), Some(cube));
+        assert_eq!(CubeN::<5>::from_3x3(&cube).facelets(), other.facelets());
+        let other = CubeN::<4>::from_iter(CubeNMetric::parse(scramble).unwrap());
+        assert_eq!(other.to_3x3(), Some(cube));
+        let m = CubeN::<3>::from_iter(CubeNMetric::parse("2L").unwrap());
+        assert_eq!(m.frame, Cube3x3::from(Stm::from_slice(Slice::M, 1)));
+        assert_eq!(
+            CubeN::<4>::from_iter(CubeNMetric::parse("2R").unwrap()).to_3x3(),
+            None
+        );
+
+        assert_eq!(
+            CubeN::<2>::from_iter(CubeNMetric::parse("R").unwrap()).to_string(),
+            "   UF\n   UF\nLL FD RR UB\nLL FD RR UB\n   DB\n   DB\n"
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! The model shared by the 3x3x2 and 2x2x3 cuboids, which are both turned by quarter turns of U
+//! and D and half turns of the sides, as in the domino group. Each has the eight corners of the
+//! cube and some of its edges, and they differ only in which edges those are and in which
+//! positions count as solved, which each gives as its `Geometry`.
+
+use crate::core::definitions as def;
+use crate::metric::htm::domino::Corners;
+use crate::metric::Domino as DominoMetric;
+use std::iter::FromIterator;
+
+/// What sets a cuboid with `N` edges apart from the others.
+pub trait Geometry<const N: usize> {
+    /// The movement of the edges by each turn, in the order of `DominoMetric::ALL`.
+    const EDGE_MOVES: [def::Array<N, 1>; DominoMetric::COUNT];
+
+    /// The positions which count as solved, the first of them the default one.
+    fn goals() -> Vec<Cuboid<N>>;
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Cuboid<const N: usize> {
+    pub corners: Corners,
+    pub edges: def::Array<N, 1>,
+}
+
+impl<const N: usize> Cuboid<N>
+where
+    Self: Geometry<N>,
+{
+    pub fn apply(&self, turn: DominoMetric) -> Self {
+        Self {
+            corners: self
+                .corners
+                .permute(&DominoMetric::CORNER_MOVES[turn as usize]),
+            edges: self.edges.permute(&Self::EDGE_MOVES[turn as usize]),
+        }
+    }
+
+    pub fn apply_seq(&self, sequence: impl IntoIterator<Item = DominoMetric>) -> Self {
+        sequence
+            .into_iter()
+            .fold(*self, |puzzle, turn| puzzle.apply(turn))
+    }
+
+    /// Whether the cuboid is in any of its solved positions.
+    pub fn is_solved(&self) -> bool {
+        Self::goals().contains(self)
+    }
+
+    /// A uniformly random position. A quarter turn is odd on the corners, and on the edges of U
+    /// and D, while a half turn of a side is odd on the edges alone, so that any two
+    /// permutations can be reached.
+    pub fn random_state() -> Self {
+        Self {
+            corners: Corners::random(),
+            edges: def::Array::random(),
+        }
+    }
+}
+
+impl<const N: usize> FromIterator<DominoMetric> for Cuboid<N>
+where
+    Self: Geometry<N>,
+{
+    fn from_iter<T: IntoIterator<Item = DominoMetric>>(iter: T) -> Self {
+        Self::default().apply_seq(iter)
+    }
+}
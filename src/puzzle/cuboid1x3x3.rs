@@ -0,0 +1,269 @@
+//! The 1x3x3 cuboid, or floppy cube, with its four corners and four edges around a fixed centre,
+//! turned by half turns of the four sides. The edges never leave their sides, so unlike the
+//! other cuboids it has a single solved position.
+//!
+//! Of its 6144 arrangements of the corners and the flips of all the pieces only 192 can be
+//! reached, which a pruning table gives the exact distance of and the solver walks down.
+
+use crate::core::definitions as def;
+use crate::core::pruning;
+use crate::core::search::{walk, Depth, Search};
+use crate::core::transition as trans;
+use crate::metric::floppy::{Corners, Edges};
+use crate::metric::Floppy as FloppyMetric;
+use crate::util;
+use rand::seq::SliceRandom;
+use std::convert::{TryFrom, TryInto};
+use std::iter::FromIterator;
+
+type Permutation = def::PermutationCoord<4>;
+
+const MOVE_COUNT: usize = FloppyMetric::COUNT;
+
+pub type Tables = (Table, PruningTable);
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Cuboid1x3x3 {
+    pub corners: Corners,
+    pub edges: Edges,
+}
+
+impl Cuboid1x3x3 {
+    pub fn apply(&self, turn: FloppyMetric) -> Self {
+        Self {
+            corners: self
+                .corners
+                .permute(&FloppyMetric::CORNER_MOVES[turn as usize]),
+            edges: self.edges.permute(&FloppyMetric::EDGE_MOVES[turn as usize]),
+        }
+    }
+
+    pub fn apply_seq(&self, sequence: impl IntoIterator<Item = FloppyMetric>) -> Self {
+        sequence
+            .into_iter()
+            .fold(*self, |puzzle, turn| puzzle.apply(turn))
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// A uniformly random position other than the solved one, chosen from those the pruning table
+    /// has reached.
+    pub fn random_state(tables: &Tables) -> Self {
+        let PruningTable(pruning_table) = &tables.1;
+        let positions = (0..Position::BOUND)
+            .map(|ix| Position::try_from(ix).unwrap())
+            .filter(|&position| pruning_table.lookup(position) > 0)
+            .collect::<Vec<_>>();
+
+        positions.choose(&mut rand::thread_rng()).unwrap().puzzle()
+    }
+
+    /// An optimal solution, found by walking down the pruning table.
+    pub fn solve(&self, (table, pruning_table): &Tables) -> Vec<FloppyMetric> {
+        walk(Position::from(self), pruning_table, table)
+            .into_iter()
+            .map(|turn| FloppyMetric::ALL[turn])
+            .collect()
+    }
+
+    /// A scramble for a random unsolved state, which is the inverse of its optimal solution.
+    pub fn random_scramble(tables: &Tables) -> Vec<FloppyMetric> {
+        FloppyMetric::invert_seq(&Self::random_state(tables).solve(tables))
+    }
+}
+
+impl FromIterator<FloppyMetric> for Cuboid1x3x3 {
+    fn from_iter<T: IntoIterator<Item = FloppyMetric>>(iter: T) -> Self {
+        Self::default().apply_seq(iter)
+    }
+}
+
+/// The flips of four pieces, one bit for each, the first the most significant.
+fn flips(pieces: &def::Array<4, 2>) -> u8 {
+    pieces
+        .elements()
+        .iter()
+        .fold(0, |flips, &(_, flip)| flips * 2 + flip)
+}
+
+/// The position of the puzzle as the permutation of the corners, and the flips of the corners
+/// and of the edges.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Position {
+    corners: Permutation,
+    corner_flips: u8,
+    edge_flips: u8,
+}
+
+impl Position {
+    pub const BOUND: usize = Permutation::BOUND * 16 * 16;
+
+    fn all() -> impl Iterator<Item = Self> {
+        (0..Self::BOUND).map(|ix| Self::try_from(ix).unwrap())
+    }
+
+    fn puzzle(self) -> Cuboid1x3x3 {
+        let flip = |pieces: [(u8, u8); 4], flips: u8| {
+            let mut pieces = pieces;
+            for (ix, (_, flip)) in pieces.iter_mut().enumerate() {
+                *flip = flips >> (3 - ix) & 1;
+            }
+            def::Array::new(pieces)
+        };
+
+        Cuboid1x3x3 {
+            corners: flip(self.corners.array::<2>().elements(), self.corner_flips),
+            edges: flip(Edges::IDENTITY.elements(), self.edge_flips),
+        }
+    }
+}
+
+impl From<&Cuboid1x3x3> for Position {
+    fn from(puzzle: &Cuboid1x3x3) -> Self {
+        Self {
+            corners: puzzle.corners.p_coordinate(),
+            corner_flips: flips(&puzzle.corners),
+            edge_flips: flips(&puzzle.edges),
+        }
+    }
+}
+
+impl From<Position> for usize {
+    fn from(position: Position) -> Self {
+        (usize::from(position.corners) * 16 + position.corner_flips as usize) * 16
+            + position.edge_flips as usize
+    }
+}
+
+impl TryFrom<usize> for Position {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            corners: (value / 256).try_into()?,
+            corner_flips: (value / 16 % 16) as u8,
+            edge_flips: (value % 16) as u8,
+        })
+    }
+}
+
+impl Search for Position {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, PruningTable(table): &Self::HeuristicData) -> Depth {
+        table.lookup(self)
+    }
+
+    fn transition(self, Table(table): &Self::TransitionData) -> Self::Iter {
+        (0..MOVE_COUNT)
+            .map(|ix| (table.lookup(self, ix), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+pub struct Table(trans::Table<Position, { Position::BOUND }, MOVE_COUNT>);
+
+impl Table {
+    pub fn new() -> Self {
+        Self(trans::Table::new(
+            &FloppyMetric::ALL,
+            Position::all(),
+            |position, &turn| Position::from(&position.puzzle().apply(turn)),
+        ))
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The exact distance of each position from the solved one, which is zero for those which cannot
+/// be reached.
+pub struct PruningTable(pruning::Table<Position, { Position::BOUND }>);
+
+impl PruningTable {
+    pub fn new(Table(table): &Table) -> Self {
+        Self(pruning::Table::new(
+            &util::count::<MOVE_COUNT>(),
+            |position, &ix| table.lookup(position, ix),
+        ))
+    }
+}
+
+pub fn generate_tables() -> Tables {
+    let table = Table::new();
+    let pruning_table = PruningTable::new(&table);
+
+    (table, pruning_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Reason;
+
+    #[test]
+    pub fn reachable() {
+        let tables = generate_tables();
+
+        // Only 192 positions can be reached, the farthest of them eight turns away
+        let PruningTable(pruning_table) = &tables.1;
+        let depths = (0..Position::BOUND)
+            .map(|ix| pruning_table.lookup(Position::try_from(ix).unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(depths.iter().filter(|&&depth| depth > 0).count() + 1, 192);
+        assert_eq!(depths.iter().max(), Some(&8));
+
+        // A single edge cannot be flipped on its own, nor two corners swapped without flips
+        let flipped = Cuboid1x3x3 {
+            corners: Corners::IDENTITY,
+            edges: Edges::new([(0, 1), (1, 0), (2, 0), (3, 0)]),
+        };
+        assert_eq!(pruning_table.lookup(Position::from(&flipped)), 0);
+        let swapped = Cuboid1x3x3 {
+            corners: Corners::new([(1, 0), (0, 0), (2, 0), (3, 0)]),
+            edges: Edges::IDENTITY,
+        };
+        assert_eq!(pruning_table.lookup(Position::from(&swapped)), 0);
+
+        // Every move is its own inverse
+        for turn in FloppyMetric::ALL {
+            assert!(Cuboid1x3x3::from_iter(vec![turn, turn]).is_solved());
+        }
+    }
+
+    #[test]
+    pub fn solutions() {
+        let tables = generate_tables();
+
+        let scramble = FloppyMetric::parse("R2 F2 L2").unwrap();
+        assert_eq!(
+            FloppyMetric::format_seq(scramble.iter().copied()),
+            "R2 F2 L2"
+        );
+        let error = |str: &str| FloppyMetric::parse(str).unwrap_err().reason;
+        assert_eq!(error("R'"), Reason::BadSuffix);
+        assert_eq!(error("R"), Reason::BadSuffix);
+        assert_eq!(error("U2"), Reason::UnknownFace);
+
+        assert!(Cuboid1x3x3::default().solve(&tables).is_empty());
+        let puzzle = Cuboid1x3x3::from_iter(scramble.iter().copied());
+        let solution = puzzle.solve(&tables);
+        assert_eq!(solution.len(), 3);
+        assert!(puzzle.apply_seq(solution).is_solved());
+
+        let scramble = Cuboid1x3x3::random_scramble(&tables);
+        let puzzle = Cuboid1x3x3::from_iter(scramble.iter().copied());
+        assert!(!scramble.is_empty());
+        assert!(!puzzle.is_solved());
+        assert!(puzzle.apply_seq(puzzle.solve(&tables)).is_solved());
+    }
+}
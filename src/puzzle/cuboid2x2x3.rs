@@ -0,0 +1,254 @@
+//! The 2x2x3 cuboid, or tower, with its eight corners and the four pieces of its middle layer,
+//! turned by quarter turns of U and D and half turns of the sides as in the domino group. The
+//! middle layer is held still, as turning it is the same as turning U and D the other way. No
+//! piece is fixed, so each of the eight rotations which keep the shape of the tower, about the
+//! long axis and upside down, leaves it solved.
+//!
+//! Its 967,680 positions are few enough for a pruning table of the exact distance of each, which
+//! the solver walks down.
+
+use crate::core::definitions as def;
+use crate::core::pruning;
+use crate::core::search::{walk, Depth, Search};
+use crate::core::transition as trans;
+use crate::metric::htm::domino::{Corners, Slice};
+use crate::metric::Domino as DominoMetric;
+use crate::puzzle::cuboid::{Cuboid, Geometry};
+use crate::util;
+use std::convert::{TryFrom, TryInto};
+
+type CornerPermutation = def::PermutationCoord<8>;
+type MiddlePermutation = def::PermutationCoord<4>;
+
+const MOVE_COUNT: usize = DominoMetric::COUNT;
+
+/// The quarter turn of the whole tower about its long axis, as `y` on the cube.
+#[rustfmt::skip]
+const Y: (Corners, Slice) = (
+    Corners::new([(3, 0), (0, 0), (1, 0), (2, 0), (7, 0), (4, 0), (5, 0), (6, 0)]),
+    Slice::new([(3, 0), (0, 0), (1, 0), (2, 0)]),
+);
+
+/// The half turn of the whole tower which turns it upside down, as `z2` on the cube.
+#[rustfmt::skip]
+const Z2: (Corners, Slice) = (
+    Corners::new([(5, 0), (4, 0), (7, 0), (6, 0), (1, 0), (0, 0), (3, 0), (2, 0)]),
+    Slice::new([(1, 0), (0, 0), (3, 0), (2, 0)]),
+);
+
+pub type Tables = (Table, PruningTable);
+
+/// The tower, whose edges are the pieces of the middle layer in the order FR, FL, BL and BR.
+pub type Cuboid2x2x3 = Cuboid<4>;
+
+impl Geometry<4> for Cuboid2x2x3 {
+    const EDGE_MOVES: [Slice; DominoMetric::COUNT] = DominoMetric::SLICE_MOVES;
+
+    /// The eight solved positions, the right way up and then upside down, each turned about the
+    /// long axis by a quarter turn more than the one before.
+    fn goals() -> Vec<Self> {
+        let mut goals = vec![Self::default(); 8];
+        goals[4] = rotate(&goals[4], &Z2);
+        for ix in (1..8).filter(|ix| ix % 4 != 0) {
+            goals[ix] = rotate(&goals[ix - 1], &Y);
+        }
+
+        goals
+    }
+}
+
+impl Cuboid2x2x3 {
+    /// An optimal solution, found by walking down the pruning table.
+    pub fn solve(&self, (table, pruning_table): &Tables) -> Vec<DominoMetric> {
+        walk(Position::from(self), pruning_table, table)
+            .into_iter()
+            .map(|turn| DominoMetric::ALL[turn])
+            .collect()
+    }
+
+    /// A scramble for a random state, which is the inverse of its optimal solution.
+    pub fn random_scramble(tables: &Tables) -> Vec<DominoMetric> {
+        DominoMetric::invert_seq(&Self::random_state().solve(tables))
+    }
+}
+
+/// The same position seen after `rotation` of the whole tower.
+fn rotate(puzzle: &Cuboid2x2x3, (corners, edges): &(Corners, Slice)) -> Cuboid2x2x3 {
+    Cuboid2x2x3 {
+        corners: puzzle.corners.permute(corners),
+        edges: puzzle.edges.permute(edges),
+    }
+}
+
+/// The position of the tower as the permutations of the corners and of the middles.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Position {
+    corners: CornerPermutation,
+    middles: MiddlePermutation,
+}
+
+impl Position {
+    pub const BOUND: usize = CornerPermutation::BOUND * MiddlePermutation::BOUND;
+}
+
+impl From<&Cuboid2x2x3> for Position {
+    fn from(puzzle: &Cuboid2x2x3) -> Self {
+        Self {
+            corners: puzzle.corners.p_coordinate(),
+            middles: puzzle.edges.p_coordinate(),
+        }
+    }
+}
+
+impl From<Position> for usize {
+    fn from(Position { corners, middles }: Position) -> Self {
+        usize::from(corners) * MiddlePermutation::BOUND + usize::from(middles)
+    }
+}
+
+impl TryFrom<usize> for Position {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            corners: (value / MiddlePermutation::BOUND).try_into()?,
+            middles: (value % MiddlePermutation::BOUND).try_into()?,
+        })
+    }
+}
+
+impl Search for Position {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, PruningTable(table): &Self::HeuristicData) -> Depth {
+        table.lookup(self)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        (0..MOVE_COUNT)
+            .map(|ix| (table.lookup(self, ix), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+pub struct Table(
+    trans::Table<CornerPermutation, { CornerPermutation::BOUND }, MOVE_COUNT>,
+    trans::Table<MiddlePermutation, { MiddlePermutation::BOUND }, MOVE_COUNT>,
+);
+
+impl Table {
+    pub fn new() -> Self {
+        Self(
+            trans::Table::new(
+                &DominoMetric::CORNER_MOVES,
+                CornerPermutation::all(),
+                CornerPermutation::permute,
+            ),
+            trans::Table::new(
+                &DominoMetric::SLICE_MOVES,
+                MiddlePermutation::all(),
+                MiddlePermutation::permute,
+            ),
+        )
+    }
+
+    fn lookup(&self, position: Position, ix: usize) -> Position {
+        let Table(corners, middles) = self;
+
+        Position {
+            corners: corners.lookup(position.corners, ix),
+            middles: middles.lookup(position.middles, ix),
+        }
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The exact distance of each position from the nearest solved one.
+pub struct PruningTable(pruning::Table<Position, { Position::BOUND }>);
+
+impl PruningTable {
+    pub fn new(table: &Table) -> Self {
+        let goals = Cuboid2x2x3::goals();
+
+        Self(pruning::Table::from_goals(
+            goals.iter().map(Position::from),
+            &util::count::<MOVE_COUNT>(),
+            |position, &ix| table.lookup(position, ix),
+        ))
+    }
+}
+
+pub fn generate_tables() -> Tables {
+    let table = Table::new();
+    let pruning_table = PruningTable::new(&table);
+
+    (table, pruning_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::iter::FromIterator;
+
+    #[test]
+    pub fn goals() {
+        let tables = generate_tables();
+
+        // Each of the eight rotations is solved, and only those are
+        let goals = Cuboid2x2x3::goals();
+        assert!((1..8).all(|ix| !goals[..ix].contains(&goals[ix])));
+        assert!(rotate(&rotate(&Cuboid2x2x3::default(), &Z2), &Y).is_solved());
+        let PruningTable(pruning_table) = &tables.1;
+        let depths = (0..Position::BOUND)
+            .map(|ix| pruning_table.lookup(Position::try_from(ix).unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            depths.iter().filter(|&&depth| depth > 0).count() + 8,
+            Position::BOUND
+        );
+
+        // Turning both ends the same way leaves the middle layer behind
+        let puzzle = Cuboid2x2x3::from_iter(DominoMetric::parse("U D'").unwrap());
+        assert!(!puzzle.is_solved());
+        assert_eq!(puzzle.solve(&tables).len(), 2);
+
+        // Upside down, a half turn of a side is still solved by itself
+        let puzzle = rotate(
+            &Cuboid2x2x3::from_iter(DominoMetric::parse("R2").unwrap()),
+            &Z2,
+        );
+        assert_eq!(puzzle.solve(&tables).len(), 1);
+    }
+
+    #[test]
+    pub fn reachable() {
+        let tables = generate_tables();
+
+        // Two pieces of the middle layer may be swapped on their own
+        let swapped = Cuboid2x2x3 {
+            corners: Corners::IDENTITY,
+            edges: Slice::new([(1, 0), (0, 0), (2, 0), (3, 0)]),
+        };
+        assert!(!swapped.is_solved());
+        assert!(swapped.apply_seq(swapped.solve(&tables)).is_solved());
+
+        let scramble = DominoMetric::parse("R2 U' F2 D2 R2 U").unwrap();
+        let puzzle = Cuboid2x2x3::from_iter(scramble.iter().copied());
+        let solution = puzzle.solve(&tables);
+        assert!(solution.len() <= 6);
+        assert!(puzzle.apply_seq(solution).is_solved());
+
+        let scramble = Cuboid2x2x3::random_scramble(&tables);
+        let puzzle = Cuboid2x2x3::from_iter(scramble.iter().copied());
+        assert!(puzzle.apply_seq(puzzle.solve(&tables)).is_solved());
+    }
+}
@@ -0,0 +1,290 @@
+//! The 3x3x2 cuboid, which is the cube without its E slice: the eight corners and the eight edges
+//! of U and D, turned by quarter turns of U and D and half turns of the sides, as in the domino
+//! group. Its two centres fix no more than the axis of U and D, so a position turned by `U D'`
+//! is just as solved, and the search has all four rotations of the solved position as goals.
+//!
+//! The search is an IDA* over the permutations of the corners and of the edges, guided by the
+//! distance to solving the corners while bringing the edges of D into D, and the other way round.
+
+use crate::core::definitions as def;
+use crate::core::pruning;
+use crate::core::search::{ida_iter, Depth, Search};
+use crate::core::transition as trans;
+use crate::metric::htm::domino::Edges;
+use crate::metric::Domino as DominoMetric;
+use crate::puzzle::cuboid::{Cuboid, Geometry};
+use crate::util;
+use std::convert::{TryFrom, TryInto};
+
+type Permutation = def::PermutationCoord<8>;
+/// Which of the pieces lie in D.
+type Layers = def::CombinationCoord<8, 4>;
+
+const MOVE_COUNT: usize = DominoMetric::COUNT;
+
+pub type Tables = (Table, PruningTable);
+
+pub type Cuboid3x3x2 = Cuboid<8>;
+
+impl Geometry<8> for Cuboid3x3x2 {
+    const EDGE_MOVES: [Edges; DominoMetric::COUNT] = DominoMetric::EDGE_MOVES;
+
+    /// The four solved positions, each turned from the one before by `U D'`.
+    fn goals() -> Vec<Self> {
+        let mut goals = vec![Self::default()];
+        for ix in 1..4 {
+            let goal =
+                goals[ix - 1].apply_seq([DominoMetric::U1, DominoMetric::D3].iter().copied());
+            goals.push(goal);
+        }
+
+        goals
+    }
+}
+
+impl Cuboid3x3x2 {
+    /// An optimal solution.
+    pub fn solve(&self, (table, pruning_table): &Tables) -> Vec<DominoMetric> {
+        let goals = Self::goals().iter().map(Position::from).collect::<Vec<_>>();
+
+        ida_iter(Position::from(self), &goals, pruning_table, table, None)
+            .next()
+            .unwrap()
+            .into_iter()
+            .map(|(_, ix)| DominoMetric::ALL[ix])
+            .collect()
+    }
+
+    /// A scramble for a random state, which is the inverse of its optimal solution.
+    pub fn random_scramble(tables: &Tables) -> Vec<DominoMetric> {
+        DominoMetric::invert_seq(&Self::random_state().solve(tables))
+    }
+}
+
+/// The permutation of the corners or of the edges together with which of the others lie in D,
+/// which a pruning table gives the distance to solving.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Part {
+    permutation: Permutation,
+    layers: Layers,
+}
+
+impl Part {
+    pub const BOUND: usize = Permutation::BOUND * Layers::BOUND;
+}
+
+impl From<Part> for usize {
+    fn from(
+        Part {
+            permutation,
+            layers,
+        }: Part,
+    ) -> Self {
+        usize::from(permutation) * Layers::BOUND + usize::from(layers)
+    }
+}
+
+impl TryFrom<usize> for Part {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            permutation: (value / Layers::BOUND).try_into()?,
+            layers: (value % Layers::BOUND).try_into()?,
+        })
+    }
+}
+
+/// The position of the cuboid as the permutations of the corners and of the edges, and which of
+/// each lie in D.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Position {
+    corners: Permutation,
+    edges: Permutation,
+    corner_layers: Layers,
+    edge_layers: Layers,
+}
+
+impl From<&Cuboid3x3x2> for Position {
+    fn from(puzzle: &Cuboid3x3x2) -> Self {
+        Self {
+            corners: puzzle.corners.p_coordinate(),
+            edges: puzzle.edges.p_coordinate(),
+            corner_layers: puzzle.corners.c_coordinate(),
+            edge_layers: puzzle.edges.c_coordinate(),
+        }
+    }
+}
+
+impl Search for Position {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        let corners = table.corners.lookup(Part {
+            permutation: self.corners,
+            layers: self.edge_layers,
+        });
+        let edges = table.edges.lookup(Part {
+            permutation: self.edges,
+            layers: self.corner_layers,
+        });
+
+        corners.max(edges)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        (0..MOVE_COUNT)
+            .map(|ix| {
+                let position = Self {
+                    corners: table.corners.lookup(self.corners, ix),
+                    edges: table.edges.lookup(self.edges, ix),
+                    corner_layers: table.corner_layers.lookup(self.corner_layers, ix),
+                    edge_layers: table.edge_layers.lookup(self.edge_layers, ix),
+                };
+                (position, ix)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+pub struct Table {
+    corners: trans::Table<Permutation, { Permutation::BOUND }, MOVE_COUNT>,
+    edges: trans::Table<Permutation, { Permutation::BOUND }, MOVE_COUNT>,
+    corner_layers: trans::Table<Layers, { Layers::BOUND }, MOVE_COUNT>,
+    edge_layers: trans::Table<Layers, { Layers::BOUND }, MOVE_COUNT>,
+}
+
+impl Table {
+    pub fn new() -> Self {
+        let corners = &DominoMetric::CORNER_MOVES;
+        let edges = &DominoMetric::EDGE_MOVES;
+
+        Self {
+            corners: trans::Table::new(corners, Permutation::all(), Permutation::permute),
+            edges: trans::Table::new(edges, Permutation::all(), Permutation::permute),
+            corner_layers: trans::Table::new(corners, Layers::all(), Layers::permute),
+            edge_layers: trans::Table::new(edges, Layers::all(), Layers::permute),
+        }
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct PruningTable {
+    corners: pruning::Table<Part, { Part::BOUND }>,
+    edges: pruning::Table<Part, { Part::BOUND }>,
+}
+
+impl PruningTable {
+    pub fn new(table: &Table) -> Self {
+        let generators = util::count::<MOVE_COUNT>();
+        let goals = Cuboid3x3x2::goals();
+
+        Self {
+            corners: pruning::Table::from_goals(
+                goals.iter().map(|goal| Part {
+                    permutation: goal.corners.p_coordinate(),
+                    layers: goal.edges.c_coordinate(),
+                }),
+                &generators,
+                |part, &ix| Part {
+                    permutation: table.corners.lookup(part.permutation, ix),
+                    layers: table.edge_layers.lookup(part.layers, ix),
+                },
+            ),
+            edges: pruning::Table::from_goals(
+                goals.iter().map(|goal| Part {
+                    permutation: goal.edges.p_coordinate(),
+                    layers: goal.corners.c_coordinate(),
+                }),
+                &generators,
+                |part, &ix| Part {
+                    permutation: table.edges.lookup(part.permutation, ix),
+                    layers: table.corner_layers.lookup(part.layers, ix),
+                },
+            ),
+        }
+    }
+}
+
+pub fn generate_tables() -> Tables {
+    let table = Table::new();
+    let pruning_table = PruningTable::new(&table);
+
+    (table, pruning_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::htm::domino::Corners;
+    use std::iter::FromIterator;
+
+    #[test]
+    pub fn goals() {
+        let tables = generate_tables();
+
+        // U and D may be turned against each other in any of four ways
+        let goals = Cuboid3x3x2::goals();
+        assert_eq!(goals.len(), 4);
+        assert!((1..4).all(|ix| !goals[..ix].contains(&goals[ix])));
+        for goal in goals {
+            assert!(goal.is_solved());
+            assert!(goal.solve(&tables).is_empty());
+        }
+
+        let turned = Cuboid3x3x2::from_iter(DominoMetric::parse("U D'").unwrap());
+        assert!(turned.is_solved());
+        let turned = Cuboid3x3x2::from_iter(DominoMetric::parse("U").unwrap());
+        assert!(!turned.is_solved());
+        assert_eq!(turned.solve(&tables).len(), 1);
+    }
+
+    #[test]
+    pub fn reachable() {
+        let tables = generate_tables();
+
+        // Every permutation of the corners can be reached with every split of the edges
+        let reachable = (0..Part::BOUND)
+            .map(|ix| tables.1.corners.lookup(Part::try_from(ix).unwrap()))
+            .filter(|&depth| depth > 0)
+            .count();
+        assert_eq!(reachable + 4, Part::BOUND);
+
+        // Unlike on the cube, two corners may be swapped on their own. Its solution of sixteen
+        // turns is too long to search for here, so only the bound is checked
+        let swapped = Cuboid3x3x2 {
+            corners: Corners::new([
+                (1, 0),
+                (0, 0),
+                (2, 0),
+                (3, 0),
+                (4, 0),
+                (5, 0),
+                (6, 0),
+                (7, 0),
+            ]),
+            edges: Edges::IDENTITY,
+        };
+        let depth = Position::from(&swapped).heuristic(&tables.1);
+        assert!(depth > 0 && depth <= 16);
+
+        let scramble = DominoMetric::parse("R2 U F2 D' L2 U2 B2").unwrap();
+        let puzzle = Cuboid3x3x2::from_iter(scramble.iter().copied());
+        let solution = puzzle.solve(&tables);
+        assert!(solution.len() <= 7);
+        assert!(puzzle.apply_seq(solution).is_solved());
+
+        let scramble = Cuboid3x3x2::random_scramble(&tables);
+        let puzzle = Cuboid3x3x2::from_iter(scramble.iter().copied());
+        assert!(puzzle.apply_seq(puzzle.solve(&tables)).is_solved());
+    }
+}
@@ -2,15 +2,15 @@ use crate::core::pruning;
 use crate::core::search::Search;
 use crate::core::transition as trans;
 use crate::metric::htm::domino::{CornerPermutation, EdgePermutation, SlicePermutation};
-use crate::metric::Domino as Metric;
+use crate::metric::Domino as DominoMetric;
 use crate::puzzle::Cube3x3;
 use std::cmp::max;
 use std::convert::TryFrom;
 
 pub type TransitionTable = (
-    trans::Table<CornerPermutation, { CornerPermutation::BOUND }, { Metric::COUNT }>,
-    trans::Table<EdgePermutation, { EdgePermutation::BOUND }, { Metric::COUNT }>,
-    trans::Table<SlicePermutation, { SlicePermutation::BOUND }, { Metric::COUNT }>,
+    trans::Table<CornerPermutation, { CornerPermutation::BOUND }, { DominoMetric::COUNT }>,
+    trans::Table<EdgePermutation, { EdgePermutation::BOUND }, { DominoMetric::COUNT }>,
+    trans::Table<SlicePermutation, { SlicePermutation::BOUND }, { DominoMetric::COUNT }>,
 );
 
 pub type PruningTable = (
@@ -19,7 +19,7 @@ pub type PruningTable = (
     pruning::Table<SlicePermutation, { SlicePermutation::BOUND }>,
 );
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
 pub struct Domino {
     corners: CornerPermutation,
     edges: EdgePermutation,
@@ -41,31 +41,34 @@ impl Domino {
 
     pub fn generate_tables() -> (TransitionTable, PruningTable) {
         let corners = trans::Table::new(
-            &Metric::CORNER_MOVES,
+            &DominoMetric::CORNER_MOVES,
             CornerPermutation::all(),
-            |coord, gen| coord.permute(&gen.0),
+            |coord, gen| coord.permute(gen),
         );
 
-        let corner_pruning =
-            pruning::Table::new(&Metric::GENERATORS, |coord, ix| corners.lookup(coord, *ix));
+        let corner_pruning = pruning::Table::new(&DominoMetric::GENERATORS, |coord, ix| {
+            corners.lookup(coord, *ix)
+        });
 
         let edges = trans::Table::new(
-            &Metric::EDGE_MOVES,
+            &DominoMetric::EDGE_MOVES,
             CornerPermutation::all(),
-            |coord, gen| coord.permute(&gen.0),
+            |coord, gen| coord.permute(gen),
         );
 
-        let edge_pruning =
-            pruning::Table::new(&Metric::GENERATORS, |coord, ix| edges.lookup(coord, *ix));
+        let edge_pruning = pruning::Table::new(&DominoMetric::GENERATORS, |coord, ix| {
+            edges.lookup(coord, *ix)
+        });
 
         let slice = trans::Table::new(
-            &Metric::SLICE_MOVES,
+            &DominoMetric::SLICE_MOVES,
             SlicePermutation::all(),
-            |coord, gen| coord.permute(&gen),
+            |coord, gen| coord.permute(gen),
         );
 
-        let slice_pruning =
-            pruning::Table::new(&Metric::GENERATORS, |coord, ix| slice.lookup(coord, *ix));
+        let slice_pruning = pruning::Table::new(&DominoMetric::GENERATORS, |coord, ix| {
+            slice.lookup(coord, *ix)
+        });
 
         (
             (corners, edges, slice),
@@ -123,7 +126,7 @@ impl Search for Domino {
 
         let (c_table, e_table, s_table) = tables;
 
-        (0..Metric::COUNT)
+        (0..DominoMetric::COUNT)
             .map(|ix| {
                 let c = c_table.lookup(corners, ix);
                 let e = e_table.lookup(edges, ix);
@@ -0,0 +1,301 @@
+//! The 3x3x3 as a string of 54 facelets, face by face in the order U, R, F, D, L, B, each read
+//! row by row from the top left as seen from outside the cube, with U above the sides, B above
+//! U and F above D. Each facelet is a character naming its colour in a `ColourScheme`.
+//!
+//! The facelets are read relative to the centres, so that a cube held any way up gives the same
+//! state with its centres in place, ready for `Cube3x3::kociemba`.
+
+use crate::metric::htm::{Corners, Edges, Face, CORNER_FACES, EDGE_FACES};
+use crate::puzzle::Cube3x3;
+
+/// The order of the faces in the string.
+const FACES: [Face; 6] = [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+/// The facelets of each corner in the order of `Corners`, with those of each in the order of its
+/// faces in `CORNER_FACES`.
+#[rustfmt::skip]
+const CORNER_FACELETS: [[usize; 3]; 8] = [
+    [8, 9, 20], [6, 18, 38], [0, 36, 47], [2, 45, 11],
+    [29, 26, 15], [27, 44, 24], [33, 53, 42], [35, 17, 51],
+];
+
+/// The facelets of each edge in the order of `Edges`, with those of each in the order of its
+/// faces in `EDGE_FACES`.
+#[rustfmt::skip]
+const EDGE_FACELETS: [[usize; 2]; 12] = [
+    [5, 10], [7, 19], [3, 37], [1, 46], [32, 16], [28, 25],
+    [30, 43], [34, 52], [23, 12], [21, 41], [50, 39], [48, 14],
+];
+
+/// The colour of each face of the solved cube.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct ColourScheme([char; 6]);
+
+impl ColourScheme {
+    /// The letters of the faces themselves, as in the usual facelet string.
+    pub const LETTERS: Self = Self(['U', 'R', 'F', 'L', 'D', 'B']);
+
+    /// White on U, red on R and green on F.
+    pub const WESTERN: Self = Self(['W', 'R', 'G', 'O', 'Y', 'B']);
+
+    /// The scheme with the colours of U, R, F, D, L and B in that order, if they all differ.
+    pub fn new(colours: [char; 6]) -> Option<Self> {
+        let mut scheme = [' '; 6];
+        for (ix, &colour) in colours.iter().enumerate() {
+            if colours[..ix].contains(&colour) {
+                return None;
+            }
+            scheme[FACES[ix] as usize] = colour;
+        }
+
+        Some(Self(scheme))
+    }
+
+    pub fn colour(&self, face: Face) -> char {
+        self.0[face as usize]
+    }
+}
+
+impl Default for ColourScheme {
+    fn default() -> Self {
+        Self::LETTERS
+    }
+}
+
+/// The reason some facelets are not those of a cube which can be solved.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FaceletError {
+    /// The number of facelets, which is not 54.
+    Length(usize),
+    /// The index of a facelet whose colour is not in the scheme.
+    UnknownColour(usize),
+    /// The centres are not the colours of the scheme in any orientation of the cube.
+    Centres,
+    /// The index in `Corners` of a position whose colours are those of no corner, e.g. two
+    /// opposite colours, or the colours of a corner in its mirror image.
+    NoSuchCorner(usize),
+    /// The index in `Edges` of a position whose colours are those of no edge.
+    NoSuchEdge(usize),
+    /// The index in `Corners` of a position holding a corner already found at another.
+    DuplicateCorner(usize),
+    /// The index in `Edges` of a position holding an edge already found at another.
+    DuplicateEdge(usize),
+    /// The twists of the corners do not sum to zero, as if a corner had been twisted in place.
+    Twist,
+    /// An odd number of edges are flipped.
+    Flip,
+    /// The permutations of the corners and of the edges differ in parity, as if two pieces had
+    /// been swapped.
+    Parity,
+}
+
+impl std::fmt::Display for FaceletError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FaceletError::Length(length) => write!(f, "{} facelets rather than 54", length),
+            FaceletError::UnknownColour(ix) => write!(f, "unknown colour at facelet {}", ix),
+            FaceletError::Centres => write!(f, "centres not in the colour scheme"),
+            FaceletError::NoSuchCorner(ix) => write!(f, "no such corner at corner {}", ix),
+            FaceletError::NoSuchEdge(ix) => write!(f, "no such edge at edge {}", ix),
+            FaceletError::DuplicateCorner(ix) => write!(f, "duplicate corner at corner {}", ix),
+            FaceletError::DuplicateEdge(ix) => write!(f, "duplicate edge at edge {}", ix),
+            FaceletError::Twist => write!(f, "twisted corner"),
+            FaceletError::Flip => write!(f, "flipped edge"),
+            FaceletError::Parity => write!(f, "swapped pieces"),
+        }
+    }
+}
+
+impl std::error::Error for FaceletError {}
+
+impl Cube3x3 {
+    /// The cube with `facelets` in the colours of `scheme`, with each colour taken to belong to
+    /// the face whose centre has it.
+    pub fn from_facelets(facelets: &str, scheme: &ColourScheme) -> Result<Self, FaceletError> {
+        let colours: Vec<char> = facelets.chars().collect();
+        if colours.len() != 54 {
+            return Err(FaceletError::Length(colours.len()));
+        }
+        if let Some(ix) = colours.iter().position(|colour| !scheme.0.contains(colour)) {
+            return Err(FaceletError::UnknownColour(ix));
+        }
+
+        let mut centres = [' '; 6];
+        for (ix, centre) in centres.iter_mut().enumerate() {
+            *centre = colours[ix * 9 + 4];
+        }
+        let oriented = Cube3x3::rotations().any(|cube| {
+            FACES
+                .iter()
+                .zip(centres.iter())
+                .all(|(&face, &centre)| scheme.colour(cube.centres.face(face)) == centre)
+        });
+        if !oriented {
+            return Err(FaceletError::Centres);
+        }
+        let faces: Vec<Face> = colours
+            .iter()
+            .map(|colour| FACES[centres.iter().position(|centre| centre == colour).unwrap()])
+            .collect();
+
+        let mut corners = [(0, 0); 8];
+        for (slot, facelets) in CORNER_FACELETS.iter().enumerate() {
+            let faces = facelets.map(|ix| faces[ix]);
+            let twist = faces
+                .iter()
+                .position(|&face| face == Face::U || face == Face::D)
+                .ok_or(FaceletError::NoSuchCorner(slot))?;
+            let corner = CORNER_FACES
+                .iter()
+                .position(|corner| (0..3).all(|ix| corner[ix] == faces[(ix + twist) % 3]))
+                .ok_or(FaceletError::NoSuchCorner(slot))?;
+            if corners[..slot].iter().any(|&(c, _)| c == corner as u8) {
+                return Err(FaceletError::DuplicateCorner(slot));
+            }
+            corners[slot] = (corner as u8, twist as u8);
+        }
+
+        let mut edges = [(0, 0); 12];
+        for (slot, facelets) in EDGE_FACELETS.iter().enumerate() {
+            let faces = facelets.map(|ix| faces[ix]);
+            let (edge, flip) = EDGE_FACES
+                .iter()
+                .enumerate()
+                .find_map(|(edge, &[first, second])| match faces {
+                    [f, s] if (f, s) == (first, second) => Some((edge, 0)),
+                    [f, s] if (f, s) == (second, first) => Some((edge, 1)),
+                    _ => None,
+                })
+                .ok_or(FaceletError::NoSuchEdge(slot))?;
+            if edges[..slot].iter().any(|&(e, _)| e == edge as u8) {
+                return Err(FaceletError::DuplicateEdge(slot));
+            }
+            edges[slot] = (edge as u8, flip);
+        }
+
+        let corners = Corners::new(corners);
+        let edges = Edges::new(edges);
+        if corners.elements().iter().map(|&(_, t)| t).sum::<u8>() % 3 != 0 {
+            return Err(FaceletError::Twist);
+        }
+        if edges.elements().iter().map(|&(_, f)| f).sum::<u8>() % 2 != 0 {
+            return Err(FaceletError::Flip);
+        }
+        if corners.is_even() != edges.is_even() {
+            return Err(FaceletError::Parity);
+        }
+
+        Ok(Self::new(corners, edges))
+    }
+
+    /// The facelets of the cube in the colours of `scheme`.
+    pub fn facelets(&self, scheme: &ColourScheme) -> String {
+        let mut faces = [Face::U; 54];
+        for (ix, &face) in FACES.iter().enumerate() {
+            faces[ix * 9 + 4] = self.centres.face(face);
+        }
+        for (&(corner, twist), facelets) in
+            self.corners.elements().iter().zip(CORNER_FACELETS.iter())
+        {
+            for (ix, &facelet) in facelets.iter().enumerate() {
+                faces[facelet] = CORNER_FACES[corner as usize][(ix + 3 - twist as usize) % 3];
+            }
+        }
+        for (&(edge, flip), facelets) in self.edges.elements().iter().zip(EDGE_FACELETS.iter()) {
+            for (ix, &facelet) in facelets.iter().enumerate() {
+                faces[facelet] = EDGE_FACES[edge as usize][(ix + flip as usize) % 2];
+            }
+        }
+
+        faces.iter().map(|&face| scheme.colour(face)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metric::Htm;
+    use crate::notation::Rotation;
+    use crate::puzzle::CubeN;
+    use rand::seq::SliceRandom;
+    use std::iter::FromIterator;
+
+    #[test]
+    pub fn facelets() {
+        let scheme = ColourScheme::default();
+        let solved = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+        assert_eq!(Cube3x3::default().facelets(&scheme), solved);
+        assert_eq!(
+            Cube3x3::from_facelets(solved, &scheme),
+            Ok(Cube3x3::default())
+        );
+
+        let cube = Cube3x3::from_iter(Htm::parse("U").unwrap());
+        assert_eq!(
+            cube.facelets(&scheme),
+            "UUUUUUUUUBBBRRRRRRRRRFFFFFFDDDDDDDDDFFFLLLLLLLLLBBBBBB"
+        );
+
+        // The facelets agree with those of the cube of any size, and are read back as they were
+        let letters: String = "URFLDB".chars().collect();
+        for _ in 0..5 {
+            let turns = (0..25).map(|_| *Htm::ALL.choose(&mut rand::thread_rng()).unwrap());
+            let cube = Cube3x3::from_iter(turns);
+            let facelets = cube.facelets(&scheme);
+            let expected: String = CubeN::<3>::from_3x3(&cube)
+                .facelets()
+                .iter()
+                .map(|&face| letters.chars().nth(face as usize).unwrap())
+                .collect();
+            assert_eq!(facelets, expected);
+            assert_eq!(Cube3x3::from_facelets(&facelets, &scheme), Ok(cube));
+
+            let western = cube.facelets(&ColourScheme::WESTERN);
+            assert_eq!(
+                Cube3x3::from_facelets(&western, &ColourScheme::WESTERN),
+                Ok(cube)
+            );
+        }
+
+        // A cube held another way up has its centres put back in place
+        let turned = Cube3x3::from_iter(Htm::parse("R U").unwrap()).rotate(Rotation::X);
+        let facelets = turned.facelets(&ColourScheme::WESTERN);
+        assert_eq!(
+            Cube3x3::from_facelets(&facelets, &ColourScheme::WESTERN),
+            Ok(Cube3x3::from_iter(Htm::parse("R B").unwrap()))
+        );
+        assert_eq!(
+            ColourScheme::new(['W', 'R', 'G', 'Y', 'O', 'B']),
+            Some(ColourScheme::WESTERN)
+        );
+        assert_eq!(ColourScheme::new(['W', 'R', 'G', 'Y', 'W', 'B']), None);
+
+        let edit = |changes: &[(usize, char)]| {
+            let mut colours: Vec<char> = solved.chars().collect();
+            for &(ix, colour) in changes {
+                colours[ix] = colour;
+            }
+            let facelets: String = colours.into_iter().collect();
+            Cube3x3::from_facelets(&facelets, &scheme).unwrap_err()
+        };
+        assert_eq!(
+            Cube3x3::from_facelets("UUU", &scheme),
+            Err(FaceletError::Length(3))
+        );
+        assert_eq!(edit(&[(10, 'X')]), FaceletError::UnknownColour(10));
+        assert_eq!(edit(&[(4, 'D'), (31, 'U')]), FaceletError::Centres);
+        assert_eq!(edit(&[(8, 'R'), (9, 'U')]), FaceletError::NoSuchCorner(0));
+        assert_eq!(edit(&[(8, 'D')]), FaceletError::NoSuchCorner(0));
+        assert_eq!(
+            edit(&[(6, 'U'), (18, 'R'), (38, 'F')]),
+            FaceletError::DuplicateCorner(1)
+        );
+        assert_eq!(edit(&[(5, 'R'), (10, 'U')]), FaceletError::Flip);
+        assert_eq!(edit(&[(7, 'U'), (19, 'R')]), FaceletError::DuplicateEdge(1));
+        assert_eq!(edit(&[(8, 'F'), (9, 'U'), (20, 'R')]), FaceletError::Twist);
+        assert_eq!(
+            edit(&[(5, 'F'), (10, 'U'), (7, 'R'), (19, 'U')]),
+            FaceletError::Parity
+        );
+    }
+}
@@ -0,0 +1,319 @@
+//! The Megaminx, with its twenty corners, thirty edges and twelve centres. Turns of the faces
+//! leave the centres where they are, while the moves of the scrambles of the WCA turn most of
+//! the puzzle and carry the centres with them, so that a position is solved when each face shows
+//! the colour of its centre.
+
+use crate::core::definitions as def;
+use crate::metric::megaminx::{Centres, Corners, Edges, Face, Pochmann};
+use crate::metric::Megaminx as MegaminxMetric;
+use rand::Rng;
+use std::iter::FromIterator;
+
+/// The faces of each corner, starting with the first facelet and going clockwise.
+#[rustfmt::skip]
+const CORNER_FACES: [[Face; 3]; 20] = {
+    use Face::*;
+    [
+        [U, R, F],
+        [U, BR, R],
+        [U, BL, BR],
+        [U, L, BL],
+        [U, F, L],
+        [DR, F, R],
+        [DBR, R, BR],
+        [B, BR, BL],
+        [DBL, BL, L],
+        [DL, L, F],
+        [F, DR, DL],
+        [R, DBR, DR],
+        [BR, B, DBR],
+        [BL, DBL, B],
+        [L, DL, DBL],
+        [D, DL, DR],
+        [D, DR, DBR],
+        [D, DBR, B],
+        [D, B, DBL],
+        [D, DBL, DL],
+    ]
+};
+
+/// The faces of each edge, starting with the first facelet.
+#[rustfmt::skip]
+const EDGE_FACES: [[Face; 2]; 30] = {
+    use Face::*;
+    [
+        [U, F],
+        [U, R],
+        [U, BR],
+        [U, BL],
+        [U, L],
+        [F, R],
+        [R, BR],
+        [BR, BL],
+        [BL, L],
+        [L, F],
+        [F, DR],
+        [R, DR],
+        [R, DBR],
+        [BR, DBR],
+        [BR, B],
+        [BL, B],
+        [BL, DBL],
+        [L, DBL],
+        [L, DL],
+        [F, DL],
+        [DL, DR],
+        [DR, DBR],
+        [DBR, B],
+        [B, DBL],
+        [DBL, DL],
+        [D, DR],
+        [D, DBR],
+        [D, B],
+        [D, DBL],
+        [D, DL],
+    ]
+};
+
+/// The number of lines of a scramble of the WCA, and of moves of R and D in each.
+const LINES: usize = 7;
+const LINE_LENGTH: usize = 10;
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Megaminx {
+    pub corners: Corners,
+    pub edges: Edges,
+    pub centres: Centres,
+}
+
+impl Megaminx {
+    pub fn apply(&self, turn: MegaminxMetric) -> Self {
+        Self {
+            corners: self.corners.permute(turn.to_corners()),
+            edges: self.edges.permute(turn.to_edges()),
+            centres: self.centres,
+        }
+    }
+
+    pub fn apply_seq(&self, sequence: impl IntoIterator<Item = MegaminxMetric>) -> Self {
+        sequence
+            .into_iter()
+            .fold(*self, |puzzle, turn| puzzle.apply(turn))
+    }
+
+    pub fn apply_pochmann(&self, turn: Pochmann) -> Self {
+        Self {
+            corners: self.corners.permute(&turn.to_corners()),
+            edges: self.edges.permute(&turn.to_edges()),
+            centres: self.centres.permute(&turn.to_centres()),
+        }
+    }
+
+    pub fn apply_pochmann_seq(&self, sequence: impl IntoIterator<Item = Pochmann>) -> Self {
+        sequence
+            .into_iter()
+            .fold(*self, |puzzle, turn| puzzle.apply_pochmann(turn))
+    }
+
+    /// A uniformly random position with the centres in place. Every turn is a five-cycle of
+    /// both corners and edges, so both permutations are even.
+    pub fn random_state() -> Self {
+        Self {
+            corners: Corners::random_even(),
+            edges: Edges::random_even(),
+            centres: Centres::IDENTITY,
+        }
+    }
+
+    /// Whether each face shows the colour of its centre, in any orientation of the puzzle.
+    pub fn is_solved(&self) -> bool {
+        let centres = self.centres.elements();
+        let colour = |face: Face| centres[face as usize].0;
+
+        let corners =
+            self.corners
+                .elements()
+                .iter()
+                .enumerate()
+                .all(|(position, &(corner, twist))| {
+                    (0..3).all(|facelet| {
+                        let face =
+                            CORNER_FACES[corner as usize][(facelet + 3 - twist as usize) % 3];
+                        face as u8 == colour(CORNER_FACES[position][facelet])
+                    })
+                });
+        let edges = self
+            .edges
+            .elements()
+            .iter()
+            .enumerate()
+            .all(|(position, &(edge, flip))| {
+                (0..2).all(|facelet| {
+                    let face = EDGE_FACES[edge as usize][(facelet + flip as usize) % 2];
+                    face as u8 == colour(EDGE_FACES[position][facelet])
+                })
+            });
+
+        corners && edges
+    }
+
+    /// A scramble as for the WCA, of seven lines of ten moves alternating between `R` and `D`,
+    /// each in a random direction, and each line ending with a turn of U in the direction of its
+    /// last move of D.
+    pub fn random_scramble() -> Vec<Pochmann> {
+        let mut rng = rand::thread_rng();
+        let mut scramble = Vec::with_capacity(LINES * (LINE_LENGTH + 1));
+
+        for _ in 0..LINES {
+            for ix in 0..LINE_LENGTH {
+                let plus = rng.gen_bool(0.5);
+                scramble.push(match (ix % 2, plus) {
+                    (0, true) => Pochmann::R2,
+                    (0, false) => Pochmann::R3,
+                    (_, true) => Pochmann::D2,
+                    (_, false) => Pochmann::D3,
+                });
+            }
+
+            match scramble.last() {
+                Some(Pochmann::D2) => scramble.push(Pochmann::U1),
+                _ => scramble.push(Pochmann::U4),
+            }
+        }
+
+        scramble
+    }
+}
+
+impl FromIterator<MegaminxMetric> for Megaminx {
+    fn from_iter<T: IntoIterator<Item = MegaminxMetric>>(iter: T) -> Self {
+        Self::default().apply_seq(iter)
+    }
+}
+
+impl FromIterator<Pochmann> for Megaminx {
+    fn from_iter<T: IntoIterator<Item = Pochmann>>(iter: T) -> Self {
+        Self::default().apply_pochmann_seq(iter)
+    }
+}
+
+/// The position of the Megaminx as coordinates. The orientations and permutations of the corners
+/// and of the edges are too many for `OrientationCoord` and `PermutationCoord`, so these are
+/// wide, and the coordinates identify the position but cannot index a table.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Coordinates {
+    pub corner_orientation: def::WideOrientationCoord<20, 3>,
+    pub corner_permutation: def::WidePermutationCoord<20>,
+    pub edge_orientation: def::WideOrientationCoord<30, 2>,
+    pub edge_permutation: def::WidePermutationCoord<30>,
+    pub centres: def::PermutationCoord<12>,
+}
+
+impl From<&Megaminx> for Coordinates {
+    fn from(puzzle: &Megaminx) -> Self {
+        Self {
+            corner_orientation: puzzle.corners.wide_o_coordinate(),
+            corner_permutation: puzzle.corners.wide_p_coordinate(),
+            edge_orientation: puzzle.edges.wide_o_coordinate(),
+            edge_permutation: puzzle.edges.wide_p_coordinate(),
+            centres: puzzle.centres.p_coordinate(),
+        }
+    }
+}
+
+/// The array of the pieces permuted as by `permutation` and oriented as by `orientation`.
+fn array<const N: usize, const M: u8>(
+    orientation: def::WideOrientationCoord<N, M>,
+    permutation: def::WidePermutationCoord<N>,
+) -> def::Array<N, M> {
+    let mut elements = permutation.array::<M>().elements();
+    for (element, (_, o)) in elements
+        .iter_mut()
+        .zip(orientation.array().elements().iter())
+    {
+        element.1 = *o;
+    }
+
+    def::Array::new(elements)
+}
+
+impl From<Coordinates> for Megaminx {
+    fn from(coordinates: Coordinates) -> Self {
+        Self {
+            corners: array(
+                coordinates.corner_orientation,
+                coordinates.corner_permutation,
+            ),
+            edges: array(coordinates.edge_orientation, coordinates.edge_permutation),
+            centres: coordinates.centres.array(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    pub fn megaminx() {
+        let turns = MegaminxMetric::parse("R U2' DBR F' BL2 D").unwrap();
+        let puzzle = Megaminx::from_iter(turns.iter().copied());
+        assert!(!puzzle.is_solved());
+        assert!(puzzle
+            .apply_seq(MegaminxMetric::invert_seq(&turns))
+            .is_solved());
+        assert_eq!(
+            MegaminxMetric::format_seq(turns.into_iter()),
+            "R U2' DBR F' BL2 D"
+        );
+
+        // Five fifths of a turn, and the same move of a scramble five times, make no change
+        for &turn in MegaminxMetric::ALL.iter().step_by(4) {
+            assert!(Megaminx::from_iter(vec![turn; 5]).is_solved());
+        }
+        for &turn in Pochmann::ALL.iter() {
+            let puzzle = Megaminx::from_iter(vec![turn; 5]);
+            assert_eq!(puzzle, Megaminx::default());
+            assert!(Megaminx::from_iter(vec![turn, turn.inverse()]).is_solved());
+        }
+
+        // The moves of the scrambles turn the whole puzzle but one face, so once that face is
+        // turned back the puzzle is solved though the centres have moved
+        let rotation = Megaminx::from_iter(vec![Pochmann::R2]).apply(MegaminxMetric::DBL3);
+        assert_ne!(rotation.centres, Centres::IDENTITY);
+        assert!(rotation.is_solved());
+
+        let scramble = Megaminx::random_scramble();
+        let text = Pochmann::format_seq(scramble.iter().copied());
+        assert_eq!(scramble.len(), 77);
+        assert_eq!(text.lines().count(), 7);
+        assert_eq!(Pochmann::parse(&text).unwrap(), scramble);
+        assert!(text
+            .lines()
+            .all(|line| line.ends_with("D++ U") || line.ends_with("D-- U'")));
+        assert!(!Megaminx::from_iter(scramble).is_solved());
+
+        let puzzle = Megaminx::random_state();
+        let coordinates = Coordinates::from(&puzzle);
+        assert_eq!(Megaminx::from(coordinates), puzzle);
+        assert_eq!(
+            u128::from(Coordinates::from(&Megaminx::default()).edge_permutation),
+            0
+        );
+        assert!(
+            def::WidePermutationCoord::<30>::try_from(def::WidePermutationCoord::<30>::BOUND)
+                .is_err()
+        );
+
+        // The orientations of the corners alone are beyond the width of `OrientationCoord`
+        let corners = Coordinates::from(&puzzle).corner_orientation;
+        assert_eq!(def::WideOrientationCoord::<20, 3>::BOUND, 3u128.pow(19));
+        assert!(u128::from(corners) < def::WideOrientationCoord::<20, 3>::BOUND);
+        assert_eq!(corners.array().wide_o_coordinate(), corners);
+        assert!(def::WideOrientationCoord::<30, 2>::try_from(
+            def::WideOrientationCoord::<30, 2>::BOUND
+        )
+        .is_err());
+    }
+}
@@ -1,8 +1,31 @@
+pub mod clock;
 pub mod cube2x2;
 pub mod cube3x3;
+pub mod cube4x4;
+pub mod cube_n;
+pub mod cuboid;
+pub mod cuboid1x3x3;
+pub mod cuboid2x2x3;
+pub mod cuboid3x3x2;
 pub mod domino;
+pub mod facelets;
+pub mod megaminx;
 pub mod positions;
+pub mod pyraminx;
+pub mod skewb;
+pub mod square1;
 
+pub use clock::Clock;
 pub use cube2x2::Cube2x2;
 pub use cube3x3::Cube3x3;
+pub use cube4x4::Cube4x4;
+pub use cube_n::CubeN;
+pub use cuboid1x3x3::Cuboid1x3x3;
+pub use cuboid2x2x3::Cuboid2x2x3;
+pub use cuboid3x3x2::Cuboid3x3x2;
 pub use domino::Domino;
+pub use facelets::{ColourScheme, FaceletError};
+pub use megaminx::Megaminx;
+pub use pyraminx::Pyraminx;
+pub use skewb::Skewb;
+pub use square1::Square1;
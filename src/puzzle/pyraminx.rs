@@ -0,0 +1,303 @@
+//! The Pyraminx, with its six edges, the four centres beneath the vertices and the four tips. The
+//! tips twist independently of everything else, so they are solved last with a turn each. The
+//! 933,120 positions of the rest are few enough for a table of the exact distance of each.
+
+use crate::core::definitions as def;
+use crate::core::pruning;
+use crate::core::search::{walk, Depth, Search};
+use crate::core::transition as trans;
+use crate::metric::pyraminx::{Edges, Twists};
+use crate::metric::Pyraminx as PyraminxMetric;
+use rand::Rng;
+use std::convert::{TryFrom, TryInto};
+use std::iter::FromIterator;
+
+type EdgeCoord = def::Coordinate<6, 2>;
+
+const EDGES: usize = def::OrientationCoord::<6, 2>::BOUND * def::PermutationCoord::<6>::BOUND;
+const CENTRES: usize = 81;
+const STATES: usize = EDGES * CENTRES;
+const MOVE_COUNT: usize = PyraminxMetric::LAYERS.len();
+
+pub type Tables = (Table, PruningTable);
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Pyraminx {
+    pub edges: Edges,
+    pub centres: Twists,
+    pub tips: Twists,
+}
+
+impl Pyraminx {
+    pub fn apply(&self, turn: PyraminxMetric) -> Self {
+        let twists = turn.twists();
+
+        Self {
+            edges: self
+                .edges
+                .permute(&PyraminxMetric::EDGE_MOVES[turn as usize]),
+            centres: if turn.is_tip() {
+                self.centres
+            } else {
+                self.centres.permute(&twists)
+            },
+            tips: self.tips.permute(&twists),
+        }
+    }
+
+    pub fn apply_seq(&self, sequence: impl IntoIterator<Item = PyraminxMetric>) -> Self {
+        sequence
+            .into_iter()
+            .fold(*self, |puzzle, turn| puzzle.apply(turn))
+    }
+
+    /// A uniformly random position, where the permutation of the edges is even.
+    pub fn random_state() -> Self {
+        let mut rng = rand::thread_rng();
+        let mut twists = || {
+            let mut twists = Twists::IDENTITY.elements();
+            for (_, twist) in twists.iter_mut() {
+                *twist = rng.gen_range(0..3);
+            }
+            Twists::new(twists)
+        };
+
+        Self {
+            edges: Edges::random_even(),
+            centres: twists(),
+            tips: twists(),
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// An optimal solution, found by walking down the pruning table for the layers and then
+    /// turning each tip which is still twisted.
+    pub fn solve(&self, (table, pruning_table): &Tables) -> Vec<PyraminxMetric> {
+        let mut solution: Vec<_> = walk(Reduced::from(self), pruning_table, table)
+            .into_iter()
+            .map(|turn| PyraminxMetric::LAYERS[turn])
+            .collect();
+
+        let tips = self.apply_seq(solution.iter().copied()).tips;
+        for (vertex, &(_, twist)) in tips.elements().iter().enumerate() {
+            if twist > 0 {
+                solution.push(PyraminxMetric::new(vertex as u8, 3 - twist, true));
+            }
+        }
+
+        solution
+    }
+
+    /// A scramble for a random state, which is the inverse of its optimal solution with the
+    /// tips turned last. As for the WCA event, states whose layers can be solved in fewer than
+    /// six turns are skipped, however many of the tips are twisted.
+    pub fn random_scramble(tables: &Tables) -> Vec<PyraminxMetric> {
+        loop {
+            let solution = Self::random_state().solve(tables);
+            let (tips, layers): (Vec<_>, Vec<_>) = solution.iter().partition(|turn| turn.is_tip());
+
+            if layers.len() >= 6 {
+                let mut scramble = PyraminxMetric::invert_seq(&layers);
+                scramble.extend(tips.iter().map(|turn| turn.inverse()));
+                return scramble;
+            }
+        }
+    }
+}
+
+impl FromIterator<PyraminxMetric> for Pyraminx {
+    fn from_iter<T: IntoIterator<Item = PyraminxMetric>>(iter: T) -> Self {
+        Self::default().apply_seq(iter)
+    }
+}
+
+fn centre_coordinate(centres: &Twists) -> u8 {
+    centres
+        .elements()
+        .iter()
+        .rev()
+        .fold(0, |coordinate, &(_, twist)| coordinate * 3 + twist)
+}
+
+/// The edges and the centres of the Pyraminx, leaving out the tips.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Reduced {
+    edges: EdgeCoord,
+    centres: u8,
+}
+
+impl Reduced {
+    fn apply(self, table: &Table, ix: usize) -> Self {
+        let turn = PyraminxMetric::LAYERS[ix];
+        let place = 3u8.pow(turn.vertex() as u32);
+        let twist = self.centres / place % 3;
+
+        Self {
+            edges: table.lookup(self.edges, ix),
+            centres: self.centres - twist * place + (twist + turn.amount()) % 3 * place,
+        }
+    }
+}
+
+impl From<&Pyraminx> for Reduced {
+    fn from(puzzle: &Pyraminx) -> Self {
+        Self {
+            edges: def::Coordinate(puzzle.edges.o_coordinate(), puzzle.edges.p_coordinate()),
+            centres: centre_coordinate(&puzzle.centres),
+        }
+    }
+}
+
+impl From<Reduced> for usize {
+    fn from(Reduced { edges, centres }: Reduced) -> Self {
+        usize::from(edges) * CENTRES + centres as usize
+    }
+}
+
+impl TryFrom<usize> for Reduced {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            edges: (value / CENTRES).try_into()?,
+            centres: (value % CENTRES) as u8,
+        })
+    }
+}
+
+impl Search for Reduced {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.0.lookup(self)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        (0..MOVE_COUNT)
+            .map(|ix| (self.apply(table, ix), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+pub struct Table(trans::Table<EdgeCoord, EDGES, MOVE_COUNT>);
+
+impl Table {
+    pub fn new() -> Self {
+        let moves = PyraminxMetric::LAYERS.map(|turn| PyraminxMetric::EDGE_MOVES[turn as usize]);
+
+        Self(trans::Table::new(
+            &moves,
+            EdgeCoord::all(),
+            |coord, turn| {
+                let edges = coord.array().permute(turn);
+                def::Coordinate(edges.o_coordinate(), edges.p_coordinate())
+            },
+        ))
+    }
+
+    pub fn lookup(&self, edges: EdgeCoord, index: usize) -> EdgeCoord {
+        self.0.lookup(edges, index)
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct PruningTable(pruning::Table<Reduced, STATES>);
+
+impl PruningTable {
+    pub fn new(table: &Table) -> Self {
+        Self(pruning::Table::new(
+            &crate::util::count::<MOVE_COUNT>(),
+            |position: Reduced, &ix: &usize| position.apply(table, ix),
+        ))
+    }
+}
+
+pub fn generate_tables() -> Tables {
+    let table = Table::new();
+    let pruning_table = PruningTable::new(&table);
+
+    (table, pruning_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Reason;
+
+    #[test]
+    pub fn distances() {
+        let (_, pruning_table) = generate_tables();
+
+        // Every position other than the solved one is at a distance of at least one, and half
+        // of the coordinates have an odd permutation of the edges and cannot be reached
+        let mut reachable = 1;
+        let mut deepest = 0;
+        for ix in 0..STATES {
+            let distance = Reduced::try_from(ix).unwrap().heuristic(&pruning_table);
+            if distance > 0 {
+                reachable += 1;
+                deepest = deepest.max(distance);
+            }
+        }
+        assert_eq!(reachable, 933_120);
+        assert_eq!(deepest, 11);
+
+        // A single swap of two edges is one of them
+        let mut edges = Edges::IDENTITY.elements();
+        edges.swap(0, 1);
+        let swapped = Pyraminx {
+            edges: Edges::new(edges),
+            ..Pyraminx::default()
+        };
+        assert_eq!(Reduced::from(&swapped).heuristic(&pruning_table), 0);
+        assert!(!swapped.is_solved());
+        assert!((0..100).all(|_| Pyraminx::random_state().edges.is_even()));
+    }
+
+    #[test]
+    pub fn tips() {
+        let tables = generate_tables();
+        let solve = |source| {
+            let puzzle = Pyraminx::from_iter(PyraminxMetric::parse(source).unwrap());
+            let solution = puzzle.solve(&tables);
+            assert!(puzzle.apply_seq(solution.iter().copied()).is_solved());
+            PyraminxMetric::format_seq(solution.into_iter())
+        };
+
+        // The tips are turned last, each by a single turn, and a layer turn also turns its tip
+        assert_eq!(solve("r b'"), "r' b");
+        assert_eq!(solve("U u'"), "U' u");
+        assert!(solve("U L' R B' U' L r b'").ends_with(" r' b"));
+
+        assert_eq!(
+            PyraminxMetric::format_seq(PyraminxMetric::parse(" U  l' ").unwrap().into_iter()),
+            "U l'"
+        );
+        let error = |source| PyraminxMetric::parse(source).unwrap_err();
+        assert_eq!(error("U2").reason, Reason::BadSuffix);
+        assert_eq!(error("R F").reason, Reason::UnknownFace);
+        assert_eq!(error("R F").offset, 2);
+
+        // The layers of a scramble take at least six turns to solve, and its tips come last
+        for _ in 0..10 {
+            let scramble = Pyraminx::random_scramble(&tables);
+            let puzzle = Pyraminx::from_iter(scramble.iter().copied());
+            let layers = scramble.iter().take_while(|turn| !turn.is_tip()).count();
+            assert!(layers >= 6);
+            assert!(scramble[layers..].iter().all(|turn| turn.is_tip()));
+            assert_eq!(puzzle.solve(&tables).len(), scramble.len());
+        }
+    }
+}
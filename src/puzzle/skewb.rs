@@ -0,0 +1,479 @@
+//! The Skewb, with its eight corners and six centres. URF never moves, and the other corners
+//! fall into two sets which only move among themselves: UFL, UBR, DFR and DBL, which R, U and L
+//! cycle, and ULB, DLF and DRB, which only B cycles.
+//!
+//! Twisting one of the first set changes which of the second is where, so the twists of neither
+//! set sum to zero and each is given a coordinate of its own. The search is an IDA* guided by
+//! the distance to solving each set together with the centres.
+
+use crate::core::definitions as def;
+use crate::core::pruning;
+use crate::core::search::{ida_iter, Depth, Search, Solved};
+use crate::core::transition as trans;
+use crate::metric::skewb::{Centres, Corners};
+use crate::metric::Skewb as SkewbMetric;
+use crate::util::{self, factorial, power};
+use rand::Rng;
+use std::convert::{TryFrom, TryInto};
+use std::iter::FromIterator;
+
+type CentreCoord = def::PermutationCoord<6>;
+
+const MOVE_COUNT: usize = SkewbMetric::COUNT;
+
+/// The corners cycled by R, U and L.
+const FREE: [u8; 4] = [1, 3, 4, 6];
+/// The corners other than URF cycled by B alone.
+const HELD: [u8; 3] = [2, 5, 7];
+
+pub type Tables = (Table, PruningTable);
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Skewb {
+    pub corners: Corners,
+    pub centres: Centres,
+}
+
+impl Skewb {
+    pub fn apply(&self, turn: SkewbMetric) -> Self {
+        Self {
+            corners: self
+                .corners
+                .permute(&SkewbMetric::CORNER_MOVES[turn as usize]),
+            centres: self
+                .centres
+                .permute(&SkewbMetric::CENTRE_MOVES[turn as usize]),
+        }
+    }
+
+    pub fn apply_seq(&self, sequence: impl IntoIterator<Item = SkewbMetric>) -> Self {
+        sequence
+            .into_iter()
+            .fold(*self, |puzzle, turn| puzzle.apply(turn))
+    }
+
+    /// A uniformly random position. The free corners may be in any even permutation and twisted
+    /// any way, which fixes the permutation of the held corners and the sum of their twists.
+    pub fn random_state() -> Self {
+        let mut rng = rand::thread_rng();
+
+        let mut free = def::Array::<4, 3>::random_even().elements();
+        for (_, twist) in free.iter_mut() {
+            *twist = rng.gen_range(0..3);
+        }
+
+        // The twists of the held corners sum to zero when the free corners are solved or have
+        // two pairs swapped, and to one or two when they are three-cycled one way or the other
+        let cycle = free[0].0 ^ free[1].0;
+        let mut held = [(0, 0); 3];
+        let mut sum = 0;
+        for (ix, piece) in held.iter_mut().enumerate() {
+            let twist = if ix < 2 {
+                rng.gen_range(0..3)
+            } else {
+                (cycle + 5 - sum) % 3
+            };
+            sum += twist;
+            *piece = (ix as u8, twist);
+        }
+
+        let shift = free.iter().map(|&(_, twist)| twist as usize).sum::<usize>() % 3;
+        let mut corners = Corners::IDENTITY.elements();
+        for (ix, &position) in FREE.iter().enumerate() {
+            let (piece, twist) = free[ix];
+            corners[position as usize] = (FREE[piece as usize], twist);
+        }
+        for (ix, &position) in HELD.iter().enumerate() {
+            let (_, twist) = held[ix];
+            corners[position as usize] = (HELD[(ix + 3 - shift) % 3], twist);
+        }
+
+        Self {
+            corners: Corners::new(corners),
+            centres: Centres::random_even(),
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Whether the position can be reached, with each set of corners in its own positions and
+    /// related to the other as in `random_state`, and the centres in an even permutation.
+    pub fn is_valid(&self) -> bool {
+        let corners = self.corners.elements();
+        let within = |set: &[u8]| set.iter().all(|&ix| set.contains(&corners[ix as usize].0));
+        if corners[0] != (0, 0) || !within(&FREE) || !within(&HELD) {
+            return false;
+        }
+
+        let (free, held) = (
+            restrict(&self.corners, &FREE),
+            restrict(&self.corners, &HELD),
+        );
+        let twists = |set: &[(u8, u8)]| set.iter().map(|&(_, twist)| twist).sum::<u8>() % 3;
+        let (free_pieces, held_pieces) = (free.elements(), held.elements());
+
+        // The twists of the free corners give the permutation of the held ones, and the cycle of
+        // the free corners the sum of the twists of the held ones
+        let shift = twists(&free_pieces);
+        let shifted = (0..3).all(|ix| held_pieces[ix].0 == (ix as u8 + 3 - shift) % 3);
+        let cycle = free_pieces[0].0 ^ free_pieces[1].0;
+
+        free.is_even()
+            && self.centres.is_even()
+            && shifted
+            && twists(&held_pieces) == (cycle + 2) % 3
+    }
+
+    /// An optimal solution, or `None` if the position cannot be reached, for which the search
+    /// would never end.
+    pub fn solve(&self, (table, pruning_table): &Tables) -> Option<Vec<SkewbMetric>> {
+        if !self.is_valid() {
+            return None;
+        }
+
+        let path = ida_iter(Position::from(self), &Solved, pruning_table, table, None).next()?;
+        Some(
+            path.into_iter()
+                .map(|(_, ix)| SkewbMetric::ALL[ix])
+                .collect(),
+        )
+    }
+
+    /// A scramble for a random state, which is the inverse of its optimal solution. As for the
+    /// WCA event, states which can be solved in fewer than seven turns are skipped.
+    pub fn random_scramble(tables: &Tables) -> Vec<SkewbMetric> {
+        loop {
+            let solution = Self::random_state().solve(tables).unwrap();
+            if solution.len() >= 7 {
+                return SkewbMetric::invert_seq(&solution);
+            }
+        }
+    }
+}
+
+impl FromIterator<SkewbMetric> for Skewb {
+    fn from_iter<T: IntoIterator<Item = SkewbMetric>>(iter: T) -> Self {
+        Self::default().apply_seq(iter)
+    }
+}
+
+/// The corners at `positions`, with each labelled by its index in `positions`.
+fn restrict<const N: usize>(corners: &Corners, positions: &[u8; N]) -> def::Array<N, 3> {
+    let corners = corners.elements();
+
+    let mut restricted = [(0, 0); N];
+    for (ix, &position) in positions.iter().enumerate() {
+        let (corner, twist) = corners[position as usize];
+        let corner = positions.iter().position(|&p| p == corner).unwrap();
+        restricted[ix] = (corner as u8, twist);
+    }
+
+    def::Array::new(restricted)
+}
+
+/// One of the two sets of corners, as the permutation and the twist of each. The twist is
+/// independent of the permutation, since it belongs to the positions rather than the pieces.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Set<const N: usize> {
+    permutation: def::PermutationCoord<N>,
+    twist: u8,
+}
+
+impl<const N: usize> Set<N> {
+    pub const TWISTS: usize = power(3, N);
+    pub const BOUND: usize = factorial(N) * Self::TWISTS;
+
+    fn array(self) -> def::Array<N, 3> {
+        let mut elements = self.permutation.array::<3>().elements();
+
+        let mut twist = self.twist;
+        for (_, t) in elements.iter_mut().rev() {
+            *t = twist % 3;
+            twist /= 3;
+        }
+
+        def::Array::new(elements)
+    }
+
+    fn all() -> impl Iterator<Item = Self> {
+        (0..Self::BOUND).map(|ix| Self::try_from(ix).unwrap())
+    }
+
+    fn permute(self, turn: &def::Array<N, 3>) -> Self {
+        Self::from(&self.array().permute(turn))
+    }
+}
+
+impl<const N: usize> From<&def::Array<N, 3>> for Set<N> {
+    fn from(array: &def::Array<N, 3>) -> Self {
+        Self {
+            permutation: array.p_coordinate(),
+            twist: array
+                .elements()
+                .iter()
+                .fold(0, |twist, &(_, t)| twist * 3 + t),
+        }
+    }
+}
+
+impl<const N: usize> From<Set<N>> for usize {
+    fn from(Set { permutation, twist }: Set<N>) -> Self {
+        usize::from(permutation) * Set::<N>::TWISTS + twist as usize
+    }
+}
+
+impl<const N: usize> TryFrom<usize> for Set<N> {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            permutation: (value / Self::TWISTS).try_into()?,
+            twist: (value % Self::TWISTS) as u8,
+        })
+    }
+}
+
+/// One of the sets of corners together with the centres, which a pruning table gives the
+/// distance to solving.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Part<const N: usize> {
+    corners: Set<N>,
+    centres: CentreCoord,
+}
+
+impl<const N: usize> Part<N> {
+    pub const BOUND: usize = Set::<N>::BOUND * CentreCoord::BOUND;
+}
+
+impl<const N: usize> From<Part<N>> for usize {
+    fn from(Part { corners, centres }: Part<N>) -> Self {
+        usize::from(corners) * CentreCoord::BOUND + usize::from(centres)
+    }
+}
+
+impl<const N: usize> TryFrom<usize> for Part<N> {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            corners: (value / CentreCoord::BOUND).try_into()?,
+            centres: (value % CentreCoord::BOUND).try_into()?,
+        })
+    }
+}
+
+/// The position of the Skewb as the coordinates of both sets of corners and of the centres.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Position {
+    free: Set<4>,
+    held: Set<3>,
+    centres: CentreCoord,
+}
+
+impl From<&Skewb> for Position {
+    fn from(puzzle: &Skewb) -> Self {
+        Self {
+            free: Set::from(&restrict(&puzzle.corners, &FREE)),
+            held: Set::from(&restrict(&puzzle.corners, &HELD)),
+            centres: puzzle.centres.p_coordinate(),
+        }
+    }
+}
+
+impl Search for Position {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        let PruningTable(free, held) = table;
+        let Self {
+            free: free_corners,
+            held: held_corners,
+            centres,
+        } = self;
+
+        free.lookup(Part {
+            corners: free_corners,
+            centres,
+        })
+        .max(held.lookup(Part {
+            corners: held_corners,
+            centres,
+        }))
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        let Table(free, held, centres) = table;
+
+        (0..MOVE_COUNT)
+            .map(|ix| {
+                let position = Self {
+                    free: free.lookup(self.free, ix),
+                    held: held.lookup(self.held, ix),
+                    centres: centres.lookup(self.centres, ix),
+                };
+                (position, ix)
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+pub struct Table(
+    trans::Table<Set<4>, { Set::<4>::BOUND }, MOVE_COUNT>,
+    trans::Table<Set<3>, { Set::<3>::BOUND }, MOVE_COUNT>,
+    trans::Table<CentreCoord, { CentreCoord::BOUND }, MOVE_COUNT>,
+);
+
+impl Table {
+    pub fn new() -> Self {
+        let free = SkewbMetric::CORNER_MOVES.map(|corners| restrict(&corners, &FREE));
+        let held = SkewbMetric::CORNER_MOVES.map(|corners| restrict(&corners, &HELD));
+
+        Self(
+            trans::Table::new(&free, Set::all(), Set::permute),
+            trans::Table::new(&held, Set::all(), Set::permute),
+            trans::Table::new(
+                &SkewbMetric::CENTRE_MOVES,
+                CentreCoord::all(),
+                CentreCoord::permute,
+            ),
+        )
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct PruningTable(
+    pruning::Table<Part<4>, { Part::<4>::BOUND }>,
+    pruning::Table<Part<3>, { Part::<3>::BOUND }>,
+);
+
+impl PruningTable {
+    pub fn new(Table(free, held, centres): &Table) -> Self {
+        let generators = util::count::<MOVE_COUNT>();
+
+        Self(
+            pruning::Table::new(&generators, |part: Part<4>, &ix: &usize| Part {
+                corners: free.lookup(part.corners, ix),
+                centres: centres.lookup(part.centres, ix),
+            }),
+            pruning::Table::new(&generators, |part: Part<3>, &ix: &usize| Part {
+                corners: held.lookup(part.corners, ix),
+                centres: centres.lookup(part.centres, ix),
+            }),
+        )
+    }
+}
+
+pub fn generate_tables() -> Tables {
+    let table = Table::new();
+    let pruning_table = PruningTable::new(&table);
+
+    (table, pruning_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Reason;
+
+    #[test]
+    pub fn parity() {
+        let (_, pruning_table) = generate_tables();
+
+        // The free corners with the centres reach 12 * 81 * 360 positions, and the held corners
+        // with the centres 3 * 27 * 360, of the 3,149,280 positions of the whole puzzle
+        let count = |table: &[Depth]| table.iter().filter(|&&depth| depth > 0).count() + 1;
+        let PruningTable(free, held) = &pruning_table;
+        let free = (0..Part::<4>::BOUND)
+            .map(|ix| free.lookup(Part::try_from(ix).unwrap()))
+            .collect::<Vec<_>>();
+        let held = (0..Part::<3>::BOUND)
+            .map(|ix| held.lookup(Part::try_from(ix).unwrap()))
+            .collect::<Vec<_>>();
+        assert_eq!(count(&free), 349_920);
+        assert_eq!(count(&held), 29_160);
+
+        // A swap of two centres or of two free corners, or a twist of a single corner, cannot be
+        // reached
+        let mut corners = Corners::IDENTITY.elements();
+        corners.swap(1, 3);
+        let swapped = Skewb {
+            corners: Corners::new(corners),
+            ..Skewb::default()
+        };
+        assert!(!swapped.is_valid());
+
+        let mut centres = Centres::IDENTITY.elements();
+        centres.swap(0, 1);
+        let swapped = Skewb {
+            centres: Centres::new(centres),
+            ..Skewb::default()
+        };
+        assert!(!swapped.is_valid());
+        for &position in FREE.iter().chain(HELD.iter()) {
+            let mut corners = Corners::IDENTITY.elements();
+            corners[position as usize].1 = 1;
+            let twisted = Skewb {
+                corners: Corners::new(corners),
+                ..Skewb::default()
+            };
+            assert!(!twisted.is_valid(), "{}", position);
+        }
+
+        // Random states match the twists of the held corners to the cycle of the free ones, as
+        // turning does
+        let mut rng = rand::thread_rng();
+        for _ in 0..1000 {
+            let turns = (0..30).map(|_| SkewbMetric::ALL[rng.gen_range(0..MOVE_COUNT)]);
+            assert!(Skewb::from_iter(turns).is_valid());
+            assert!(Skewb::random_state().is_valid());
+        }
+    }
+
+    #[test]
+    pub fn solutions() {
+        let tables = generate_tables();
+
+        let scramble = SkewbMetric::parse("R U' L B' R' U").unwrap();
+        let puzzle = Skewb::from_iter(scramble.iter().copied());
+        let solution = puzzle.solve(&tables).unwrap();
+        assert_eq!(solution.len(), 6);
+        assert!(puzzle.apply_seq(solution).is_solved());
+        assert_eq!(
+            Skewb::from_iter(SkewbMetric::parse("R R R").unwrap()),
+            Skewb::default()
+        );
+
+        // A position which cannot be reached has no solution
+        let mut centres = Centres::IDENTITY.elements();
+        centres.swap(0, 1);
+        let swapped = Skewb {
+            centres: Centres::new(centres),
+            ..Skewb::default()
+        };
+        assert_eq!(swapped.solve(&tables), None);
+
+        let error = |source| SkewbMetric::parse(source).unwrap_err();
+        assert_eq!(error("R2").reason, Reason::BadSuffix);
+        assert_eq!(error("R F'").reason, Reason::UnknownFace);
+        assert_eq!(error("R F'").offset, 2);
+
+        // Scrambles are for states at least seven turns from solved
+        for _ in 0..5 {
+            let scramble = Skewb::random_scramble(&tables);
+            let puzzle = Skewb::from_iter(scramble.iter().copied());
+            assert!(scramble.len() >= 7);
+            assert_eq!(puzzle.solve(&tables).unwrap().len(), scramble.len());
+        }
+    }
+}
@@ -0,0 +1,945 @@
+//! The Square-1, whose top and bottom layers are each a ring of twelve slots of a twelfth of a
+//! turn, filled by edges of one slot and corners of two, and whose middle layer is split in two
+//! halves. The layers turn freely, but the right half of the puzzle can only be twisted when no
+//! corner lies across the cut, so that the moves available depend on the shape of the puzzle.
+//!
+//! The solver works in two phases, counting only the twists. The first brings the puzzle into
+//! the shape of a cube, searching over the puzzle itself, as its successors depend on its shape,
+//! guided by the exact distance of each shape up to the turns of the layers. The second solves
+//! the pieces with the moves which keep the shape of a cube, in two steps each guided by an exact
+//! table: the first brings each piece into its layer and solves the middle layer, and the second
+//! orders the pieces within their layers with macros of four moves which keep them there.
+
+use crate::core::definitions as def;
+use crate::core::pruning;
+use crate::core::search::{ida_iter, Depth, Search, Solved};
+use crate::core::transition as trans;
+use crate::metric::Square1 as Square1Metric;
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::convert::{TryFrom, TryInto};
+
+/// The slots of the top layer count clockwise seen from above from the back end of the cut, and
+/// those of the bottom layer clockwise seen from below from the front end, so that a twist swaps
+/// the first six slots of each. Corners are pieces 0 to 7, and edges 8 to 15.
+#[rustfmt::skip]
+const SOLVED: [[u8; 12]; 2] = [
+    [0, 0, 8, 1, 1, 9, 2, 2, 10, 3, 3, 11],
+    [4, 4, 12, 5, 5, 13, 6, 6, 14, 7, 7, 15],
+];
+
+/// The number of shapes of a layer up to turns of the layer.
+const LAYER_COUNT: usize = 31;
+
+/// Each shape of a layer up to turns, as the slots at which its pieces start, taking the least
+/// of its turns.
+const LAYERS: [u16; LAYER_COUNT] = {
+    let mut layers = [0; LAYER_COUNT];
+
+    let mut count = 0;
+    let mut mask = 0;
+    while mask < 1 << 12 {
+        if is_layer(mask) && canonical(mask) == mask {
+            layers[count] = mask;
+            count += 1;
+        }
+        mask += 1;
+    }
+
+    layers
+};
+
+/// The turns of the layers, each followed by a twist, which keep the shape of a cube once
+/// its layers are turned to start with a corner.
+const CUBE_MOVES: [(i8, i8); CUBE_MOVE_COUNT] = {
+    let mut moves = [(0, 0); CUBE_MOVE_COUNT];
+
+    let mut ix = 0;
+    while ix < CUBE_MOVE_COUNT {
+        // Both layers by quarter turns, or both by a twelfth more
+        let (offset, turn) = (ix % 2, ix / 2);
+        moves[ix] = ((turn / 4 * 3 + offset) as i8, (turn % 4 * 3 + offset) as i8);
+        ix += 1;
+    }
+
+    moves
+};
+
+const CUBE_MOVE_COUNT: usize = 32;
+
+/// The number of distinct sequences of four moves which keep the shape of a cube and every piece
+/// in its layer, other than those which only turn the layers.
+const MACRO_COUNT: usize = 256;
+
+type Permutation = def::PermutationCoord<8>;
+/// Which of the corners or of the edges lie in the bottom layer.
+type Layers = def::CombinationCoord<8, 4>;
+/// The order of the four corners or edges of a layer.
+type Half = def::PermutationCoord<4>;
+
+pub type Tables = (ShapeTable, Table, PruningTable);
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Square1 {
+    pub top: [u8; 12],
+    pub bottom: [u8; 12],
+    /// Whether the right half of the middle layer is turned over.
+    pub middle: bool,
+}
+
+impl Default for Square1 {
+    fn default() -> Self {
+        Self {
+            top: SOLVED[0],
+            bottom: SOLVED[1],
+            middle: false,
+        }
+    }
+}
+
+impl Square1 {
+    /// The position after `turn`, or `None` for a twist with a corner across the cut.
+    pub fn apply(&self, turn: Square1Metric) -> Option<Self> {
+        match turn {
+            Square1Metric::Turn(top, bottom) => Some(self.turn(top, bottom)),
+            Square1Metric::Twist if self.can_twist() => Some(self.twist()),
+            Square1Metric::Twist => None,
+        }
+    }
+
+    pub fn apply_seq(&self, sequence: impl IntoIterator<Item = Square1Metric>) -> Option<Self> {
+        sequence
+            .into_iter()
+            .try_fold(*self, |puzzle, turn| puzzle.apply(turn))
+    }
+
+    fn turn(&self, top: i8, bottom: i8) -> Self {
+        let rotate = |layer: &[u8; 12], amount: i8| {
+            let mut rotated = [0; 12];
+            for (ix, &piece) in layer.iter().enumerate() {
+                rotated[(ix + amount.rem_euclid(12) as usize) % 12] = piece;
+            }
+            rotated
+        };
+
+        Self {
+            top: rotate(&self.top, top),
+            bottom: rotate(&self.bottom, bottom),
+            middle: self.middle,
+        }
+    }
+
+    fn twist(&self) -> Self {
+        let mut puzzle = *self;
+        puzzle.top[..6].swap_with_slice(&mut puzzle.bottom[..6]);
+        puzzle.middle = !puzzle.middle;
+        puzzle
+    }
+
+    /// Whether no corner lies across the cut in either layer.
+    pub fn can_twist(&self) -> bool {
+        let (top, bottom) = (mask(&self.top), mask(&self.bottom));
+        top & bottom & CUT == CUT
+    }
+
+    pub fn shape(&self) -> Shape {
+        Shape {
+            top: layer(mask(&self.top)),
+            bottom: layer(mask(&self.bottom)),
+        }
+    }
+
+    pub fn is_solved(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// A uniformly random position in which the puzzle can be twisted, as for the WCA. Every
+    /// shape allows every arrangement of the corners and of the edges, with either turn of the
+    /// middle layer.
+    pub fn random_state(shape_table: &ShapeTable) -> Self {
+        let mut rng = rand::thread_rng();
+
+        let twistable = (0..1 << 12)
+            .filter(|&mask| is_layer(mask) && mask & CUT == CUT)
+            .collect::<Vec<u16>>();
+        let (top, bottom) = loop {
+            let top = *twistable.choose(&mut rng).unwrap();
+            let bottom = *twistable.choose(&mut rng).unwrap();
+            let shape = Shape {
+                top: layer(top),
+                bottom: layer(bottom),
+            };
+            if corners(top) + corners(bottom) == 8 && shape_table.reachable(shape) {
+                break (top, bottom);
+            }
+        };
+
+        let mut corners = (0..8).collect::<Vec<u8>>();
+        let mut edges = (8..16).collect::<Vec<u8>>();
+        corners.shuffle(&mut rng);
+        edges.shuffle(&mut rng);
+
+        let mut fill = |mask: u16| {
+            let mut layer = [0; 12];
+            for ix in 0..12 {
+                // Every layer which can be twisted has a piece starting in the first slot
+                layer[ix] = match ((mask >> ix) & 1, (mask >> ((ix + 1) % 12)) & 1) {
+                    (0, _) => layer[ix - 1],
+                    (_, 0) => corners.pop().unwrap(),
+                    _ => edges.pop().unwrap(),
+                };
+            }
+            layer
+        };
+
+        Self {
+            top: fill(top),
+            bottom: fill(bottom),
+            middle: rng.gen(),
+        }
+    }
+
+    /// A solution in two phases, which is optimal in neither.
+    pub fn solve(&self, (shape_table, table, pruning_table): &Tables) -> Vec<Square1Metric> {
+        let goal = |puzzle: &Square1| {
+            puzzle.shape() == Shape::CUBE && Cube::from(&puzzle.normalize().0).is_solvable()
+        };
+
+        let mut solution = vec![];
+        let mut puzzle = *self;
+
+        let shape = ida_iter(puzzle, &goal, shape_table, &(), None)
+            .next()
+            .unwrap();
+        for &(_, (top, bottom)) in shape.iter() {
+            solution.push(Square1Metric::turn(top, bottom));
+            solution.push(Square1Metric::Twist);
+            puzzle = puzzle.turn(top, bottom).twist();
+        }
+
+        let (cube, (top, bottom)) = puzzle.normalize();
+        solution.push(Square1Metric::turn(top, bottom));
+        puzzle = cube;
+
+        // The second phase first separates the layers, then orders the pieces within them
+        let cube = Cube::from(&puzzle);
+        let separation = ida_iter(Separation::from(&cube), &Solved, pruning_table, table, None)
+            .next()
+            .unwrap();
+        let mut moves = separation.iter().map(|&(_, ix)| ix).collect::<Vec<_>>();
+
+        let cube = moves
+            .iter()
+            .fold(cube, |cube, &ix| cube.apply(&table.moves, ix));
+        let goals = Cube::goals().iter().map(Within::from).collect::<Vec<_>>();
+        let within = ida_iter(Within::from(&cube), &goals, pruning_table, table, None)
+            .next()
+            .unwrap();
+        moves.extend(
+            within
+                .iter()
+                .flat_map(|&(_, ix)| table.macros[ix].iter().copied()),
+        );
+
+        for ix in moves {
+            let (top, bottom) = CUBE_MOVES[ix];
+            solution.push(Square1Metric::turn(top, bottom));
+            solution.push(Square1Metric::Twist);
+
+            let (cube, (top, bottom)) = puzzle.turn(top, bottom).twist().normalize();
+            solution.push(Square1Metric::turn(top, bottom));
+            puzzle = cube;
+        }
+
+        let (top, bottom) = (0..12)
+            .flat_map(|top| (0..12).map(move |bottom| (top, bottom)))
+            .find(|&(top, bottom)| puzzle.turn(top, bottom).is_solved())
+            .unwrap();
+        solution.push(Square1Metric::turn(top, bottom));
+
+        Square1Metric::simplify(&solution)
+    }
+
+    /// A scramble for a random state, which is the inverse of its solution.
+    pub fn random_scramble(tables: &Tables) -> Vec<Square1Metric> {
+        Square1Metric::invert_seq(&Self::random_state(&tables.0).solve(tables))
+    }
+
+    /// The same puzzle in the shape of a cube, with each layer turned to start with a corner,
+    /// together with that turn.
+    fn normalize(&self) -> (Self, (i8, i8)) {
+        let start = |layer: &[u8; 12]| {
+            (0..3i8)
+                .find(|&amount| {
+                    let ix = 12 - amount as usize;
+                    let (first, second, third) =
+                        (layer[ix % 12], layer[(ix + 1) % 12], layer[(ix + 2) % 12]);
+                    first == second && first < 8 && third >= 8
+                })
+                .unwrap()
+        };
+        let turn = (start(&self.top), start(&self.bottom));
+
+        (self.turn(turn.0, turn.1), turn)
+    }
+}
+
+/// The slots at which the cut lies, at the start of the first and the seventh.
+const CUT: u16 = 1 | 1 << 6;
+
+/// The slots of the layer at which its pieces start.
+fn mask(layer: &[u8; 12]) -> u16 {
+    (0..12)
+        .filter(|&ix| layer[ix] != layer[(ix + 11) % 12])
+        .fold(0, |mask, ix| mask | 1 << ix)
+}
+
+/// Whether the pieces start at the slots of `mask` in some layer, which is when no two slots in
+/// a row are without a start.
+const fn is_layer(mask: u16) -> bool {
+    let pairs = mask | rotate(mask, 1);
+    pairs & 0xfff == 0xfff
+}
+
+/// The starts of the pieces once the layer is turned `amount` twelfths clockwise.
+const fn rotate(mask: u16, amount: usize) -> u16 {
+    (mask << amount | mask >> (12 - amount % 12)) & 0xfff
+}
+
+/// The least of the turns of a layer.
+const fn canonical(mask: u16) -> u16 {
+    let mut least = mask;
+
+    let mut amount = 1;
+    while amount < 12 {
+        let rotated = rotate(mask, amount);
+        if rotated < least {
+            least = rotated;
+        }
+        amount += 1;
+    }
+
+    least
+}
+
+/// The index of the shape of a layer in `LAYERS`.
+fn layer(mask: u16) -> u8 {
+    let mask = canonical(mask);
+    LAYERS.iter().position(|&layer| layer == mask).unwrap() as u8
+}
+
+/// The number of corners in a layer, which fill twelve slots together with its edges.
+fn corners(mask: u16) -> u32 {
+    12 - mask.count_ones()
+}
+
+/// The shape of the puzzle, as that of each layer up to turns of the layer.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Shape {
+    top: u8,
+    bottom: u8,
+}
+
+impl Shape {
+    pub const BOUND: usize = LAYER_COUNT * LAYER_COUNT;
+
+    /// The shape of a cube, with corners and edges alternating in each layer.
+    pub const CUBE: Self = {
+        let square = canonical(0b1011_0110_1101);
+        let mut ix = 0;
+        while LAYERS[ix] != square {
+            ix += 1;
+        }
+        Self {
+            top: ix as u8,
+            bottom: ix as u8,
+        }
+    };
+
+    /// The shape after the layers are turned by `top` and `bottom` twelfths and twisted, or the
+    /// same shape if it cannot be twisted.
+    fn twist(self, (top, bottom): (i8, i8)) -> Self {
+        let top_mask = rotate(LAYERS[self.top as usize], top as usize);
+        let bottom_mask = rotate(LAYERS[self.bottom as usize], bottom as usize);
+        if top_mask & bottom_mask & CUT != CUT {
+            return self;
+        }
+
+        let half = (1 << 6) - 1;
+        Self {
+            top: layer(top_mask & !half | bottom_mask & half),
+            bottom: layer(bottom_mask & !half | top_mask & half),
+        }
+    }
+}
+
+impl From<Shape> for usize {
+    fn from(Shape { top, bottom }: Shape) -> Self {
+        top as usize * LAYER_COUNT + bottom as usize
+    }
+}
+
+impl TryFrom<usize> for Shape {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        if value < Self::BOUND {
+            Ok(Self {
+                top: (value / LAYER_COUNT) as u8,
+                bottom: (value % LAYER_COUNT) as u8,
+            })
+        } else {
+            Err(())
+        }
+    }
+}
+
+/// The number of twists needed to bring each shape into the shape of a cube.
+pub struct ShapeTable(pruning::Table<Shape, { Shape::BOUND }>);
+
+impl ShapeTable {
+    pub fn new() -> Self {
+        let mut turns = [(0, 0); 144];
+        for (ix, turn) in turns.iter_mut().enumerate() {
+            *turn = ((ix / 12) as i8, (ix % 12) as i8);
+        }
+
+        Self(pruning::Table::from_goals(
+            std::iter::once(Shape::CUBE),
+            &turns,
+            |shape, &turn| shape.twist(turn),
+        ))
+    }
+
+    pub fn lookup(&self, shape: Shape) -> Depth {
+        self.0.lookup(shape)
+    }
+
+    /// Whether the shape can be reached from the shape of a cube.
+    pub fn reachable(&self, shape: Shape) -> bool {
+        shape == Shape::CUBE || self.lookup(shape) > 0
+    }
+}
+
+impl Default for ShapeTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The first phase searches over the puzzle itself, with each edge a turn of the layers followed
+/// by a twist, of which only those which the shape allows are followed.
+impl Search for Square1 {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = (i8, i8);
+    type HeuristicData = ShapeTable;
+    type TransitionData = ();
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.lookup(self.shape())
+    }
+
+    fn transition(self, _: &Self::TransitionData) -> Self::Iter {
+        (0..12)
+            .flat_map(|top| (0..12).map(move |bottom| (top, bottom)))
+            .filter_map(|(top, bottom)| {
+                let puzzle = self.turn(top, bottom);
+                if puzzle.can_twist() {
+                    Some((puzzle.twist(), (top, bottom)))
+                } else {
+                    None
+                }
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// The puzzle in the shape of a cube with each layer starting with a corner, as the permutations
+/// of the corners and of the edges across their slots, top layer first, which layer each of them
+/// lies in, and the middle layer.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Cube {
+    corners: Permutation,
+    edges: Permutation,
+    corner_layers: Layers,
+    edge_layers: Layers,
+    middle: bool,
+}
+
+impl Cube {
+    /// The solved puzzle after each turn of the layers by quarter turns, which is solved once the
+    /// layers are turned back.
+    fn goals() -> Vec<Self> {
+        (0..4)
+            .flat_map(|top| (0..4).map(move |bottom| (top, bottom)))
+            .map(|(top, bottom)| Cube::from(&Square1::default().turn(top * 3, bottom * 3)))
+            .collect()
+    }
+
+    /// Whether the moves which keep the shape of a cube can solve the pieces. Each of them is an
+    /// even permutation of the corners and of the edges together.
+    fn is_solvable(&self) -> bool {
+        self.corners.array::<1>().is_even() == self.edges.array::<1>().is_even()
+    }
+
+    /// The puzzle after the move of `CUBE_MOVES` at `ix`.
+    fn apply(self, moves: &Moves, ix: usize) -> Self {
+        Self {
+            corners: moves.corners.lookup(self.corners, ix),
+            edges: moves.edges.lookup(self.edges, ix),
+            corner_layers: moves.corner_layers.lookup(self.corner_layers, ix),
+            edge_layers: moves.edge_layers.lookup(self.edge_layers, ix),
+            middle: !self.middle,
+        }
+    }
+}
+
+impl From<&Square1> for Cube {
+    fn from(puzzle: &Square1) -> Self {
+        let slots = |offset: usize, base: u8| {
+            let mut pieces = [(0, 0); 8];
+            for (ix, piece) in pieces.iter_mut().enumerate() {
+                let layer = if ix < 4 { &puzzle.top } else { &puzzle.bottom };
+                *piece = (layer[ix % 4 * 3 + offset] - base, 0);
+            }
+            def::Array::<8, 1>::new(pieces)
+        };
+        let (corners, edges) = (slots(0, 0), slots(2, 8));
+
+        Self {
+            corners: corners.p_coordinate(),
+            edges: edges.p_coordinate(),
+            corner_layers: corners.c_coordinate(),
+            edge_layers: edges.c_coordinate(),
+            middle: puzzle.middle,
+        }
+    }
+}
+
+/// Which layer each of the corners and of the edges lies in, and the middle layer, which the
+/// first step of the second phase solves.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Separation {
+    corners: Layers,
+    edges: Layers,
+    middle: bool,
+}
+
+impl Separation {
+    pub const BOUND: usize = Layers::BOUND * Layers::BOUND * 2;
+
+    fn apply(self, moves: &Moves, ix: usize) -> Self {
+        Self {
+            corners: moves.corner_layers.lookup(self.corners, ix),
+            edges: moves.edge_layers.lookup(self.edges, ix),
+            middle: !self.middle,
+        }
+    }
+}
+
+impl From<&Cube> for Separation {
+    fn from(cube: &Cube) -> Self {
+        Self {
+            corners: cube.corner_layers,
+            edges: cube.edge_layers,
+            middle: cube.middle,
+        }
+    }
+}
+
+impl From<Separation> for usize {
+    fn from(
+        Separation {
+            corners,
+            edges,
+            middle,
+        }: Separation,
+    ) -> Self {
+        (usize::from(corners) * Layers::BOUND + usize::from(edges)) * 2 + middle as usize
+    }
+}
+
+impl TryFrom<usize> for Separation {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            corners: (value / 2 / Layers::BOUND).try_into()?,
+            edges: (value / 2 % Layers::BOUND).try_into()?,
+            middle: value % 2 == 1,
+        })
+    }
+}
+
+/// The first step of the second phase searches over the moves which keep the shape of a cube,
+/// guided by the exact distance.
+impl Search for Separation {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.separation.lookup(self)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        (0..CUBE_MOVE_COUNT)
+            .map(|ix| (self.apply(&table.moves, ix), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// The order of four of the corners or of the edges within each layer, once each lies in its own
+/// layer.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Arrangement(u16);
+
+impl Arrangement {
+    pub const BOUND: usize = Half::BOUND * Half::BOUND;
+
+    fn all() -> impl Iterator<Item = Self> {
+        (0..Self::BOUND).map(|value| Self(value as u16))
+    }
+
+    fn array(self) -> def::Array<8, 1> {
+        let (top, bottom) = (
+            Half::try_from(self.0 as usize / Half::BOUND)
+                .unwrap()
+                .array::<1>(),
+            Half::try_from(self.0 as usize % Half::BOUND)
+                .unwrap()
+                .array::<1>(),
+        );
+        let (top, bottom) = (top.elements(), bottom.elements());
+
+        let mut pieces = [(0, 0); 8];
+        for ix in 0..4 {
+            pieces[ix] = top[ix];
+            pieces[ix + 4] = (bottom[ix].0 + 4, 0);
+        }
+        def::Array::new(pieces)
+    }
+
+    fn permute(self, array: &def::Array<8, 1>) -> Self {
+        Self::from(&self.array().permute(array))
+    }
+}
+
+impl From<&def::Array<8, 1>> for Arrangement {
+    fn from(array: &def::Array<8, 1>) -> Self {
+        let pieces = array.elements();
+        let (mut top, mut bottom) = ([(0, 0); 4], [(0, 0); 4]);
+        for ix in 0..4 {
+            top[ix] = pieces[ix];
+            bottom[ix] = (pieces[ix + 4].0 - 4, 0);
+        }
+        let (top, bottom) = (
+            def::Array::<4, 1>::new(top).p_coordinate(),
+            def::Array::<4, 1>::new(bottom).p_coordinate(),
+        );
+
+        Self((usize::from(top) * Half::BOUND + usize::from(bottom)) as u16)
+    }
+}
+
+impl From<Arrangement> for usize {
+    fn from(Arrangement(value): Arrangement) -> Self {
+        value as usize
+    }
+}
+
+impl TryFrom<usize> for Arrangement {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        if value < Self::BOUND {
+            Ok(Self(value as u16))
+        } else {
+            Err(())
+        }
+    }
+}
+
+/// The order of the corners and of the edges within each layer, which the second step of the
+/// second phase solves.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub struct Within {
+    corners: Arrangement,
+    edges: Arrangement,
+}
+
+impl Within {
+    pub const BOUND: usize = Arrangement::BOUND * Arrangement::BOUND;
+
+    fn apply(self, table: &Table, ix: usize) -> Self {
+        Self {
+            corners: table.corner_arrangements.lookup(self.corners, ix),
+            edges: table.edge_arrangements.lookup(self.edges, ix),
+        }
+    }
+}
+
+impl From<&Cube> for Within {
+    fn from(cube: &Cube) -> Self {
+        Self {
+            corners: Arrangement::from(&cube.corners.array()),
+            edges: Arrangement::from(&cube.edges.array()),
+        }
+    }
+}
+
+impl From<Within> for usize {
+    fn from(Within { corners, edges }: Within) -> Self {
+        usize::from(corners) * Arrangement::BOUND + usize::from(edges)
+    }
+}
+
+impl TryFrom<usize> for Within {
+    type Error = ();
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        Ok(Self {
+            corners: (value / Arrangement::BOUND).try_into()?,
+            edges: (value % Arrangement::BOUND).try_into()?,
+        })
+    }
+}
+
+/// The second step of the second phase searches over the macros, each of four of the moves which
+/// keep the shape of a cube, which keep every piece in its layer, guided by the exact distance.
+impl Search for Within {
+    type Iter = std::vec::IntoIter<(Self, Self::Edge)>;
+    type Edge = usize;
+    type HeuristicData = PruningTable;
+    type TransitionData = Table;
+
+    fn heuristic(self, table: &Self::HeuristicData) -> Depth {
+        table.within.lookup(self)
+    }
+
+    fn transition(self, table: &Self::TransitionData) -> Self::Iter {
+        (0..MACRO_COUNT)
+            .map(|ix| (self.apply(table, ix), ix))
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+/// The moves which keep the shape of a cube, on each part of the puzzle.
+struct Moves {
+    corners: trans::Table<Permutation, { Permutation::BOUND }, CUBE_MOVE_COUNT>,
+    edges: trans::Table<Permutation, { Permutation::BOUND }, CUBE_MOVE_COUNT>,
+    corner_layers: trans::Table<Layers, { Layers::BOUND }, CUBE_MOVE_COUNT>,
+    edge_layers: trans::Table<Layers, { Layers::BOUND }, CUBE_MOVE_COUNT>,
+}
+
+impl Moves {
+    fn new() -> Self {
+        let moves = CUBE_MOVES.map(|(top, bottom)| {
+            let (puzzle, _) = Square1::default().turn(top, bottom).twist().normalize();
+            let pieces = |offset: usize, base: u8| {
+                let mut pieces = [(0, 0); 8];
+                for (ix, piece) in pieces.iter_mut().enumerate() {
+                    let layer = if ix < 4 { &puzzle.top } else { &puzzle.bottom };
+                    *piece = (layer[ix % 4 * 3 + offset] - base, 0);
+                }
+                def::Array::<8, 1>::new(pieces)
+            };
+            (pieces(0, 0), pieces(2, 8))
+        });
+
+        let corners = moves.map(|(corners, _)| corners);
+        let edges = moves.map(|(_, edges)| edges);
+
+        Self {
+            corners: trans::Table::new(&corners, Permutation::all(), Permutation::permute),
+            edges: trans::Table::new(&edges, Permutation::all(), Permutation::permute),
+            corner_layers: trans::Table::new(&corners, Layers::all(), Layers::permute),
+            edge_layers: trans::Table::new(&edges, Layers::all(), Layers::permute),
+        }
+    }
+
+    /// Every distinct sequence of four moves which keeps every piece in its layer and does more
+    /// than turn the layers, together with its effect on the solved puzzle.
+    fn macros(&self) -> Vec<([usize; 4], Cube)> {
+        let goals = Cube::goals();
+        let mut macros: Vec<([usize; 4], Cube)> = vec![];
+
+        let solved = Cube::default();
+        for a in 0..CUBE_MOVE_COUNT {
+            let first = solved.apply(self, a);
+            for b in 0..CUBE_MOVE_COUNT {
+                let second = first.apply(self, b);
+                for c in 0..CUBE_MOVE_COUNT {
+                    let third = second.apply(self, c);
+                    for d in 0..CUBE_MOVE_COUNT {
+                        let cube = third.apply(self, d);
+                        if Separation::from(&cube) == Separation::default()
+                            && !goals.contains(&cube)
+                            && macros.iter().all(|&(_, effect)| effect != cube)
+                        {
+                            macros.push(([a, b, c, d], cube));
+                        }
+                    }
+                }
+            }
+        }
+
+        macros
+    }
+}
+
+pub struct Table {
+    moves: Moves,
+    /// The moves of each macro, as indices into `CUBE_MOVES`.
+    macros: [[usize; 4]; MACRO_COUNT],
+    corner_arrangements: trans::Table<Arrangement, { Arrangement::BOUND }, MACRO_COUNT>,
+    edge_arrangements: trans::Table<Arrangement, { Arrangement::BOUND }, MACRO_COUNT>,
+}
+
+impl Table {
+    pub fn new() -> Self {
+        let moves = Moves::new();
+        let (macros, effects): (Vec<_>, Vec<_>) = moves.macros().into_iter().unzip();
+        let effects: [Cube; MACRO_COUNT] = effects.try_into().unwrap();
+
+        Self {
+            moves,
+            macros: macros.try_into().unwrap(),
+            corner_arrangements: trans::Table::new(
+                &effects.map(|cube| cube.corners.array()),
+                Arrangement::all(),
+                Arrangement::permute,
+            ),
+            edge_arrangements: trans::Table::new(
+                &effects.map(|cube| cube.edges.array()),
+                Arrangement::all(),
+                Arrangement::permute,
+            ),
+        }
+    }
+}
+
+impl Default for Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct PruningTable {
+    separation: pruning::Table<Separation, { Separation::BOUND }>,
+    within: pruning::Table<Within, { Within::BOUND }>,
+}
+
+impl PruningTable {
+    pub fn new(table: &Table) -> Self {
+        Self {
+            separation: pruning::Table::new(
+                &crate::util::count::<CUBE_MOVE_COUNT>(),
+                |separation: Separation, &ix: &usize| separation.apply(&table.moves, ix),
+            ),
+            within: pruning::Table::from_goals(
+                Cube::goals().iter().map(Within::from),
+                &crate::util::count::<MACRO_COUNT>(),
+                |within: Within, &ix: &usize| within.apply(table, ix),
+            ),
+        }
+    }
+}
+
+pub fn generate_tables() -> Tables {
+    let table = Table::new();
+    let pruning_table = PruningTable::new(&table);
+
+    (ShapeTable::new(), table, pruning_table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::notation::Reason;
+
+    #[test]
+    pub fn shapes() {
+        let shape_table = ShapeTable::new();
+
+        // 170 shapes can be reached, the farthest of them seven twists from a cube
+        let shapes = (0..Shape::BOUND)
+            .map(|ix| Shape::try_from(ix).unwrap())
+            .filter(|&shape| shape_table.reachable(shape))
+            .collect::<Vec<_>>();
+        assert_eq!(shapes.len(), 170);
+        assert_eq!(
+            shapes.iter().map(|&shape| shape_table.lookup(shape)).max(),
+            Some(7)
+        );
+        assert!(shapes.iter().all(|shape| {
+            corners(LAYERS[shape.top as usize]) + corners(LAYERS[shape.bottom as usize]) == 8
+        }));
+
+        // A star of six corners in one layer, with the other two corners among the edges
+        let star = shapes
+            .iter()
+            .find(|shape| corners(LAYERS[shape.top as usize]) == 6);
+        assert!(star.is_some());
+        assert!(!shape_table.reachable(Shape { top: 0, bottom: 0 }));
+
+        // A corner lies across the cut after a turn of a twelfth anticlockwise
+        let turned = Square1::default()
+            .apply(Square1Metric::Turn(-1, 0))
+            .unwrap();
+        assert!(!turned.can_twist());
+        assert_eq!(turned.apply(Square1Metric::Twist), None);
+        assert_eq!(turned.shape(), Shape::CUBE);
+        let twisted = Square1::default().apply(Square1Metric::Twist).unwrap();
+        assert_eq!(twisted.shape(), Shape::CUBE);
+        assert!(twisted.middle && !twisted.is_solved());
+    }
+
+    #[test]
+    pub fn two_phase() {
+        let tables = generate_tables();
+
+        let scramble = Square1Metric::parse("(1, 0)/ (-3, 3) / (0,-3)/(5, -2) /").unwrap();
+        assert_eq!(
+            Square1Metric::format_seq(scramble.iter().copied()),
+            "(1, 0) / (-3, 3) / (0, -3) / (5, -2) /"
+        );
+        let error = |str: &str| Square1Metric::parse(str).unwrap_err().reason;
+        assert_eq!(error("(7, 0)"), Reason::BadAmount);
+        assert_eq!(error("(1 0)"), Reason::MissingSeparator);
+        assert_eq!(error("/ (1, 0"), Reason::UnbalancedBracket);
+
+        assert_eq!(error("(1, 0) x"), Reason::UnknownFace);
+
+        // Turns merge and cancel, and so do twists
+        let simplified = Square1Metric::simplify(
+            &Square1Metric::parse("(1, 0) (-1, 0) / / (3, 3) (3, 3) /").unwrap(),
+        );
+        assert_eq!(
+            Square1Metric::format_seq(simplified.into_iter()),
+            "(6, 6) /"
+        );
+        assert!(Square1::default().solve(&tables).is_empty());
+
+        let puzzle = Square1::default().apply_seq(scramble).unwrap();
+        assert_ne!(puzzle.shape(), Shape::CUBE);
+        let solution = puzzle.solve(&tables);
+        assert!(puzzle.apply_seq(solution).unwrap().is_solved());
+
+        for _ in 0..3 {
+            let puzzle = Square1::random_state(&tables.0);
+            assert!(puzzle.can_twist());
+            let solution = puzzle.solve(&tables);
+            assert!(puzzle.apply_seq(solution).unwrap().is_solved());
+        }
+
+        let scramble = Square1::random_scramble(&tables);
+        let puzzle = Square1::default().apply_seq(scramble).unwrap();
+        assert!(puzzle.can_twist());
+        assert!(tables.0.reachable(puzzle.shape()));
+    }
+}
@@ -33,6 +33,19 @@ pub const fn factorial(x: usize) -> usize {
     t
 }
 
+/// The factorial of `x`, for those too large for `factorial`. Fits up to 34!.
+pub const fn wide_factorial(x: usize) -> u128 {
+    let mut t = 1;
+
+    let mut x = x as u128;
+    while x > 1 {
+        t *= x;
+        x -= 1;
+    }
+
+    t
+}
+
 pub const fn power(base: u8, exponent: usize) -> usize {
     let mut t = base as usize;
     let mut y = exponent;
@@ -43,6 +56,17 @@ pub const fn power(base: u8, exponent: usize) -> usize {
     t
 }
 
+/// The power of `base`, for those too large for `power`.
+pub const fn wide_power(base: u8, exponent: usize) -> u128 {
+    let mut t = 1;
+    let mut y = exponent;
+    while y > 0 {
+        t *= base as u128;
+        y -= 1;
+    }
+    t
+}
+
 pub const fn count<const N: usize>() -> [usize; N] {
     let mut xs = [0; N];
 
@@ -73,6 +97,11 @@ mod tests {
         assert_eq!(1, factorial(0));
         assert_eq!(1, factorial(1));
         assert_eq!(40320, factorial(8));
+        assert_eq!(factorial(20) as u128, wide_factorial(20));
+        assert_eq!(
+            265_252_859_812_191_058_636_308_480_000_000,
+            wide_factorial(30)
+        );
     }
 
     #[test]
@@ -80,5 +109,8 @@ mod tests {
         assert_eq!(65536, power(2, 16));
         assert_eq!(8, power(2, 3));
         assert_eq!(2187, power(3, 7));
+        assert_eq!(power(3, 19) as u128, wide_power(3, 19));
+        assert_eq!(1, wide_power(2, 0));
+        assert_eq!(536_870_912, wide_power(2, 29));
     }
 }